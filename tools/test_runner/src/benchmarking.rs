@@ -3,12 +3,16 @@ use std::process::Stdio;
 /// Benchmark options
 pub struct BenchmarkOptions {
     sample_count: usize,
+    args: Vec<String>,
 }
 
 /// Default options
 impl Default for BenchmarkOptions {
     fn default() -> Self {
-        Self { sample_count: 200 }
+        Self {
+            sample_count: 200,
+            args: Vec::new(),
+        }
     }
 }
 
@@ -18,6 +22,11 @@ impl BenchmarkOptions {
         self.sample_count = count;
         self
     }
+
+    pub fn args(mut self, args: &[&str]) -> Self {
+        self.args = args.iter().map(|x| x.to_string()).collect();
+        self
+    }
 }
 
 /// Running benchmark with given compiler, target file and options.
@@ -27,7 +36,10 @@ pub fn run_benchmark_on(compiler_path: &str, filepath: &str, options: &Benchmark
     for _ in 0..options.sample_count {
         // Create command instance and give it a file path
         let mut command = std::process::Command::new(compiler_path);
-        let command = command.arg(filepath).stdout(Stdio::null());
+        let command = command
+            .args(&options.args)
+            .arg(filepath)
+            .stdout(Stdio::null());
 
         // Current time
         let start_time = std::time::Instant::now();
@@ -44,7 +56,33 @@ pub fn run_benchmark_on(compiler_path: &str, filepath: &str, options: &Benchmark
 
     // 1 ms = 1_000_000 ns.
     println!(
-        "[BENCH] {filepath}: {:.2} ms",
+        "[BENCH] {filepath} {}: {:.2} ms",
+        options.args.join(" "),
         (mean_time as f64) / 1_000_000.0
     );
 }
+
+/// Running file once with `--gc-stats` and printing
+/// collections amount and pause times of gc.
+pub fn print_gc_pauses(compiler_path: &str, filepath: &str, args: &[&str]) {
+    let output = std::process::Command::new(compiler_path)
+        .args(args)
+        .arg("--gc-stats")
+        .arg(filepath)
+        .output()
+        .unwrap();
+
+    // Picking gc stats lines: "<value>  <name>"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stats: Vec<String> = stdout
+        .lines()
+        .skip_while(|line| *line != "gc stats:")
+        .filter(|line| line.contains("collections") || line.contains("pause"))
+        .map(|line| {
+            let (value, name) = line.trim().split_once("  ").unwrap_or((line, ""));
+            format!("{name}: {value}")
+        })
+        .collect();
+
+    println!("[GC] {filepath} {}: {}", args.join(" "), stats.join(", "));
+}
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Line hits by source file
+/// Key: source file path
+/// Value: hits by line
+pub type CoverageMap = BTreeMap<String, BTreeMap<u64, u64>>;
+
+/// Merges an lcov report into the coverage map, summing line hits.
+pub fn merge_lcov(map: &mut CoverageMap, report: &str) {
+    let mut current: Option<String> = None;
+
+    for line in report.lines() {
+        if let Some(file) = line.strip_prefix("SF:") {
+            map.entry(file.to_string()).or_default();
+            current = Some(file.to_string());
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let Some(file) = &current else {
+                continue;
+            };
+
+            // DA:<line>,<hits>
+            let mut parts = data.split(',');
+            let line_number = parts.next().and_then(|x| x.parse::<u64>().ok());
+            let hits = parts.next().and_then(|x| x.parse::<u64>().ok());
+
+            if let (Some(line_number), Some(hits)) = (line_number, hits) {
+                *map.get_mut(file).unwrap().entry(line_number).or_insert(0) += hits;
+            }
+        } else if line == "end_of_record" {
+            current = None;
+        }
+    }
+}
+
+/// Writes the coverage map as an lcov report.
+pub fn write_lcov(map: &CoverageMap, path: &Path) -> std::io::Result<()> {
+    let mut report = String::from("TN:\n");
+
+    for (file, lines) in map {
+        report += &format!("SF:{file}\n");
+        for (line, hits) in lines {
+            report += &format!("DA:{line},{hits}\n");
+        }
+        report += &format!("LF:{}\n", lines.len());
+        report += &format!("LH:{}\n", lines.values().filter(|x| **x > 0).count());
+        report += "end_of_record\n";
+    }
+
+    std::fs::write(path, report)
+}
+
+/// Prints covered lines percent for every file.
+pub fn print_summary(map: &CoverageMap) {
+    let mut total_found = 0;
+    let mut total_hit = 0;
+
+    for (file, lines) in map {
+        let found = lines.len();
+        let hit = lines.values().filter(|x| **x > 0).count();
+
+        total_found += found;
+        total_hit += hit;
+
+        println!("{:>6.1}% {hit}/{found} {file}", percent(hit, found));
+    }
+
+    println!(
+        "coverage: {:.1}% {total_hit}/{total_found} lines",
+        percent(total_hit, total_found)
+    );
+}
+
+fn percent(hit: usize, found: usize) -> f64 {
+    if found == 0 {
+        100.0
+    } else {
+        hit as f64 * 100.0 / found as f64
+    }
+}
@@ -1,9 +1,13 @@
 use crate::benchmarking::BenchmarkOptions;
 
 pub mod benchmarking;
+pub mod coverage;
 pub mod testing;
 
-const AVAILABLE_MODES: &[&str] = &["test", "bench"];
+const AVAILABLE_MODES: &[&str] = &["test", "bench", "gc-bench", "coverage"];
+
+/// Garbage collector modes, compared by gc-bench.
+const GC_MODES: &[&str] = &["--gc-mode=mark-sweep", "--gc-mode=generational"];
 
 fn main() {
     let mut arguments = std::env::args();
@@ -63,7 +67,7 @@ fn main() {
             println!("----- Running tests -----");
 
             // Run tests.
-            let stats = testing::run_tests(&compiler_path, &working_directory, &tests_table);
+            let stats = testing::run_tests(&compiler_path, &working_directory, &tests_table, None);
 
             println!(
                 "test results: {} ran: {color_green}{}{color_end} ok, {color_red}{}{color_end} fail",
@@ -79,6 +83,58 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        "coverage" => {
+            let tests_path = working_directory.clone() + "/tests";
+            let coverage_path = working_directory.clone() + "/watt/target/coverage";
+            let reports_path = coverage_path.clone() + "/tests";
+
+            // Recreate the per-test reports directory.
+            let _ = std::fs::remove_dir_all(&reports_path);
+            if let Err(e) = std::fs::create_dir_all(&reports_path) {
+                eprintln!("Failed to create coverage directory. ({e:?})");
+                std::process::exit(1);
+            }
+
+            let tests_table = match testing::build_verification_table(&tests_path) {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("Failed to build tests table. ({e:?})");
+                    std::process::exit(1);
+                }
+            };
+
+            println!("----- Running tests with coverage -----");
+
+            let stats = testing::run_tests(
+                &compiler_path,
+                &working_directory,
+                &tests_table,
+                Some(&reports_path),
+            );
+
+            // Merge the per-test reports.
+            let mut map = coverage::CoverageMap::new();
+            for entry in std::fs::read_dir(&reports_path).unwrap() {
+                let path = entry.unwrap().path();
+                if let Ok(report) = std::fs::read_to_string(&path) {
+                    coverage::merge_lcov(&mut map, &report);
+                }
+            }
+
+            let output = std::path::PathBuf::from(coverage_path + "/lcov.info");
+            if let Err(e) = coverage::write_lcov(&map, &output) {
+                eprintln!("Failed to write coverage report. ({e:?})");
+                std::process::exit(1);
+            }
+
+            println!("----- Coverage -----");
+            coverage::print_summary(&map);
+            println!("lcov report: {}", output.display());
+
+            if stats.fail != 0 {
+                std::process::exit(1);
+            }
+        }
         "bench" => {
             let file = match arguments.next() {
                 Some(file) => file,
@@ -92,6 +148,38 @@ fn main() {
 
             benchmarking::run_benchmark_on(&compiler_path, &file, &BenchmarkOptions::default());
         }
+        "gc-bench" => {
+            // Benchmarks of given file, or all gc benchmarks.
+            let files: Vec<String> = match arguments.next() {
+                Some(file) => vec![file],
+                None => {
+                    let mut files: Vec<String> =
+                        std::fs::read_dir(working_directory.clone() + "/benches")
+                            .unwrap()
+                            .map(|entry| entry.unwrap().path())
+                            .filter(|path| {
+                                path.extension().is_some_and(|x| x == "wt")
+                                    && path
+                                        .file_name()
+                                        .is_some_and(|x| x.to_string_lossy().starts_with("gc_"))
+                            })
+                            .map(|path| path.to_string_lossy().to_string())
+                            .collect();
+                    files.sort();
+                    files
+                }
+            };
+
+            println!("----- Running gc benchmarks -----");
+
+            for file in &files {
+                for mode in GC_MODES {
+                    let options = BenchmarkOptions::default().sample_count(20).args(&[mode]);
+                    benchmarking::run_benchmark_on(&compiler_path, file, &options);
+                    benchmarking::print_gc_pauses(&compiler_path, file, &[mode]);
+                }
+            }
+        }
         _ => {
             eprintln!("Invalid mode: {mode:?}! Run: `{program_name} help`");
             std::process::exit(1);
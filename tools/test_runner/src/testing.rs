@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 /// Данный тип описывает таблицу тестов, которые могут или не могут иметь данные для сравнения
 /// Ключ: путь к тесту
@@ -54,6 +57,58 @@ pub fn build_verification_table(on: &str) -> std::io::Result<TestMap> {
     Ok(table)
 }
 
+/// Читает значение заголовка теста из его начальных комментариев,
+/// например `// flags: --allow-net=127.0.0.1` или `// exit: 3`
+fn test_header(test_file: &str, name: &str) -> Option<String> {
+    let content = std::fs::read_to_string(test_file).unwrap_or_default();
+    let prefix = format!("// {name}:");
+
+    content
+        .lines()
+        .take_while(|line| line.starts_with("//"))
+        .find_map(|line| line.strip_prefix(&prefix))
+        .map(|value| value.trim().to_string())
+}
+
+/// Читает флаги запуска теста
+fn test_flags(test_file: &str) -> Vec<String> {
+    test_header(test_file, "flags")
+        .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Читает ожидаемый код выхода теста, по умолчанию `0`
+fn test_exit_code(test_file: &str) -> i32 {
+    test_header(test_file, "exit")
+        .and_then(|code| code.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Путь к ожидаемому stderr теста, если он есть
+fn test_stderr(test_file: &str) -> Option<PathBuf> {
+    let test_path = Path::new(test_file);
+    let stderr_path = test_path
+        .parent()?
+        .join("output")
+        .join(test_path.file_name()?.to_str()?.to_string() + ".stderr");
+
+    stderr_path.exists().then_some(stderr_path)
+}
+
+#[inline]
+fn report_mismatch(short_filename: &str, stream: &str, expected: &[u8], got: &[u8]) {
+    report_fail(short_filename);
+    println!("Expected {stream}:\n---");
+
+    print!("{}", String::from_utf8_lossy(expected));
+    println!("\n---");
+
+    println!("Got {stream}:\n---");
+
+    print!("{}", String::from_utf8_lossy(got));
+    println!("\n---");
+}
+
 #[inline]
 fn report_ok(short_filename: &str) {
     println!(
@@ -82,10 +137,12 @@ fn report_ok_no_output(short_filename: &str) {
 }
 
 /// Запускает все тесты из таблицы
+/// Если указан `coverage_dir`, то каждый тест пишет туда свой lcov отчёт
 pub fn run_tests(
     watt_path: &str,
     working_directory: &str,
     tests_table: &HashMap<String, Option<String>>,
+    coverage_dir: Option<&str>,
 ) -> TesterResults {
     let mut stats = TesterResults::default();
 
@@ -98,6 +155,13 @@ pub fn run_tests(
 
         // Создаём команду для запуска Watt
         let mut command = std::process::Command::new(watt_path);
+        // Добавляем аргумент отчёта покрытия
+        if let Some(dir) = coverage_dir {
+            let stem = Path::new(test_file).file_stem().unwrap().to_str().unwrap();
+            command.arg(format!("--coverage={dir}/{stem}.lcov"));
+        }
+        // Добавляем флаги теста
+        command.args(test_flags(test_file));
         // Добавляем аргумент пути файла для запуска
         let command = command.arg(test_file);
 
@@ -106,9 +170,14 @@ pub fn run_tests(
         match command.output() {
             // Если программа была запущена...
             Ok(data) => {
-                // ...неуспешно, то это провал
-                if !data.status.success() {
+                // ...с неожиданным кодом выхода, то это провал
+                let expected_code = test_exit_code(test_file);
+                if data.status.code() != Some(expected_code) {
                     report_fail(short_filename);
+                    println!(
+                        "Expected exit code {expected_code}, got {:?}",
+                        data.status.code()
+                    );
                     println!("{}", str::from_utf8(&data.stdout).unwrap());
 
                     stats.fail += 1;
@@ -117,6 +186,20 @@ pub fn run_tests(
                     continue;
                 }
 
+                // Если есть ожидаемый stderr, то сравниваем и его
+                if let Some(stderr_file) = test_stderr(test_file) {
+                    let verify_data = std::fs::read(stderr_file).unwrap();
+
+                    if verify_data != data.stderr {
+                        report_mismatch(short_filename, "stderr", &verify_data, &data.stderr);
+
+                        stats.fail += 1;
+                        stats.ran += 1;
+
+                        continue;
+                    }
+                }
+
                 // Если нет ожидаемых данных, то это хорошо, поскольку программа была обработана нормально
                 if expected_content_file.is_none() {
                     report_ok_no_output(short_filename);
@@ -133,16 +216,7 @@ pub fn run_tests(
                         stats.ok += 1;
                     } else {
                         // Ну а если нет, то печатаем отчет об ощибке.
-                        report_fail(short_filename);
-                        println!("Expected:\n---");
-
-                        print!("{}", str::from_utf8(&verify_data).unwrap());
-                        println!("\n---");
-
-                        println!("Got:\n---");
-
-                        print!("{}", str::from_utf8(&data.stdout).unwrap());
-                        println!("\n---");
+                        report_mismatch(short_filename, "stdout", &verify_data, &data.stdout);
 
                         stats.fail += 1;
                     }
@@ -6,17 +6,37 @@
 
 // imports
 use std::{fs, path::PathBuf};
-use watt_analyze::analyzer::Analyzer;
-use watt_ast::ast::Node;
+use watt_analyze::{analyzer::Analyzer, checker::Checker};
+use watt_ast::{ast::Node, import::Import};
 use watt_common::{address::Address, error, errors::Error};
-use watt_gen::visitor::CompileVisitor;
+use watt_gen::{resolver::ImportsResolver, visitor::CompileVisitor};
 use watt_lex::{lexer::Lexer, tokens::Token};
 use watt_parse::parser::Parser;
 use watt_vm::{
     bytecode::Chunk,
+    coverage::Coverage,
+    profiler::Profiler,
     vm::{VM, VmSettings},
 };
 
+// re-exports
+pub use watt_gen::resolver::Modules;
+pub use watt_vm::permissions::{Grant, Permissions};
+pub use watt_vm::vm::{GcMode, GcStats, VmLimits};
+
+/// Default garbage collector threshold
+pub const GC_THRESHOLD: usize = 200;
+
+/// Default garbage collector threshold grow factor
+pub const GC_THRESHOLD_GROW_FACTOR: usize = 2;
+
+/// Default young objects amount, that
+/// triggers minor collection in generational gc
+pub const GC_NURSERY: usize = 1000;
+
+/// Default max depth of non-tail calls
+pub const MAX_CALL_DEPTH: usize = 10000;
+
 /// Reading file
 ///
 /// raises error if path is not exists,
@@ -82,7 +102,12 @@ pub fn read_file(addr: Option<Address>, path: &PathBuf) -> String {
 /// # Run args
 ///
 /// * `gc_threshold`: garbage collector threshold
+/// * `gc_mode`: garbage collector mode
+/// * `max_call_depth`: max depth of non-tail calls
+/// * `limits`: execution limits for untrusted scripts
+/// * `permissions`: capabilities of script, denied by default
 /// * `gc_debug`: on/off garbage collector debug
+/// * `gc_stats`: on/off garbage collector statistics summary
 /// * `lexer_debug`: on/off lexer debug
 /// * `ast_debug`: on/off ast debug
 /// * `opcodes_debug`: on/of opcodes debug
@@ -90,13 +115,26 @@ pub fn read_file(addr: Option<Address>, path: &PathBuf) -> String {
 /// * `parser_bench`: on/off parser benchmark
 /// * `compile_bench`: on/off compile benchmark
 /// * `runtime_bench`: on/off runtime benchmark
+/// * `profile`: path to write collapsed stacks profile
+/// * `coverage`: path to write lcov coverage report
+/// * `heap_snapshot`: path to write heap snapshot on exit
+///
+/// # Safety
+///
+/// should be called once per thread, vm
+/// is made and cleaned up by this call
 ///
 #[allow(unused_qualifications)]
 pub unsafe fn run(
     path: PathBuf,
     gc_threshold: Option<usize>,
     gc_threshold_grow_factor: Option<usize>,
+    gc_mode: GcMode,
+    max_call_depth: Option<usize>,
+    limits: VmLimits,
+    permissions: Permissions,
     gc_debug: bool,
+    gc_stats: bool,
     lexer_debug: bool,
     ast_debug: bool,
     opcodes_debug: bool,
@@ -104,6 +142,9 @@ pub unsafe fn run(
     parser_bench: bool,
     compile_bench: bool,
     runtime_bench: bool,
+    profile: Option<PathBuf>,
+    coverage: Option<PathBuf>,
+    heap_snapshot: Option<PathBuf>,
 ) {
     // reading file
     let code = read_file(Option::None, &path);
@@ -120,18 +161,27 @@ pub unsafe fn run(
     let ast = parse(&path, tokens.unwrap(), ast_debug, parser_bench, &None);
 
     // analyzing
-    let analyzed = analyze(ast);
+    let modules = Modules::default();
+    let analyzed = analyze(ast, &modules);
 
     // compiling
-    let compiled = compile(&analyzed, opcodes_debug, compile_bench);
+    let compiled = compile(&analyzed, &modules, opcodes_debug, compile_bench);
 
     // run compiled opcodes chunk with vm
     run_chunk(
         compiled,
-        gc_threshold.unwrap_or(200),
-        gc_threshold_grow_factor.unwrap_or(2),
+        gc_threshold.unwrap_or(GC_THRESHOLD),
+        gc_threshold_grow_factor.unwrap_or(GC_THRESHOLD_GROW_FACTOR),
+        gc_mode,
+        max_call_depth.unwrap_or(MAX_CALL_DEPTH),
+        limits,
+        permissions,
         gc_debug,
+        gc_stats,
         runtime_bench,
+        profile,
+        coverage,
+        heap_snapshot,
     );
 }
 
@@ -218,24 +268,44 @@ pub fn parse(
     }
 
     // returning ast
-    return ast;
+    ast
 }
 
-/// Semantic analyzer
-/// Provides analyzed node on the exhaust
-pub fn analyze(ast: Node) -> Node {
+/// Semantic analyzer and gradual type checker
+/// Provides analyzed node on the exhaust,
+/// imported modules are parsed into `modules`
+pub fn analyze(ast: Node, modules: &Modules) -> Node {
     Analyzer::new().analyze(&ast);
+
+    // checking types, imports are resolved
+    // separately from compilation, but
+    // parsed modules are reused by it
+    let mut resolver = ImportsResolver::with_modules(modules.clone());
+    let builtins = resolver.import_builtins();
+    let mut resolve = |import: &Import| resolver.import(import.addr.clone(), import);
+    let mut checker = Checker::new(&mut resolve);
+    for builtin in &builtins {
+        checker.declare(builtin);
+    }
+    checker.check(&ast);
     ast
 }
 
 /// Compilation
-/// Provides compiled chunk on the exhaust
-pub unsafe fn compile(ast: &Node, opcodes_debug: bool, bench: bool) -> Chunk {
+/// Provides compiled chunk on the exhaust,
+/// reusing modules parsed by `analyze`
+///
+/// # Safety
+///
+/// imported modules are resolved from
+/// import paths, that should be valid
+///
+pub unsafe fn compile(ast: &Node, modules: &Modules, opcodes_debug: bool, bench: bool) -> Chunk {
     // benchmark
     let start = std::time::Instant::now();
 
     // compile
-    let compiled = CompileVisitor::new().compile(ast);
+    let compiled = CompileVisitor::with_modules(modules.clone()).compile(ast);
 
     // benchmark end
     if bench {
@@ -257,16 +327,60 @@ pub unsafe fn compile(ast: &Node, opcodes_debug: bool, bench: bool) -> Chunk {
     compiled
 }
 
+/// Loads module for thread
+///
+/// module path is resolved like import path,
+/// when `definitions` is true, module is imported,
+/// otherwise it's compiled as a program
+///
+/// # Safety
+///
+/// imported modules are resolved from
+/// import paths, that should be valid
+///
+pub unsafe fn load_module(addr: &Address, module: &str, definitions: bool) -> Chunk {
+    if definitions {
+        let import = Import::new(Some(addr.clone()), module.to_string(), None);
+        CompileVisitor::new().compile_import(import)
+    } else {
+        // path is resolved like import path
+        let path = PathBuf::from(module);
+
+        // compiling
+        let code = read_file(Some(addr.clone()), &path);
+        let tokens = lex(&path, &code.chars().collect::<Vec<char>>(), false, false);
+        let ast = parse(&path, tokens.unwrap(), false, false, &None);
+        let modules = Modules::default();
+        compile(&analyze(ast, &modules), &modules, false, false)
+    }
+}
+
 /// Runs chunk on the vm
 ///
 /// * gc_threshold: garbage collector threshold
+/// * gc_mode: garbage collector mode
+/// * max_call_depth: max depth of non-tail calls
+/// * limits: execution limits for untrusted scripts
+/// * permissions: capabilities of script
+/// * gc_stats: print gc statistics summary
+/// * profile: path to write collapsed stacks profile
+/// * coverage: path to write lcov coverage report
+/// * heap_snapshot: path to write heap snapshot on exit
 #[allow(unused_qualifications)]
 unsafe fn run_chunk(
     chunk: Chunk,
     gc_threshold: usize,
     gc_threshold_grow_factor: usize,
+    gc_mode: GcMode,
+    max_call_depth: usize,
+    limits: VmLimits,
+    permissions: Permissions,
     gc_debug: bool,
+    gc_stats: bool,
     bench: bool,
+    profile: Option<PathBuf>,
+    coverage: Option<PathBuf>,
+    heap_snapshot: Option<PathBuf>,
 ) {
     // benchmark
     let start = std::time::Instant::now();
@@ -275,11 +389,35 @@ unsafe fn run_chunk(
     let mut vm = VM::new(VmSettings::new(
         gc_threshold,
         gc_threshold_grow_factor,
+        gc_mode,
         gc_debug,
+        max_call_depth,
+        limits,
+        permissions,
     ));
 
-    // handling errors
-    if let Err(e) = vm.run(&chunk, vm.globals) {
+    // threads load modules by compiling them
+    vm.loader = Some(load_module);
+
+    // profiler
+    if profile.is_some() {
+        vm.profiler = Some(Profiler::new());
+    }
+
+    // coverage
+    if coverage.is_some() {
+        let mut instrumented = Coverage::new();
+        instrumented.instrument(&chunk);
+        vm.coverage = Some(instrumented);
+    }
+
+    // handling errors, running scheduled
+    // tasks and finalizers of alive instances
+    if let Err(e) = vm
+        .run(&chunk, vm.globals)
+        .and_then(|_| vm.run_tasks(vm.globals))
+        .and_then(|_| vm.finalize_all(vm.globals))
+    {
         error!(Error::own_text(
             Address::unknown(),
             format!("control flow leak: {e:?}"),
@@ -296,6 +434,37 @@ unsafe fn run_chunk(
         );
     }
 
+    // profile
+    if let Some(path) = profile
+        && let Some(mut profiler) = vm.profiler.take()
+    {
+        profiler.finish();
+        if let Err(e) = profiler.write_folded(&path) {
+            crash(format!("failed to write profile {path:?}: {e}"));
+        }
+        profiler.print_summary(20);
+    }
+
+    // coverage report
+    if let Some(path) = coverage
+        && let Some(coverage) = vm.coverage.take()
+        && let Err(e) = coverage.write_lcov(&path)
+    {
+        crash(format!("failed to write coverage {path:?}: {e}"));
+    }
+
+    // heap snapshot
+    if let Some(path) = heap_snapshot
+        && let Err(e) = vm.write_heap_snapshot(vm.globals, &path)
+    {
+        crash(format!("failed to write heap snapshot {path:?}: {e}"));
+    }
+
+    // gc stats
+    if gc_stats {
+        vm.gc_stats().print_summary();
+    }
+
     // cleanup
     vm.cleanup();
 }
@@ -1,6 +1,6 @@
 // imports
 use std::collections::VecDeque;
-use watt_ast::ast::{MatchCase, Node};
+use watt_ast::ast::{FnKind, MatchCase, Node, Pattern};
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
@@ -17,6 +17,7 @@ pub enum AnalyzerNode {
     Loop,
     For,
     Fn,
+    Generator,
 }
 
 /// Semantic analyzer
@@ -33,7 +34,7 @@ impl Analyzer {
     }
 
     /// Analyzes node
-    pub fn analyze<'node>(&mut self, node: &'node Node) {
+    pub fn analyze(&mut self, node: &Node) {
         match node {
             Node::Block { body } => {
                 for node in body {
@@ -54,8 +55,11 @@ impl Analyzer {
             Node::For { iterable, body, .. } => {
                 self.analyze_for(body, iterable);
             }
-            Node::FnDeclaration { body, .. } => {
-                self.analyze_fn(body);
+            Node::With { value, body, .. } => {
+                self.analyze_with(body, value);
+            }
+            Node::FnDeclaration { body, kind, .. } => {
+                self.analyze_fn(body, *kind);
             }
             Node::Break { location } => {
                 self.analyze_break(&location.address);
@@ -74,11 +78,25 @@ impl Analyzer {
                     self.analyze(v);
                 }
             }
-            Node::Match { cases, default, .. } => {
+            Node::Match {
+                matchable,
+                cases,
+                default,
+                ..
+            } => {
+                self.analyze(matchable);
                 self.analyze_match(cases, default);
             }
-            Node::Ret { location, .. } => {
+            Node::Ret { location, value } => {
                 self.analyze_return(&location.address);
+                self.analyze(value);
+            }
+            Node::Yield { location, value } => {
+                self.analyze_yield(&location.address);
+                self.analyze(value);
+            }
+            Node::Await { value, .. } => {
+                self.analyze(value);
             }
             Node::Type { body, .. } => {
                 self.analyze(body);
@@ -113,8 +131,8 @@ impl Analyzer {
             Node::Assign { value, .. } => {
                 self.analyze(value);
             }
-            Node::AnFnDeclaration { body, .. } => {
-                self.analyze_fn(body);
+            Node::AnFnDeclaration { body, kind, .. } => {
+                self.analyze_fn(body, *kind);
             }
             Node::Cond { left, right, .. } => {
                 self.analyze(left);
@@ -128,9 +146,19 @@ impl Analyzer {
                 self.analyze(from);
                 self.analyze(to);
             }
+            Node::Index { value, key, .. } => {
+                self.analyze(value);
+                self.analyze(key);
+            }
             Node::Impls { value, .. } => {
                 self.analyze(value);
             }
+            Node::NamedArg { value, .. } => {
+                self.analyze(value);
+            }
+            Node::Destructure { value, .. } => {
+                self.analyze(value);
+            }
             _ => {}
         }
     }
@@ -150,13 +178,26 @@ impl Analyzer {
     /// * hierarchy is analyze_stack
     fn hierarchy_has_fn(&self) -> bool {
         for node in self.analyze_stack.clone() {
-            if let AnalyzerNode::Fn = node {
+            if let AnalyzerNode::Fn | AnalyzerNode::Generator = node {
                 return true;
             }
         }
         false
     }
 
+    /// Checks if nearest fn in analyze_stack is generator
+    /// * hierarchy is analyze_stack
+    fn hierarchy_in_generator(&self) -> bool {
+        for node in self.analyze_stack.iter().rev() {
+            match node {
+                AnalyzerNode::Generator => return true,
+                AnalyzerNode::Fn => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
     /// Analyzes if
     pub fn analyze_if(&mut self, body: &Node, logical: &Node, elseif: &Option<Box<Node>>) {
         // push if node to analyzer stack and analyze if
@@ -172,11 +213,61 @@ impl Analyzer {
 
     /// Analyzes match
     pub fn analyze_match(&mut self, cases: &Vec<MatchCase>, default: &Node) {
-        // qnalyzing cases
+        // analyzing cases
         self.analyze(default);
         for case in cases {
+            if let Some(guard) = &case.guard {
+                self.analyze(guard);
+            }
             self.analyze(&case.body);
         }
+        // checking reachability
+        let mut literals: Vec<(String, String)> = vec![];
+        let mut exhausted = false;
+        for case in cases {
+            // case after irrefutable case
+            if exhausted {
+                Error::new(
+                    case.location.address.clone(),
+                    "unreachable case.",
+                    "previous case matches every value.",
+                )
+                .warn();
+                continue;
+            }
+            // same literal case
+            if case.guard.is_none()
+                && let Some(literal) = Self::pattern_literal(&case.pattern)
+            {
+                if literals.contains(&literal) {
+                    Error::new(
+                        case.location.address.clone(),
+                        "unreachable case.",
+                        "same value is already matched by previous case.",
+                    )
+                    .warn();
+                } else {
+                    literals.push(literal);
+                }
+            }
+            if case.guard.is_none() && case.pattern.is_irrefutable() {
+                exhausted = true;
+            }
+        }
+    }
+
+    /// Literal of value pattern, as kind and value
+    fn pattern_literal(pattern: &Pattern) -> Option<(String, String)> {
+        match pattern {
+            Pattern::Value { value } => match value.as_ref() {
+                Node::Number { value } => Some(("number".to_string(), value.value.clone())),
+                Node::String { value } => Some(("string".to_string(), value.value.clone())),
+                Node::Bool { value } => Some(("bool".to_string(), value.value.clone())),
+                Node::Null { .. } => Some(("null".to_string(), String::new())),
+                _ => None,
+            },
+            _ => None,
+        }
     }
 
     /// Analyzing loop while
@@ -195,6 +286,14 @@ impl Analyzer {
         self.analyze_stack.pop_back();
     }
 
+    /// Analyzing with
+    pub fn analyze_with(&mut self, body: &Node, value: &Node) {
+        self.analyze_stack.push_back(AnalyzerNode::Block);
+        self.analyze(value);
+        self.analyze(body);
+        self.analyze_stack.pop_back();
+    }
+
     /// Analyzing continue
     ///
     /// Checking has_loop_in_hierarchy
@@ -240,8 +339,11 @@ impl Analyzer {
     }
 
     /// Analyzing fn declaration
-    fn analyze_fn(&mut self, body: &Node) {
-        self.analyze_stack.push_back(AnalyzerNode::Fn);
+    fn analyze_fn(&mut self, body: &Node, kind: FnKind) {
+        match kind {
+            FnKind::Generator => self.analyze_stack.push_back(AnalyzerNode::Generator),
+            FnKind::Plain | FnKind::Async => self.analyze_stack.push_back(AnalyzerNode::Fn),
+        }
         self.analyze(body);
         self.analyze_stack.pop_back();
     }
@@ -268,6 +370,21 @@ impl Analyzer {
         }
     }
 
+    /// Analyzing yield
+    ///
+    /// Checking hierarchy_in_generator
+    /// raises error, if nearest fn is not a generator
+    ///
+    fn analyze_yield(&self, addr: &Address) {
+        if !self.hierarchy_in_generator() {
+            error!(Error::new(
+                addr.clone(),
+                "couldn't use yield outside generator.",
+                "you can use it only inside fn* generators."
+            ))
+        }
+    }
+
     /// Analyzing import
     ///
     /// Checks if analyze stack is empty, because
@@ -301,3 +418,9 @@ impl Analyzer {
         }
     }
 }
+/// Semantic analyzer default implementation
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,1419 @@
+// imports
+use crate::natives;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use watt_ast::ast::*;
+use watt_ast::import::Import;
+use watt_common::address::Address;
+use watt_common::{error, errors::Error};
+
+/// Inferred type
+///
+/// `Dynamic` is unknown type, it's compatible
+/// with any hint, `Number` is int or float
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ty {
+    Dynamic,
+    Int,
+    Float,
+    Number,
+    String,
+    Bool,
+    Null,
+    Fn,
+    Instance(String),
+}
+/// Inferred type display implementation
+impl Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ty::Dynamic => write!(f, "any"),
+            Ty::Int => write!(f, "int"),
+            Ty::Float => write!(f, "float"),
+            Ty::Number => write!(f, "number"),
+            Ty::String => write!(f, "string"),
+            Ty::Bool => write!(f, "bool"),
+            Ty::Null => write!(f, "null"),
+            Ty::Fn => write!(f, "fn"),
+            Ty::Instance(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// Type hint of signature,
+/// taken from ast or native signature
+#[derive(Debug, Clone)]
+struct Hint {
+    addr: Address,
+    name: String,
+    nullable: bool,
+}
+/// Hint implementation
+impl Hint {
+    /// Hint of ast type hint
+    fn of(hint: &TypeHint) -> Hint {
+        Hint {
+            addr: hint.name.address.clone(),
+            name: hint.name.value.clone(),
+            nullable: hint.nullable,
+        }
+    }
+
+    /// Hint of native signature
+    fn named(name: &str) -> Hint {
+        Hint {
+            addr: Address::unknown(),
+            name: name.to_string(),
+            nullable: false,
+        }
+    }
+}
+/// Hint display implementation
+impl Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.nullable {
+            write!(f, "{}?", self.name)
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+/// Fn signature param
+#[derive(Debug, Clone)]
+struct SignatureParam {
+    name: String,
+    hint: Option<Hint>,
+    variadic: bool,
+}
+
+/// Fn signature
+#[derive(Debug, Clone)]
+struct Signature {
+    name: String,
+    params: Vec<SignatureParam>,
+    returns: Option<Hint>,
+    kind: FnKind,
+}
+/// Signature implementation
+impl Signature {
+    /// Signature of declared fn
+    fn of(name: &str, params: &[Parameter], returns: &Option<TypeHint>, kind: FnKind) -> Self {
+        Signature {
+            name: name.to_string(),
+            params: params
+                .iter()
+                .map(|param| SignatureParam {
+                    name: param.name.value.clone(),
+                    hint: param.hint.as_ref().map(Hint::of),
+                    variadic: param.variadic,
+                })
+                .collect(),
+            returns: returns.as_ref().map(Hint::of),
+            kind,
+        }
+    }
+
+    /// Signature of stdlib native, if it's known
+    fn of_native(name: &str, fn_name: &str) -> Option<Self> {
+        let native = natives::signature(fn_name)?;
+        Some(Signature {
+            name: name.to_string(),
+            params: native
+                .params
+                .iter()
+                .enumerate()
+                .map(|(i, hint)| SignatureParam {
+                    name: format!("#{}", i + 1),
+                    hint: Some(Hint::named(hint)),
+                    variadic: false,
+                })
+                .collect(),
+            returns: native.returns.map(Hint::named),
+            kind: FnKind::Plain,
+        })
+    }
+}
+
+/// Declared type
+#[derive(Debug, Clone)]
+struct TypeInfo {
+    fields: Vec<(String, Option<Hint>)>,
+    methods: HashMap<String, Signature>,
+    impls: Vec<String>,
+}
+
+/// Scope binding
+#[derive(Debug, Clone)]
+enum Binding {
+    Var(Ty),
+    Fn(Signature),
+}
+
+/// Scope kind
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScopeKind {
+    Program,
+    Fn,
+    Type,
+    Unit,
+}
+
+/// Checker scope
+///
+/// * `bindings`: variables and fn-s of scope
+/// * `hints`: hints of params and fields with
+///   their description, like `param a of add`
+/// * `dynamic`: names, that are reassigned or
+///   defined twice, so their type is unknown
+/// * `returns`: fn name and it's return hint
+///
+struct Scope {
+    kind: ScopeKind,
+    bindings: HashMap<String, Binding>,
+    hints: HashMap<String, (String, Hint)>,
+    dynamic: HashSet<String>,
+    returns: Option<(String, Hint)>,
+}
+/// Scope implementation
+impl Scope {
+    /// New scope
+    fn new(kind: ScopeKind, dynamic: HashSet<String>) -> Self {
+        Scope {
+            kind,
+            bindings: HashMap::new(),
+            hints: HashMap::new(),
+            dynamic,
+            returns: None,
+        }
+    }
+}
+
+/// Gradual type checker
+///
+/// infers types of locals, checks calls of annotated
+/// fn-s, types and stdlib natives, returns and hinted
+/// fields assignments. unknown types are dynamic,
+/// so only definite mismatches are reported
+///
+pub struct Checker<'resolver> {
+    resolver: &'resolver mut dyn FnMut(&Import) -> Option<Node>,
+    scopes: Vec<Scope>,
+    types: HashMap<String, TypeInfo>,
+    units: HashMap<String, HashMap<String, Signature>>,
+    traits: HashMap<String, Vec<String>>,
+    enums: HashSet<String>,
+    variants: HashMap<String, HashSet<(String, String)>>,
+}
+/// Checker implementation
+impl<'resolver> Checker<'resolver> {
+    /// New checker, `resolver` resolves
+    /// imports to their declarations
+    pub fn new(resolver: &'resolver mut dyn FnMut(&Import) -> Option<Node>) -> Self {
+        Checker {
+            resolver,
+            scopes: vec![Scope::new(ScopeKind::Program, HashSet::new())],
+            types: HashMap::new(),
+            units: HashMap::new(),
+            traits: HashMap::new(),
+            enums: HashSet::new(),
+            variants: HashMap::new(),
+        }
+    }
+
+    /// Declares module declarations, such as
+    /// fn-s, types, units and traits
+    pub fn declare(&mut self, module: &Node) {
+        if let Node::Block { body } = module {
+            for node in body {
+                match node {
+                    Node::Import { imports, .. } => {
+                        for import in imports {
+                            if let Some(module) = (self.resolver)(import) {
+                                self.declare(&module);
+                            }
+                        }
+                    }
+                    Node::FnDeclaration {
+                        name,
+                        params,
+                        returns,
+                        kind,
+                        ..
+                    } => {
+                        let signature = Signature::of(&name.value, params, returns, *kind);
+                        self.scopes[0]
+                            .bindings
+                            .insert(name.value.clone(), Binding::Fn(signature));
+                    }
+                    Node::Native { name, fn_name } => {
+                        if let Some(signature) = Signature::of_native(&name.value, &fn_name.value) {
+                            self.scopes[0]
+                                .bindings
+                                .insert(name.value.clone(), Binding::Fn(signature));
+                        }
+                    }
+                    _ => self.declare_nested(node),
+                }
+            }
+        }
+    }
+
+    /// Checks program
+    pub fn check(&mut self, ast: &Node) {
+        // resolving imports and declarations
+        self.declare_imports(ast);
+        self.declare_nested(ast);
+
+        // names of program, that are dynamic
+        let dynamic = Self::dynamic_names(ast, &[]);
+        for name in &dynamic {
+            self.scopes[0]
+                .bindings
+                .insert(name.clone(), Binding::Var(Ty::Dynamic));
+        }
+        self.scopes[0].dynamic = dynamic;
+
+        // checking
+        self.check_node(ast);
+    }
+
+    /// Declares imports of program
+    fn declare_imports(&mut self, ast: &Node) {
+        if let Node::Block { body } = ast {
+            for node in body {
+                if let Node::Import { imports, .. } = node {
+                    for import in imports {
+                        if let Some(module) = (self.resolver)(import) {
+                            self.declare(&module);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Declares types, units, traits and enums,
+    /// declared anywhere in node
+    fn declare_nested(&mut self, node: &Node) {
+        match node {
+            Node::Type {
+                name,
+                full_name,
+                constructor,
+                body,
+                impls,
+            } => {
+                // methods
+                let mut methods = HashMap::new();
+                if let Node::Block { body } = body.as_ref() {
+                    for node in body {
+                        if let Node::FnDeclaration {
+                            name,
+                            params,
+                            returns,
+                            kind,
+                            ..
+                        } = node
+                        {
+                            methods.insert(
+                                name.value.clone(),
+                                Signature::of(&name.value, params, returns, *kind),
+                            );
+                        }
+                    }
+                }
+                let info = TypeInfo {
+                    fields: constructor
+                        .iter()
+                        .map(|field| (field.name.value.clone(), field.hint.as_ref().map(Hint::of)))
+                        .collect(),
+                    methods,
+                    impls: impls.iter().map(|name| name.value.clone()).collect(),
+                };
+                // by name and full name
+                if let Some(full_name) = full_name {
+                    self.types.insert(full_name.value.clone(), info.clone());
+                }
+                self.types.insert(name.value.clone(), info);
+            }
+            Node::Unit {
+                name,
+                full_name,
+                body,
+            } => {
+                // fn-s and natives
+                let mut functions = HashMap::new();
+                if let Node::Block { body } = body.as_ref() {
+                    for node in body {
+                        match node {
+                            Node::FnDeclaration {
+                                name,
+                                params,
+                                returns,
+                                kind,
+                                ..
+                            } => {
+                                functions.insert(
+                                    name.value.clone(),
+                                    Signature::of(&name.value, params, returns, *kind),
+                                );
+                            }
+                            Node::Native { name, fn_name } => {
+                                if let Some(signature) =
+                                    Signature::of_native(&name.value, &fn_name.value)
+                                {
+                                    functions.insert(name.value.clone(), signature);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                // by name and full name
+                if let Some(full_name) = full_name {
+                    self.units
+                        .insert(full_name.value.clone(), functions.clone());
+                }
+                self.units.insert(name.value.clone(), functions);
+            }
+            Node::Trait {
+                name,
+                full_name,
+                parents,
+                ..
+            } => {
+                let parents: Vec<String> = parents.iter().map(|p| p.value.clone()).collect();
+                if let Some(full_name) = full_name {
+                    self.traits.insert(full_name.value.clone(), parents.clone());
+                }
+                self.traits.insert(name.value.clone(), parents);
+            }
+            Node::Enum {
+                name,
+                full_name,
+                variants,
+            } => {
+                if let Some(full_name) = full_name {
+                    self.enums.insert(full_name.value.clone());
+                }
+                self.enums.insert(name.value.clone());
+                // enums by variant name, as full name and name
+                let e = (
+                    full_name.as_ref().unwrap_or(name).value.clone(),
+                    name.value.clone(),
+                );
+                for variant in variants {
+                    self.variants
+                        .entry(variant.name.value.clone())
+                        .or_default()
+                        .insert(e.clone());
+                }
+            }
+            _ => {}
+        }
+        for child in children(node) {
+            self.declare_nested(child);
+        }
+    }
+
+    /// Names of scope, that are reassigned, defined
+    /// twice or bound by loops and patterns
+    ///
+    /// `predefined` are params or fields, that
+    /// are already defined in scope
+    ///
+    fn dynamic_names(body: &Node, predefined: &[String]) -> HashSet<String> {
+        /// Scans node, defines of nested fn-s
+        /// and types are not counted
+        fn scan(
+            node: &Node,
+            nested: bool,
+            defines: &mut HashMap<String, usize>,
+            dynamic: &mut HashSet<String>,
+        ) {
+            match node {
+                Node::Define {
+                    previous: None,
+                    name,
+                    ..
+                } if !nested => {
+                    *defines.entry(name.value.clone()).or_insert(0) += 1;
+                }
+                Node::FnDeclaration { name, .. } if !nested => {
+                    *defines.entry(name.value.clone()).or_insert(0) += 1;
+                }
+                Node::Assign {
+                    previous: None,
+                    name,
+                    ..
+                } => {
+                    dynamic.insert(name.value.clone());
+                }
+                Node::Destructure { names, .. } => {
+                    for name in names {
+                        dynamic.insert(name.value.clone());
+                    }
+                }
+                Node::For { variable_name, .. } | Node::With { variable_name, .. } => {
+                    dynamic.insert(variable_name.value.clone());
+                }
+                Node::Match { cases, .. } => {
+                    for case in cases {
+                        pattern_binds(&case.pattern, dynamic);
+                    }
+                }
+                _ => {}
+            }
+            // nested scopes
+            let nested = nested
+                || matches!(
+                    node,
+                    Node::FnDeclaration { .. }
+                        | Node::AnFnDeclaration { .. }
+                        | Node::Type { .. }
+                        | Node::Unit { .. }
+                        | Node::Trait { .. }
+                );
+            for child in children(node) {
+                scan(child, nested, defines, dynamic);
+            }
+        }
+
+        // scanning
+        let mut defines: HashMap<String, usize> = HashMap::new();
+        let mut dynamic: HashSet<String> = HashSet::new();
+        for name in predefined {
+            defines.insert(name.clone(), 1);
+        }
+        scan(body, false, &mut defines, &mut dynamic);
+
+        // defined twice
+        for (name, amount) in defines {
+            if amount > 1 {
+                dynamic.insert(name);
+            }
+        }
+        dynamic
+    }
+
+    /// Looks up binding by name
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.bindings.get(name))
+    }
+
+    /// Looks up hint of param or field by name,
+    /// if name is bound by the scope of hint
+    fn lookup_hint(&self, name: &str) -> Option<&(String, Hint)> {
+        self.scopes
+            .iter()
+            .rev()
+            .find(|scope| scope.bindings.contains_key(name))
+            .and_then(|scope| scope.hints.get(name))
+    }
+
+    /// Binds value in current scope, dynamic
+    /// names are always bound as dynamic
+    fn bind(&mut self, name: &str, binding: Binding) {
+        let scope = self.scopes.last_mut().unwrap();
+        let binding = if scope.dynamic.contains(name) {
+            Binding::Var(Ty::Dynamic)
+        } else {
+            binding
+        };
+        scope.bindings.insert(name.to_string(), binding);
+    }
+
+    /// Checks hint names existing type
+    fn check_hint(&self, hint: &Hint) {
+        let known = matches!(
+            hint.name.as_str(),
+            "any" | "int" | "float" | "number" | "string" | "bool" | "fn" | "null" | "list" | "map"
+        ) || self.types.contains_key(&hint.name)
+            || self.traits.contains_key(&hint.name)
+            || self.enums.contains(&hint.name);
+        if !known {
+            error!(Error::own_text(
+                hint.addr.clone(),
+                format!("unknown type {} in hint.", hint.name),
+                "check type is declared or imported."
+            ))
+        }
+    }
+
+    /// Type of value, annotated with hint
+    fn hint_ty(&self, hint: &Hint) -> Ty {
+        if hint.nullable {
+            return Ty::Dynamic;
+        }
+        match hint.name.as_str() {
+            "int" => Ty::Int,
+            "float" => Ty::Float,
+            "number" => Ty::Number,
+            "string" => Ty::String,
+            "bool" => Ty::Bool,
+            "fn" => Ty::Fn,
+            "null" => Ty::Null,
+            "list" => Ty::Instance("List".to_string()),
+            "map" => Ty::Instance("Map".to_string()),
+            name if self.types.contains_key(name) => Ty::Instance(name.to_string()),
+            _ => Ty::Dynamic,
+        }
+    }
+
+    /// Checks type impls trait, directly
+    /// or through child trait
+    fn impls_trait(&self, type_name: &str, trait_name: &str) -> bool {
+        let Some(info) = self.types.get(type_name) else {
+            return true;
+        };
+        let mut pending: Vec<String> = info.impls.clone();
+        let mut visited: HashSet<String> = HashSet::new();
+        while let Some(name) = pending.pop() {
+            if name == trait_name {
+                return true;
+            }
+            if visited.insert(name.clone())
+                && let Some(parents) = self.traits.get(&name)
+            {
+                pending.extend(parents.iter().cloned());
+            }
+        }
+        false
+    }
+
+    /// Checks value of type fits hint
+    fn fits(&self, ty: &Ty, hint: &Hint) -> bool {
+        if hint.name == "any" {
+            return true;
+        }
+        match ty {
+            Ty::Dynamic => true,
+            Ty::Null => hint.nullable || hint.name == "null",
+            _ => match hint.name.as_str() {
+                "int" => matches!(ty, Ty::Int | Ty::Number),
+                "float" | "number" => matches!(ty, Ty::Int | Ty::Float | Ty::Number),
+                "string" => *ty == Ty::String,
+                "bool" => *ty == Ty::Bool,
+                "fn" => *ty == Ty::Fn,
+                "null" => false,
+                "list" => matches!(ty, Ty::Instance(name) if name == "List"),
+                "map" => matches!(ty, Ty::Instance(name) if name == "Map"),
+                name if self.traits.contains_key(name) => match ty {
+                    Ty::Instance(type_name) => self.impls_trait(type_name, name),
+                    _ => false,
+                },
+                name => matches!(ty, Ty::Instance(type_name) if type_name == name),
+            },
+        }
+    }
+
+    /// Result type of call
+    fn call_result(&self, signature: &Signature) -> Ty {
+        match (&signature.returns, signature.kind) {
+            (Some(hint), FnKind::Plain) => self.hint_ty(hint),
+            _ => Ty::Dynamic,
+        }
+    }
+
+    /// Checks call args against signature,
+    /// named args are matched by name
+    fn check_args(&mut self, addr: &Address, signature: &Signature, args: &[Node]) {
+        let mut position = 0;
+        for arg in args {
+            // param of arg
+            let (param, value) = match arg {
+                Node::NamedArg { name, value } => (
+                    signature.params.iter().find(|p| p.name == name.value),
+                    value.as_ref(),
+                ),
+                _ => {
+                    let param = signature
+                        .params
+                        .get(position)
+                        .or_else(|| signature.params.last().filter(|p| p.variadic));
+                    position += 1;
+                    (param, arg)
+                }
+            };
+            // checking
+            let ty = self.infer(value);
+            if let Some(param) = param
+                && let Some(hint) = &param.hint
+                && !self.fits(&ty, hint)
+            {
+                error!(Error::own_text(
+                    addr.clone(),
+                    format!(
+                        "expected {hint} for param {} of {}, got {ty}.",
+                        param.name, signature.name
+                    ),
+                    "check type of argument."
+                ))
+            }
+        }
+    }
+
+    /// Checks value, assigned to hinted field or param
+    fn check_assign(&self, addr: &Address, subject: &str, hint: &Hint, ty: &Ty) {
+        if !self.fits(ty, hint) {
+            error!(Error::own_text(
+                addr.clone(),
+                format!("expected {hint} for {subject}, got {ty}."),
+                "check type of assigned value."
+            ))
+        }
+    }
+
+    /// Hint of field of instance type
+    fn field_hint(&self, ty: &Ty, name: &str) -> Option<(String, Hint)> {
+        match ty {
+            Ty::Instance(type_name) => self.types.get(type_name).and_then(|info| {
+                info.fields
+                    .iter()
+                    .find(|(field, _)| field == name)
+                    .and_then(|(_, hint)| hint.clone())
+                    .map(|hint| (type_name.clone(), hint))
+            }),
+            _ => None,
+        }
+    }
+
+    /// Hoists fn declarations of block
+    fn hoist(&mut self, body: &[Node]) {
+        for node in body {
+            if let Node::FnDeclaration {
+                name,
+                params,
+                returns,
+                kind,
+                ..
+            } = node
+            {
+                let signature = Signature::of(&name.value, params, returns, *kind);
+                self.bind(&name.value, Binding::Fn(signature));
+            }
+        }
+    }
+
+    /// Checks fn
+    fn check_fn(
+        &mut self,
+        name: &str,
+        params: &[Parameter],
+        returns: &Option<TypeHint>,
+        kind: FnKind,
+        body: &Node,
+    ) {
+        // scope of fn
+        let names: Vec<String> = params.iter().map(|p| p.name.value.clone()).collect();
+        let mut scope = Scope::new(ScopeKind::Fn, Self::dynamic_names(body, &names));
+        if kind != FnKind::Generator
+            && let Some(hint) = returns
+        {
+            let hint = Hint::of(hint);
+            self.check_hint(&hint);
+            scope.returns = Some((name.to_string(), hint));
+        }
+        self.scopes.push(scope);
+
+        // params
+        for param in params {
+            // default value
+            if let Some(default) = &param.default {
+                self.infer(default);
+            }
+            let ty = match &param.hint {
+                _ if param.variadic => Ty::Instance("List".to_string()),
+                Some(hint) => {
+                    let hint = Hint::of(hint);
+                    self.check_hint(&hint);
+                    let ty = self.hint_ty(&hint);
+                    self.scopes.last_mut().unwrap().hints.insert(
+                        param.name.value.clone(),
+                        (format!("param {} of {name}", param.name.value), hint),
+                    );
+                    ty
+                }
+                None => Ty::Dynamic,
+            };
+            self.bind(&param.name.value, Binding::Var(ty));
+        }
+
+        // body
+        self.check_node(body);
+        self.scopes.pop();
+    }
+
+    /// Checks type or unit body, `fields` are
+    /// bound with their hints
+    fn check_body(
+        &mut self,
+        kind: ScopeKind,
+        owner: Ty,
+        fields: &[Field],
+        functions: HashMap<String, Signature>,
+        body: &Node,
+    ) {
+        // scope of body
+        let names: Vec<String> = fields.iter().map(|f| f.name.value.clone()).collect();
+        self.scopes
+            .push(Scope::new(kind, Self::dynamic_names(body, &names)));
+        self.bind("self", Binding::Var(owner.clone()));
+        for (name, signature) in functions {
+            self.bind(&name, Binding::Fn(signature));
+        }
+        for field in fields {
+            let ty = match &field.hint {
+                Some(hint) => {
+                    let hint = Hint::of(hint);
+                    self.check_hint(&hint);
+                    let ty = self.hint_ty(&hint);
+                    self.scopes.last_mut().unwrap().hints.insert(
+                        field.name.value.clone(),
+                        (format!("field {} of {owner}", field.name.value), hint),
+                    );
+                    ty
+                }
+                None => Ty::Dynamic,
+            };
+            self.bind(&field.name.value, Binding::Var(ty));
+        }
+
+        // body
+        self.check_node(body);
+        self.scopes.pop();
+    }
+
+    /// Checks statement node
+    fn check_node(&mut self, node: &Node) {
+        match node {
+            Node::Block { body } => {
+                self.hoist(body);
+                for node in body {
+                    self.check_node(node);
+                }
+            }
+            Node::If {
+                logical,
+                body,
+                elseif,
+                ..
+            } => {
+                self.infer(logical);
+                self.check_node(body);
+                if let Some(elseif) = elseif {
+                    self.check_node(elseif);
+                }
+            }
+            Node::While { logical, body, .. } => {
+                self.infer(logical);
+                self.check_node(body);
+            }
+            Node::For {
+                iterable,
+                variable_name,
+                body,
+            } => {
+                self.infer(iterable);
+                self.bind(&variable_name.value, Binding::Var(Ty::Dynamic));
+                self.check_node(body);
+            }
+            Node::With {
+                variable_name,
+                value,
+                body,
+            } => {
+                self.infer(value);
+                self.bind(&variable_name.value, Binding::Var(Ty::Dynamic));
+                self.check_node(body);
+            }
+            Node::Define {
+                previous: None,
+                name,
+                value,
+            } => {
+                let ty = self.infer(value);
+                // fields of types and units could be
+                // assigned from outside, so they're dynamic
+                let ty = match self.scopes.last().unwrap().kind {
+                    ScopeKind::Type | ScopeKind::Unit => Ty::Dynamic,
+                    _ => ty,
+                };
+                self.bind(&name.value, Binding::Var(ty));
+            }
+            Node::Assign {
+                previous: None,
+                name,
+                value,
+            } => {
+                let ty = self.infer(value);
+                if let Some((subject, hint)) = self.lookup_hint(&name.value) {
+                    self.check_assign(&name.address, subject, hint, &ty);
+                }
+            }
+            Node::Define {
+                previous: Some(previous),
+                name,
+                value,
+            }
+            | Node::Assign {
+                previous: Some(previous),
+                name,
+                value,
+            } => {
+                let owner = self.infer(previous);
+                let ty = self.infer(value);
+                if let Some((type_name, hint)) = self.field_hint(&owner, &name.value) {
+                    let subject = format!("field {} of {type_name}", name.value);
+                    self.check_assign(&name.address, &subject, &hint, &ty);
+                }
+            }
+            Node::FnDeclaration {
+                name,
+                params,
+                returns,
+                body,
+                kind,
+                ..
+            } => {
+                self.check_fn(&name.value, params, returns, *kind, body);
+            }
+            Node::Type {
+                name,
+                constructor,
+                body,
+                ..
+            } => {
+                let methods = self
+                    .types
+                    .get(&name.value)
+                    .map(|info| info.methods.clone())
+                    .unwrap_or_default();
+                self.check_body(
+                    ScopeKind::Type,
+                    Ty::Instance(name.value.clone()),
+                    constructor,
+                    methods,
+                    body,
+                );
+            }
+            Node::Unit { name, body, .. } => {
+                let functions = self.units.get(&name.value).cloned().unwrap_or_default();
+                self.check_body(ScopeKind::Unit, Ty::Dynamic, &[], functions, body);
+            }
+            Node::Trait { functions, .. } => {
+                for function in functions {
+                    if let Some(default) = &function.default {
+                        self.scopes
+                            .push(Scope::new(ScopeKind::Type, HashSet::new()));
+                        self.bind("self", Binding::Var(Ty::Dynamic));
+                        for param in &function.params {
+                            self.bind(&param.value, Binding::Var(Ty::Dynamic));
+                        }
+                        self.check_node(default);
+                        self.scopes.pop();
+                    }
+                }
+            }
+            Node::Native { name, fn_name } => {
+                if let Some(signature) = Signature::of_native(&name.value, &fn_name.value) {
+                    self.bind(&name.value, Binding::Fn(signature));
+                }
+            }
+            Node::Ret { location, value } => {
+                let ty = self.infer(value);
+                // return hint of nearest fn
+                let returns = self
+                    .scopes
+                    .iter()
+                    .rev()
+                    .find(|scope| scope.kind == ScopeKind::Fn)
+                    .and_then(|scope| scope.returns.clone());
+                if let Some((name, hint)) = returns
+                    && !self.fits(&ty, &hint)
+                {
+                    error!(Error::own_text(
+                        location.address.clone(),
+                        format!("expected {hint} to return from {name}, got {ty}."),
+                        "check type of returned value."
+                    ))
+                }
+            }
+            Node::Match {
+                matchable,
+                cases,
+                default,
+                ..
+            } => {
+                self.infer(matchable);
+                for case in cases {
+                    self.check_pattern(&case.pattern);
+                    if let Some(guard) = &case.guard {
+                        self.infer(guard);
+                    }
+                    self.check_node(&case.body);
+                }
+                self.check_node(default);
+            }
+            Node::Destructure { names, value, .. } => {
+                self.infer(value);
+                for name in names {
+                    self.bind(&name.value, Binding::Var(Ty::Dynamic));
+                }
+            }
+            Node::Import { .. }
+            | Node::Enum { .. }
+            | Node::Break { .. }
+            | Node::Continue { .. } => {}
+            _ => {
+                self.infer(node);
+            }
+        }
+    }
+
+    /// Checks pattern values and binds it's names
+    fn check_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Value { value } => {
+                self.infer(value);
+            }
+            Pattern::Range { from, to, .. } => {
+                self.infer(from);
+                self.infer(to);
+            }
+            _ => {
+                self.check_variants(pattern);
+                let mut names = HashSet::new();
+                pattern_binds(pattern, &mut names);
+                for name in names {
+                    self.bind(&name, Binding::Var(Ty::Dynamic));
+                }
+            }
+        }
+    }
+
+    /// Checks unqualified variant patterns
+    /// name variant of the only enum
+    fn check_variants(&self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Constructor {
+                name,
+                qualifier,
+                fields,
+                ..
+            } => {
+                if qualifier.is_none()
+                    && let Some(enums) = self.variants.get(&name.value)
+                    && enums.len() > 1
+                {
+                    let mut enums: Vec<&str> = enums.iter().map(|e| e.1.as_str()).collect();
+                    enums.sort();
+                    error!(Error::own(
+                        name.address.clone(),
+                        format!("variant pattern {} is ambiguous.", name.value),
+                        format!(
+                            "it's a variant of {}, use Enum.{}(...).",
+                            enums.join(", "),
+                            name.value
+                        )
+                    ))
+                }
+                for field in fields {
+                    self.check_variants(field);
+                }
+            }
+            Pattern::List { items, rest, .. } => {
+                for item in items.iter().chain(rest.as_deref()) {
+                    self.check_variants(item);
+                }
+            }
+            Pattern::Or { patterns } => {
+                for pattern in patterns {
+                    self.check_variants(pattern);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Infers type of expression node,
+    /// checking it's calls
+    fn infer(&mut self, node: &Node) -> Ty {
+        match node {
+            Node::Number { value } => {
+                if value.value.contains('.') {
+                    Ty::Float
+                } else {
+                    Ty::Int
+                }
+            }
+            Node::String { .. } => Ty::String,
+            Node::Bool { .. } => Ty::Bool,
+            Node::Null { .. } => Ty::Null,
+            Node::List { values, .. } => {
+                for value in values {
+                    self.infer(value);
+                }
+                Ty::Instance("List".to_string())
+            }
+            Node::Map { values, .. } => {
+                for (key, value) in values {
+                    self.infer(key);
+                    self.infer(value);
+                }
+                Ty::Instance("Map".to_string())
+            }
+            Node::Bin { left, right, op } => {
+                let a = self.infer(left);
+                let b = self.infer(right);
+                Self::infer_binary(&op.value, a, b)
+            }
+            Node::Unary { value, op } => {
+                let ty = self.infer(value);
+                match op.value.as_str() {
+                    "!" => Ty::Bool,
+                    "-" if matches!(ty, Ty::Int | Ty::Float | Ty::Number) => ty,
+                    _ => Ty::Dynamic,
+                }
+            }
+            Node::Cond { left, right, .. } | Node::Logical { left, right, .. } => {
+                self.infer(left);
+                self.infer(right);
+                Ty::Bool
+            }
+            Node::Impls { value, .. } => {
+                self.infer(value);
+                Ty::Bool
+            }
+            Node::Get {
+                previous: None,
+                name,
+                ..
+            } => match self.lookup(&name.value) {
+                Some(Binding::Var(ty)) => ty.clone(),
+                Some(Binding::Fn(_)) => Ty::Fn,
+                None => Ty::Dynamic,
+            },
+            Node::Get {
+                previous: Some(previous),
+                name,
+                ..
+            } => {
+                // unit is not a value
+                if self.unit_of(previous).is_some() {
+                    return Ty::Dynamic;
+                }
+                let owner = self.infer(previous);
+                match self.field_hint(&owner, &name.value) {
+                    Some((_, hint)) => self.hint_ty(&hint),
+                    None => Ty::Dynamic,
+                }
+            }
+            Node::Call {
+                previous,
+                name,
+                args,
+                ..
+            } => {
+                let signature = match previous {
+                    None => match self.lookup(&name.value) {
+                        Some(Binding::Fn(signature)) => Some(signature.clone()),
+                        _ => None,
+                    },
+                    Some(previous) => match self.unit_of(previous) {
+                        Some(unit) => unit.get(&name.value).cloned(),
+                        None => match self.infer(previous) {
+                            Ty::Instance(type_name) => self
+                                .types
+                                .get(&type_name)
+                                .and_then(|info| info.methods.get(&name.value).cloned()),
+                            _ => None,
+                        },
+                    },
+                };
+                match signature {
+                    Some(signature) => {
+                        self.check_args(&name.address, &signature, args);
+                        self.call_result(&signature)
+                    }
+                    None => {
+                        for arg in args {
+                            self.infer(arg);
+                        }
+                        Ty::Dynamic
+                    }
+                }
+            }
+            Node::Instance {
+                name, constructor, ..
+            } => {
+                match self.types.get(&name.value).cloned() {
+                    Some(info) => {
+                        for (i, arg) in constructor.iter().enumerate() {
+                            let ty = self.infer(arg);
+                            if let Some((field, Some(hint))) = info.fields.get(i) {
+                                let subject = format!("field {field} of {}", name.value);
+                                self.check_assign(&name.address, &subject, hint, &ty);
+                            }
+                        }
+                    }
+                    None => {
+                        for arg in constructor {
+                            self.infer(arg);
+                        }
+                    }
+                }
+                Ty::Instance(name.value.clone())
+            }
+            Node::AnFnDeclaration {
+                location,
+                params,
+                returns,
+                body,
+                kind,
+                ..
+            } => {
+                self.check_fn(&location.value, params, returns, *kind, body);
+                Ty::Fn
+            }
+            Node::NamedArg { value, .. }
+            | Node::Await { value, .. }
+            | Node::Yield { value, .. }
+            | Node::ErrorPropagation { value, .. } => {
+                self.infer(value);
+                Ty::Dynamic
+            }
+            Node::Index { value, key, .. } => {
+                self.infer(value);
+                self.infer(key);
+                Ty::Dynamic
+            }
+            Node::Range { from, to, .. } => {
+                self.infer(from);
+                self.infer(to);
+                Ty::Dynamic
+            }
+            _ => {
+                self.check_node(node);
+                Ty::Dynamic
+            }
+        }
+    }
+
+    /// Unit, accessed by node, if
+    /// it's name isn't shadowed
+    fn unit_of(&self, node: &Node) -> Option<&HashMap<String, Signature>> {
+        match node {
+            Node::Get {
+                previous: None,
+                name,
+                ..
+            } if self.lookup(&name.value).is_none() => self.units.get(&name.value),
+            _ => None,
+        }
+    }
+
+    /// Infers result of binary operation
+    fn infer_binary(op: &str, a: Ty, b: Ty) -> Ty {
+        /// Numeric result of operands
+        fn numeric(a: &Ty, b: &Ty) -> Ty {
+            match (a, b) {
+                (Ty::Int, Ty::Int) => Ty::Int,
+                (Ty::Float, Ty::Int | Ty::Float | Ty::Number)
+                | (Ty::Int | Ty::Number, Ty::Float) => Ty::Float,
+                (Ty::Int | Ty::Number, Ty::Int | Ty::Number) => Ty::Number,
+                _ => Ty::Dynamic,
+            }
+        }
+        match op {
+            "+" if a == Ty::String || b == Ty::String => Ty::String,
+            "+" | "-" | "*" | "%" => numeric(&a, &b),
+            // int division could give float
+            "/" => match numeric(&a, &b) {
+                Ty::Int => Ty::Number,
+                ty => ty,
+            },
+            _ => Ty::Dynamic,
+        }
+    }
+}
+
+/// Names, bound by pattern
+fn pattern_binds(pattern: &Pattern, names: &mut HashSet<String>) {
+    match pattern {
+        Pattern::Bind { name } => {
+            names.insert(name.value.clone());
+        }
+        Pattern::List { items, rest, .. } => {
+            for item in items {
+                pattern_binds(item, names);
+            }
+            if let Some(rest) = rest {
+                pattern_binds(rest, names);
+            }
+        }
+        Pattern::Constructor { fields, .. } => {
+            for field in fields {
+                pattern_binds(field, names);
+            }
+        }
+        Pattern::Or { patterns } => {
+            for pattern in patterns {
+                pattern_binds(pattern, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Value nodes of pattern
+fn pattern_nodes<'node>(pattern: &'node Pattern, nodes: &mut Vec<&'node Node>) {
+    match pattern {
+        Pattern::Value { value } => nodes.push(value),
+        Pattern::Range { from, to, .. } => {
+            nodes.push(from);
+            nodes.push(to);
+        }
+        Pattern::List { items, rest, .. } => {
+            for item in items {
+                pattern_nodes(item, nodes);
+            }
+            if let Some(rest) = rest {
+                pattern_nodes(rest, nodes);
+            }
+        }
+        Pattern::Constructor { fields, .. } => {
+            for field in fields {
+                pattern_nodes(field, nodes);
+            }
+        }
+        Pattern::Or { patterns } => {
+            for pattern in patterns {
+                pattern_nodes(pattern, nodes);
+            }
+        }
+        Pattern::Wildcard { .. } | Pattern::Bind { .. } => {}
+    }
+}
+
+/// Child nodes of node
+fn children(node: &Node) -> Vec<&Node> {
+    let mut nodes: Vec<&Node> = Vec::new();
+    match node {
+        Node::Block { body } => nodes.extend(body),
+        Node::Bin { left, right, .. }
+        | Node::Cond { left, right, .. }
+        | Node::Logical { left, right, .. } => {
+            nodes.push(left);
+            nodes.push(right);
+        }
+        Node::Unary { value, .. }
+        | Node::Ret { value, .. }
+        | Node::Yield { value, .. }
+        | Node::Await { value, .. }
+        | Node::ErrorPropagation { value, .. }
+        | Node::Impls { value, .. }
+        | Node::NamedArg { value, .. }
+        | Node::Destructure { value, .. } => nodes.push(value),
+        Node::If {
+            logical,
+            body,
+            elseif,
+            ..
+        } => {
+            nodes.push(logical);
+            nodes.push(body);
+            if let Some(elseif) = elseif {
+                nodes.push(elseif);
+            }
+        }
+        Node::While { logical, body, .. } => {
+            nodes.push(logical);
+            nodes.push(body);
+        }
+        Node::Define {
+            previous, value, ..
+        }
+        | Node::Assign {
+            previous, value, ..
+        } => {
+            if let Some(previous) = previous {
+                nodes.push(previous);
+            }
+            nodes.push(value);
+        }
+        Node::Get {
+            previous: Some(previous),
+            ..
+        } => nodes.push(previous),
+        Node::Call { previous, args, .. } => {
+            if let Some(previous) = previous {
+                nodes.push(previous);
+            }
+            nodes.extend(args);
+        }
+        Node::FnDeclaration { params, body, .. } | Node::AnFnDeclaration { params, body, .. } => {
+            for param in params {
+                if let Some(default) = &param.default {
+                    nodes.push(default);
+                }
+            }
+            nodes.push(body);
+        }
+        Node::List { values, .. } => nodes.extend(values),
+        Node::Map { values, .. } => {
+            for (key, value) in values {
+                nodes.push(key);
+                nodes.push(value);
+            }
+        }
+        Node::Match {
+            matchable,
+            cases,
+            default,
+            ..
+        } => {
+            nodes.push(matchable);
+            for case in cases {
+                pattern_nodes(&case.pattern, &mut nodes);
+                if let Some(guard) = &case.guard {
+                    nodes.push(guard);
+                }
+                nodes.push(&case.body);
+            }
+            nodes.push(default);
+        }
+        Node::Instance { constructor, .. } => nodes.extend(constructor),
+        Node::Type { body, .. } | Node::Unit { body, .. } => nodes.push(body),
+        Node::For { iterable, body, .. } => {
+            nodes.push(iterable);
+            nodes.push(body);
+        }
+        Node::With { value, body, .. } => {
+            nodes.push(value);
+            nodes.push(body);
+        }
+        Node::Trait { functions, .. } => {
+            for function in functions {
+                if let Some(default) = &function.default {
+                    nodes.push(default);
+                }
+            }
+        }
+        Node::Index { value, key, .. } => {
+            nodes.push(value);
+            nodes.push(key);
+        }
+        Node::Range { from, to, .. } => {
+            nodes.push(from);
+            nodes.push(to);
+        }
+        _ => {}
+    }
+    nodes
+}
@@ -1 +1,3 @@
 pub mod analyzer;
+pub mod checker;
+pub mod natives;
@@ -0,0 +1,164 @@
+/// Native signature
+///
+/// * `params`: type hints of params
+/// * `returns`: optional type hint of result
+///
+pub struct NativeSignature {
+    pub params: &'static [&'static str],
+    pub returns: Option<&'static str>,
+}
+
+/// Gets signature of stdlib native by it's fn name,
+/// params are the values, natives take with `expect_*`
+///
+/// returns none, if native is unknown
+///
+pub fn signature(fn_name: &str) -> Option<NativeSignature> {
+    let (params, returns): (&'static [&'static str], Option<&'static str>) = match fn_name {
+        // base
+        "base@panic" => (&["string", "string"], None),
+        "base@typeof" | "base@full_typeof" => (&["any"], Some("string")),
+        "base@is_instance" => (&["any"], Some("bool")),
+        // convert
+        "convert@to_int" => (&["any"], Some("int")),
+        "convert@to_float" => (&["any"], Some("float")),
+        "convert@to_string" => (&["any"], Some("string")),
+        "convert@to_bool" => (&["any"], Some("bool")),
+        "convert@to_bigint" => (&["any"], None),
+        // crypto
+        "crypto@b64_encode" | "crypto@b64_decode" | "crypto@sha256" | "crypto@sha512"
+        | "crypto@sha224" | "crypto@sha384" | "crypto@md5" => (&["string"], Some("string")),
+        // io
+        "io@print" | "io@println" => (&["any"], Some("null")),
+        "io@flush" => (&[], Some("null")),
+        "io@input" => (&[], Some("string")),
+        // gc
+        "gc@invoke" => (&[], Some("null")),
+        "gc@stats" => (&[], None),
+        "gc@snapshot" => (&["string"], Some("null")),
+        // generators and tasks
+        "generator@has_next" | "task@is_done" => (&["any"], Some("bool")),
+        "generator@next" => (&["any"], None),
+        // ffi
+        "ffi@load" => (&["string"], None),
+        "ffi@load_fn" => (&["any", "string", "any", "string"], None),
+        "ffi@call_fn" => (&["any", "string", "any"], None),
+        // math
+        "math@sin" | "math@cos" | "math@tan" | "math@ctg" | "math@atan" | "math@asin"
+        | "math@acos" | "math@tanh" => (&["number"], Some("float")),
+        // chars
+        "char@is_digit" => (&["string", "int"], Some("bool")),
+        "char@is_ascii_letter" => (&["string"], Some("bool")),
+        "char@as_int" => (&["string"], Some("int")),
+        // fs
+        "fs@open"
+        | "fs@create"
+        | "fs@mkdir"
+        | "fs@delete_directory"
+        | "fs@delete_directory_all"
+        | "fs@exists"
+        | "fs@list"
+        | "fs@is_directory"
+        | "fs@read_async" => (&["string"], None),
+        "fs@close" => (&["any"], None),
+        "fs@read_to_string" => (&["any"], Some("string")),
+        "fs@write" => (&["any", "string"], None),
+        "fs@tell" => (&["any"], Some("int")),
+        "fs@seek" => (&["any", "int", "int"], None),
+        "fs@write_async" => (&["string", "string"], None),
+        // net
+        "net@get" | "net@post" | "net@put" | "net@options" | "net@delete" | "net@patch"
+        | "net@head" | "net@listen" | "net@connect" => (&["string"], None),
+        "net@header" => (&["any", "string", "string"], None),
+        "net@body" => (&["any", "string"], None),
+        "net@send" | "net@send_async" | "net@response_bytes" | "net@accept" => (&["any"], None),
+        "net@response_status" => (&["any"], Some("int")),
+        "net@response_headers"
+        | "net@response_utf8"
+        | "net@listener_address"
+        | "net@socket_peer" => (&["any"], Some("string")),
+        "net@response_close" | "net@listener_close" | "net@socket_close" => {
+            (&["any"], Some("null"))
+        }
+        "net@socket_read" => (&["any", "int"], Some("string")),
+        "net@socket_write" => (&["any", "string"], Some("null")),
+        // list, raw list is taken
+        "list@make" => (&[], None),
+        "list@add" | "list@delete_at" => (&["any", "any"], Some("null")),
+        "list@set" => (&["any", "any", "any"], Some("null")),
+        "list@get" | "list@delete" => (&["any", "any"], None),
+        "list@index_of" => (&["any", "any"], Some("int")),
+        "list@length" => (&["any"], Some("int")),
+        // strings
+        "strings@replace" => (&["string", "string", "string"], Some("string")),
+        "strings@replace_n" => (&["string", "string", "string", "int"], Some("string")),
+        "strings@replace_range" => (&["string", "string", "int", "int"], Some("string")),
+        "strings@char_at" => (&["string", "int"], Some("string")),
+        "strings@chars" => (&["string"], None),
+        "strings@trim" | "strings@lower" | "strings@upper" => (&["string"], Some("string")),
+        "strings@split" => (&["string", "string"], None),
+        "strings@substring" => (&["string", "int", "int"], Some("string")),
+        "strings@contains" => (&["string", "string"], Some("bool")),
+        "strings@find" | "strings@rfind" => (&["string", "string"], Some("int")),
+        "strings@push" => (&["string", "string"], Some("null")),
+        "strings@length" => (&["string"], Some("int")),
+        // time
+        "time@now" => (&[], None),
+        "time@sleep" | "timedelta@new" => (&["int"], None),
+        "timestamp@millis" | "timestamp@seconds" | "timestamp@second" | "timestamp@minute"
+        | "timestamp@hour" | "timestamp@day" | "timestamp@year" | "timestamp@month"
+        | "timestamp@weekday" | "timestamp@week" | "timedelta@millis" | "timedelta@seconds"
+        | "timedelta@minutes" | "timedelta@hours" | "timedelta@days" | "timedelta@weeks" => {
+            (&["any"], Some("int"))
+        }
+        "timestamp@gt" | "timestamp@lt" => (&["any", "any"], Some("bool")),
+        "timestamp@sub"
+        | "timestamp@ge"
+        | "timestamp@le"
+        | "timestamp@eq"
+        | "timestamp@add_delta"
+        | "timedelta@add"
+        | "timedelta@sub"
+        | "timedelta@gt"
+        | "timedelta@lt"
+        | "timedelta@ge"
+        | "timedelta@le" => (&["any", "any"], None),
+        "timestamp@add_millis"
+        | "timestamp@add_seconds"
+        | "timestamp@add_minutes"
+        | "timestamp@add_hours"
+        | "timestamp@add_days"
+        | "timestamp@add_weeks" => (&["any", "int"], None),
+        // system
+        "system@getenv" | "system@process_spawn_shell" => (&["string"], None),
+        "system@setenv" => (&["string", "string"], None),
+        "system@getcwd" | "system@getargs" | "system@cpu_count" => (&[], None),
+        "system@memory_total" | "system@memory_used" | "system@this_process_id" => {
+            (&[], Some("int"))
+        }
+        "system@get_osname" => (&[], Some("string")),
+        "system@this_process_terminate" => (&["int"], None),
+        "system@process_terminate" | "system@process_close" => (&["any"], Some("null")),
+        "system@process_wait" | "system@process_wait_async" => (&["any"], None),
+        "system@process_id" => (&["any"], Some("int")),
+        // thread
+        "thread@spawn" => (&["fn", "any"], None),
+        "thread@run" => (&["string", "any"], None),
+        "thread@args" | "thread@channel" => (&[], None),
+        "thread@join" | "thread@join_async" | "thread@recv" | "thread@recv_async" => {
+            (&["any"], None)
+        }
+        "thread@is_finished" => (&["any"], Some("bool")),
+        "thread@send" => (&["any", "any"], Some("null")),
+        "thread@close" => (&["any"], Some("null")),
+        // weak
+        "weak@ref" | "weak@get" => (&["any"], None),
+        "weak@map_make" => (&[], None),
+        "weak@map_set" => (&["any", "any", "any"], Some("null")),
+        "weak@map_get" => (&["any", "any"], None),
+        "weak@map_has" | "weak@map_delete" => (&["any", "any"], Some("bool")),
+        "weak@map_length" => (&["any"], Some("int")),
+        _ => return None,
+    };
+    Some(NativeSignature { params, returns })
+}
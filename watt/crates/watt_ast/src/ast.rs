@@ -62,15 +62,19 @@ pub enum Node {
     FnDeclaration {
         name: Token,
         full_name: Option<Token>,
-        params: Vec<Token>,
+        params: Vec<Parameter>,
+        returns: Option<TypeHint>,
         body: Box<Node>,
         make_closure: bool,
+        kind: FnKind,
     },
     AnFnDeclaration {
         location: Token,
-        params: Vec<Token>,
+        params: Vec<Parameter>,
+        returns: Option<TypeHint>,
         body: Box<Node>,
         make_closure: bool,
+        kind: FnKind,
     },
     Break {
         location: Token,
@@ -119,13 +123,22 @@ pub enum Node {
         location: Token,
         value: Box<Node>,
     },
+    Yield {
+        location: Token,
+        value: Box<Node>,
+    },
+    Await {
+        location: Token,
+        value: Box<Node>,
+        should_push: bool,
+    },
     Null {
         location: Token,
     },
     Type {
         name: Token,
         full_name: Option<Token>,
-        constructor: Vec<Token>,
+        constructor: Vec<Field>,
         body: Box<Node>,
         impls: Vec<Token>,
     },
@@ -139,11 +152,22 @@ pub enum Node {
         variable_name: Token,
         body: Box<Node>,
     },
+    With {
+        variable_name: Token,
+        value: Box<Node>,
+        body: Box<Node>,
+    },
     Trait {
         name: Token,
         full_name: Option<Token>,
+        parents: Vec<Token>,
         functions: Vec<TraitNodeFn>,
     },
+    Enum {
+        name: Token,
+        full_name: Option<Token>,
+        variants: Vec<EnumNodeVariant>,
+    },
     ErrorPropagation {
         location: Token,
         value: Box<Node>,
@@ -153,25 +177,189 @@ pub enum Node {
         value: Box<Node>,
         trait_name: Token,
     },
+    Index {
+        location: Token,
+        value: Box<Node>,
+        key: Box<Node>,
+        should_push: bool,
+    },
     Range {
         location: Token,
         from: Box<Node>,
         to: Box<Node>,
     },
+    NamedArg {
+        name: Token,
+        value: Box<Node>,
+    },
+    Destructure {
+        location: Token,
+        kind: DestructureKind,
+        names: Vec<Token>,
+        value: Box<Node>,
+        is_define: bool,
+    },
+}
+
+/// Destructuring kind
+///
+/// `List` takes values by position `[a, b]` or `a, b`,
+/// `Fields` takes fields by name `{a, b}`
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DestructureKind {
+    List,
+    Fields,
+}
+
+/// Fn kind
+///
+/// `Plain` fn runs body, when called, `Generator` fn
+/// `fn*` returns generator and `Async` fn `async fn`
+/// returns task, scheduled to run body
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FnKind {
+    Plain,
+    Generator,
+    Async,
+}
+
+/// Type hint
+///
+/// * `name`: name of type, `int`, `string`, `Point`...
+/// * `nullable`: `T?` hint also accepts null
+///
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TypeHint {
+    pub name: Token,
+    pub nullable: bool,
+}
+/// Type hint implementation
+impl TypeHint {
+    pub fn new(name: Token, nullable: bool) -> Self {
+        Self { name, nullable }
+    }
+}
+
+/// Fn parameter
+///
+/// * `name`: name of param
+/// * `default`: optional default value
+/// * `variadic`: collects rest args to list
+/// * `hint`: optional type hint, for variadic
+///   param it's a hint of each rest arg
+///
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Parameter {
+    pub name: Token,
+    pub default: Option<Box<Node>>,
+    pub variadic: bool,
+    pub hint: Option<TypeHint>,
+}
+/// Fn parameter implementation
+impl Parameter {
+    pub fn new(
+        name: Token,
+        default: Option<Box<Node>>,
+        variadic: bool,
+        hint: Option<TypeHint>,
+    ) -> Self {
+        Self {
+            name,
+            default,
+            variadic,
+            hint,
+        }
+    }
+}
+
+/// Type field, declared in constructor
+///
+/// * `name`: name of field
+/// * `hint`: optional type hint
+///
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Field {
+    pub name: Token,
+    pub hint: Option<TypeHint>,
+}
+/// Type field implementation
+impl Field {
+    pub fn new(name: Token, hint: Option<TypeHint>) -> Self {
+        Self { name, hint }
+    }
+}
+
+/// Match case pattern
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Pattern {
+    /// `_`, matches everything
+    Wildcard { location: Token },
+    /// `let name`, or `name` inside of list and
+    /// constructor patterns, matches everything and binds value
+    Bind { name: Token },
+    /// `expr`, matches values equal to expr
+    Value { value: Box<Node> },
+    /// `a..b`, matches numbers in range
+    Range {
+        location: Token,
+        from: Box<Node>,
+        to: Box<Node>,
+    },
+    /// `[a, b, ..rest]`, matches lists
+    List {
+        location: Token,
+        items: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// `Type(a, b)` or `Enum.Variant(a, ..)`,
+    /// matches instances and variants
+    Constructor {
+        name: Token,
+        qualifier: Option<Box<Node>>,
+        fields: Vec<Pattern>,
+        rest: bool,
+    },
+    /// `a | b`, matches if any of patterns matches
+    Or { patterns: Vec<Pattern> },
+}
+/// Pattern implementation
+impl Pattern {
+    /// Checks pattern matches every value
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Pattern::Wildcard { .. } | Pattern::Bind { .. } => true,
+            Pattern::Or { patterns } => patterns.iter().any(|p| p.is_irrefutable()),
+            _ => false,
+        }
+    }
 }
 
 /// Match statement case
-/// Represents pattern value, body
+/// Represents pattern, optional guard, body
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct MatchCase {
-    pub value: Box<Node>,
+    pub location: Token,
+    pub pattern: Pattern,
+    pub guard: Option<Box<Node>>,
     pub body: Box<Node>,
 }
 /// Match case implementation
 impl MatchCase {
     /// New match case
-    pub fn new(value: Box<Node>, body: Box<Node>) -> MatchCase {
-        MatchCase { value, body }
+    pub fn new(
+        location: Token,
+        pattern: Pattern,
+        guard: Option<Box<Node>>,
+        body: Box<Node>,
+    ) -> MatchCase {
+        MatchCase {
+            location,
+            pattern,
+            guard,
+            body,
+        }
     }
 }
 
@@ -198,6 +386,24 @@ impl TraitNodeFn {
     }
 }
 
+/// Enum node variant
+///
+/// * `name`: name of variant
+/// * `fields`: payload fields, empty
+///   for constant variant
+///
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct EnumNodeVariant {
+    pub name: Token,
+    pub fields: Vec<Token>,
+}
+/// Enum node variant implementation
+impl EnumNodeVariant {
+    pub fn new(name: Token, fields: Vec<Token>) -> Self {
+        Self { name, fields }
+    }
+}
+
 /// Sets should push for access node
 pub fn set_should_push(node: Node, should_push: bool) -> Node {
     match node {
@@ -231,6 +437,17 @@ pub fn set_should_push(node: Node, should_push: bool) -> Node {
             value,
             should_push,
         },
+        Node::Index {
+            location,
+            value,
+            key,
+            ..
+        } => Node::Index {
+            location,
+            value,
+            key,
+            should_push,
+        },
         _ => node,
     }
 }
@@ -1,3 +1,2 @@
 pub mod ast;
 pub mod import;
- 
\ No newline at end of file
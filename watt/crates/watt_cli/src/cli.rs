@@ -1,70 +1,192 @@
 // imports
-use clap::{Arg, ArgAction};
+use clap::{Arg, ArgAction, ArgMatches};
 
-/// Run cli
-#[allow(unsafe_op_in_unsafe_fn)]
-pub unsafe fn cli() {
-    // Command-line parser
-    let parser = clap::Command::new("watt")
-        .author("Watt developers.")
-        .about("The Watt interpreter.")
-        .arg(
-            Arg::new("gc-debug")
-                .long("gc-debug")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("ast-debug")
-                .long("ast-debug")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("opcodes-debug")
-                .long("opcodes-debug")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("lexer-debug")
-                .long("lexer-debug")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("parse-bench")
-                .long("parser-bench")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("compile-bench")
-                .long("compile-bench")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("lexer-bench")
-                .long("lexer-bench")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("runtime-bench")
-                .long("runtime-bench")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("gc-threshold")
-                .long("gc-threshold")
-                .value_parser(clap::value_parser!(usize)),
-        )
-        .arg(
-            Arg::new("gc-threshold-grow-factor")
-                .long("gc-threshold-grow-factor")
-                .value_parser(clap::value_parser!(usize)),
-        )
-        .arg(Arg::new("file").required(true))
-        .arg(Arg::new("args").action(ArgAction::Append));
+/// Run args, shared by the
+/// top level command and `run`
+fn run_args() -> Vec<Arg> {
+    vec![
+        Arg::new("gc-debug")
+            .long("gc-debug")
+            .action(ArgAction::SetTrue),
+        Arg::new("gc-stats")
+            .long("gc-stats")
+            .action(ArgAction::SetTrue)
+            .help("Print garbage collector statistics after run"),
+        Arg::new("ast-debug")
+            .long("ast-debug")
+            .action(ArgAction::SetTrue),
+        Arg::new("opcodes-debug")
+            .long("opcodes-debug")
+            .action(ArgAction::SetTrue),
+        Arg::new("lexer-debug")
+            .long("lexer-debug")
+            .action(ArgAction::SetTrue),
+        Arg::new("parse-bench")
+            .long("parser-bench")
+            .action(ArgAction::SetTrue),
+        Arg::new("compile-bench")
+            .long("compile-bench")
+            .action(ArgAction::SetTrue),
+        Arg::new("lexer-bench")
+            .long("lexer-bench")
+            .action(ArgAction::SetTrue),
+        Arg::new("runtime-bench")
+            .long("runtime-bench")
+            .action(ArgAction::SetTrue),
+        Arg::new("gc-threshold")
+            .long("gc-threshold")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("gc-threshold-grow-factor")
+            .long("gc-threshold-grow-factor")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("gc-mode")
+            .long("gc-mode")
+            .value_parser(["mark-sweep", "generational"])
+            .default_value("mark-sweep")
+            .help("Garbage collector mode"),
+        Arg::new("gc-nursery")
+            .long("gc-nursery")
+            .value_parser(clap::value_parser!(usize))
+            .help("Young objects amount, that triggers minor collection"),
+        Arg::new("max-call-depth")
+            .long("max-call-depth")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("max-instructions")
+            .long("max-instructions")
+            .value_parser(clap::value_parser!(u64))
+            .help("Limit executed instructions amount"),
+        Arg::new("timeout")
+            .long("timeout")
+            .value_name("MS")
+            .value_parser(clap::value_parser!(u64))
+            .help("Limit execution wall-clock time in milliseconds"),
+        Arg::new("max-stack")
+            .long("max-stack")
+            .value_parser(clap::value_parser!(usize))
+            .help("Limit vm stack size"),
+        Arg::new("max-objects")
+            .long("max-objects")
+            .value_parser(clap::value_parser!(usize))
+            .help("Limit alive gc objects amount"),
+        Arg::new("max-bytes")
+            .long("max-bytes")
+            .value_parser(clap::value_parser!(usize))
+            .help("Limit approximate size of alive gc objects"),
+        Arg::new("allow-read")
+            .long("allow-read")
+            .value_name("PATH")
+            .num_args(0..=1)
+            .require_equals(true)
+            .value_delimiter(',')
+            .action(ArgAction::Append)
+            .help("Allow reading files, everywhere or in listed paths"),
+        Arg::new("allow-write")
+            .long("allow-write")
+            .value_name("PATH")
+            .num_args(0..=1)
+            .require_equals(true)
+            .value_delimiter(',')
+            .action(ArgAction::Append)
+            .help("Allow writing files, everywhere or in listed paths"),
+        Arg::new("allow-net")
+            .long("allow-net")
+            .value_name("HOST")
+            .num_args(0..=1)
+            .require_equals(true)
+            .value_delimiter(',')
+            .action(ArgAction::Append)
+            .help("Allow network requests, to any or listed hosts"),
+        Arg::new("allow-run")
+            .long("allow-run")
+            .action(ArgAction::SetTrue)
+            .help("Allow spawning processes"),
+        Arg::new("allow-env")
+            .long("allow-env")
+            .action(ArgAction::SetTrue)
+            .help("Allow reading and setting environment variables"),
+        Arg::new("allow-ffi")
+            .long("allow-ffi")
+            .action(ArgAction::SetTrue)
+            .help("Allow loading foreign libraries"),
+        Arg::new("allow-all")
+            .short('A')
+            .long("allow-all")
+            .action(ArgAction::SetTrue)
+            .help("Allow everything"),
+        Arg::new("profile")
+            .long("profile")
+            .value_name("OUT.folded")
+            .help("Write collapsed stacks profile, and print top fn-s"),
+        Arg::new("coverage")
+            .long("coverage")
+            .value_name("OUT.lcov")
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("lcov.info")
+            .help("Write lcov line coverage report"),
+        Arg::new("heap-snapshot")
+            .long("heap-snapshot")
+            .value_name("OUT.json")
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("heap.json")
+            .help("Write heap snapshot json on exit"),
+        Arg::new("file").required(true),
+        Arg::new("args").action(ArgAction::Append),
+    ]
+}
 
-    let matches = parser.get_matches();
+/// Parses grant of permission flag,
+/// flag without values grants everything
+fn grant<T: From<String>>(matches: &ArgMatches, flag: &str) -> watt::Grant<T> {
+    match matches.get_many::<String>(flag) {
+        Some(values) if values.len() == 0 => watt::Grant::All,
+        Some(values) => watt::Grant::Only(values.map(|x| T::from(x.clone())).collect()),
+        None => watt::Grant::Denied,
+    }
+}
 
+/// Runs file with parsed run args
+#[allow(unsafe_op_in_unsafe_fn)]
+unsafe fn run(matches: &ArgMatches) {
     let file = matches.get_one::<String>("file").unwrap();
 
+    // execution limits
+    let limits = watt::VmLimits {
+        max_instructions: matches.get_one::<u64>("max-instructions").copied(),
+        timeout: matches
+            .get_one::<u64>("timeout")
+            .map(|ms| std::time::Duration::from_millis(*ms)),
+        max_stack: matches.get_one::<usize>("max-stack").copied(),
+        max_objects: matches.get_one::<usize>("max-objects").copied(),
+        max_bytes: matches.get_one::<usize>("max-bytes").copied(),
+    };
+
+    // gc mode
+    let gc_mode = match matches.get_one::<String>("gc-mode").map(|x| x.as_str()) {
+        Some("generational") => watt::GcMode::Generational {
+            nursery: matches
+                .get_one::<usize>("gc-nursery")
+                .copied()
+                .unwrap_or(watt::GC_NURSERY),
+        },
+        _ => watt::GcMode::MarkSweep,
+    };
+
+    // permissions
+    let permissions = if matches.get_flag("allow-all") {
+        watt::Permissions::all()
+    } else {
+        watt::Permissions {
+            read: grant(matches, "allow-read"),
+            write: grant(matches, "allow-write"),
+            net: grant(matches, "allow-net"),
+            run: matches.get_flag("allow-run"),
+            env: matches.get_flag("allow-env"),
+            ffi: matches.get_flag("allow-ffi"),
+        }
+    };
+
     // run executor with parsed args
     watt::run(
         file.into(),
@@ -72,7 +194,12 @@ pub unsafe fn cli() {
         matches
             .get_one::<usize>("gc-threshold-grow-factor")
             .copied(),
+        gc_mode,
+        matches.get_one::<usize>("max-call-depth").copied(),
+        limits,
+        permissions,
         matches.get_flag("gc-debug"),
+        matches.get_flag("gc-stats"),
         matches.get_flag("lexer-debug"),
         matches.get_flag("ast-debug"),
         matches.get_flag("opcodes-debug"),
@@ -80,5 +207,37 @@ pub unsafe fn cli() {
         matches.get_flag("parse-bench"),
         matches.get_flag("compile-bench"),
         matches.get_flag("runtime-bench"),
+        matches.get_one::<String>("profile").map(|x| x.into()),
+        matches.get_one::<String>("coverage").map(|x| x.into()),
+        matches.get_one::<String>("heap-snapshot").map(|x| x.into()),
     )
 }
+
+/// Run cli
+#[allow(unsafe_op_in_unsafe_fn)]
+pub unsafe fn cli() {
+    // Command-line parser
+    let parser = clap::Command::new("watt")
+        .author("Watt developers.")
+        .about("The Watt interpreter.")
+        .args(run_args())
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            clap::Command::new("run")
+                .about("Runs a Watt file.")
+                .args(run_args()),
+        )
+        .subcommand(
+            clap::Command::new("debug").about("Runs debug adapter, speaking DAP over stdio."),
+        );
+
+    let matches = parser.get_matches();
+
+    // running subcommand, or file
+    match matches.subcommand() {
+        Some(("run", run_matches)) => run(run_matches),
+        Some(("debug", _)) => watt_dap::debug(),
+        _ => run(&matches),
+    }
+}
@@ -2,6 +2,26 @@
 use crate::address::Address;
 use crate::colors;
 use std::borrow::Cow;
+use std::cell::Cell;
+use std::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Exit code of limit errors
+pub const LIMIT_EXIT_CODE: i32 = 3;
+
+/// Error kind
+///
+/// `Panic` is a regular runtime error,
+/// `Limit` is raised, when script exceeds
+/// execution limits of vm, `Permission` is
+/// raised, when script lacks permission
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ErrorKind {
+    Panic,
+    Limit,
+    Permission,
+}
 
 /// Error
 #[derive(Debug, Clone)]
@@ -9,6 +29,12 @@ pub struct Error {
     addr: Address,
     text: Cow<'static, str>,
     hint: Cow<'static, str>,
+    kind: ErrorKind,
+}
+
+// errors of this thread are captured, instead of ending process
+thread_local! {
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
 }
 
 /// Error macro that panics error
@@ -27,6 +53,7 @@ impl Error {
             addr,
             text: Cow::Borrowed(text),
             hint: Cow::Borrowed(hint),
+            kind: ErrorKind::Panic,
         }
     }
 
@@ -36,6 +63,7 @@ impl Error {
             addr,
             text: Cow::Owned(text),
             hint: Cow::Owned(hint),
+            kind: ErrorKind::Panic,
         }
     }
 
@@ -45,6 +73,7 @@ impl Error {
             addr,
             text: Cow::Owned(text),
             hint: Cow::Borrowed(hint),
+            kind: ErrorKind::Panic,
         }
     }
 
@@ -54,16 +83,56 @@ impl Error {
             addr,
             text: Cow::Borrowed(text),
             hint: Cow::Owned(hint),
+            kind: ErrorKind::Panic,
         }
     }
 
-    /// Panic error
+    /// New limit error with owned text and own hint
+    pub fn limit(addr: Address, text: String, hint: String) -> Self {
+        Error {
+            addr,
+            text: Cow::Owned(text),
+            hint: Cow::Owned(hint),
+            kind: ErrorKind::Limit,
+        }
+    }
+
+    /// New permission error with owned text and own hint
+    pub fn permission(addr: Address, text: String, hint: String) -> Self {
+        Error {
+            addr,
+            text: Cow::Owned(text),
+            hint: Cow::Owned(hint),
+            kind: ErrorKind::Permission,
+        }
+    }
+
+    /// Error kind
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Error address
+    pub fn addr(&self) -> &Address {
+        &self.addr
+    }
+
+    /// Error text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Error hint
+    pub fn hint(&self) -> &str {
+        &self.hint
+    }
+
+    /// Renders error box
     ///
-    /// Prints error information,
-    /// address, and then
-    /// ends process
+    /// `label` is printed in the
+    /// header with given `color`
     ///
-    pub fn panic(&self) -> ! {
+    fn render(&self, label: &str, color: &str) -> String {
         // file_name and line_text
         let file_name = self
             .addr
@@ -74,44 +143,103 @@ impl Error {
             .unwrap_or(String::from("-"));
         let line_text = self.addr.get_line().unwrap_or(String::from("-"));
 
-        // print
-        println!(
-            "┌─ {red}panic:{reset} {text}",
-            red = colors::RedColor,
+        // render
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "┌─ {color}{label}{reset} {text}",
             reset = colors::ResetColor,
             text = self.text,
         );
-        println!("│");
-        println!(
+        let _ = writeln!(out, "│");
+        let _ = writeln!(
+            out,
             "│ {cyan}{file_name}{reset}:",
             cyan = colors::CyanColor,
             reset = colors::ResetColor,
         );
-        println!(
+        let _ = writeln!(
+            out,
             "│ {gray}{line}{reset} {text}",
             line = self.addr.line,
             text = line_text,
             gray = colors::WhiteColor,
             reset = colors::ResetColor,
         );
-        println!(
-            "│ {space:spaces$}{red}{arrows}{reset}",
+        let _ = writeln!(
+            out,
+            "│ {space:spaces$}{color}{arrows}{reset}",
             space = " ",
             spaces = self.addr.span.start as usize + self.addr.line.to_string().len(),
             arrows = "^".repeat((self.addr.span.end - self.addr.span.start) as usize + 1),
-            red = colors::RedColor,
             reset = colors::ResetColor,
         );
-        println!("│");
-        println!(
+        let _ = writeln!(out, "│");
+        let _ = writeln!(
+            out,
             "│ {cyan}hint{reset}: {hint}",
             hint = self.hint,
             cyan = colors::CyanColor,
             reset = colors::ResetColor
         );
-        println!("{}", colors::ResetColor);
+        let _ = writeln!(out, "{}", colors::ResetColor);
+        out
+    }
+
+    /// Panic error
+    ///
+    /// Prints error information,
+    /// address, and then
+    /// ends process
+    ///
+    pub fn panic(&self) -> ! {
+        // unwinding to `capture`, if error is captured
+        if CAPTURING.get() {
+            panic::resume_unwind(Box::new(self.clone()));
+        }
+
+        // label
+        let label = match self.kind {
+            ErrorKind::Panic => "panic:",
+            ErrorKind::Limit => "limit exceeded:",
+            ErrorKind::Permission => "permission denied:",
+        };
+
+        // print
+        print!("{}", self.render(label, colors::RedColor));
 
         // exit process
-        std::process::exit(1);
+        match self.kind {
+            ErrorKind::Panic | ErrorKind::Permission => std::process::exit(1),
+            ErrorKind::Limit => std::process::exit(LIMIT_EXIT_CODE),
+        }
+    }
+
+    /// Captures error
+    ///
+    /// Runs `run`, errors raised by it on this
+    /// thread are returned, instead of ending process
+    ///
+    pub fn capture<T>(run: impl FnOnce() -> T) -> Result<T, Error> {
+        let previous = CAPTURING.replace(true);
+        let result = panic::catch_unwind(AssertUnwindSafe(run));
+        CAPTURING.set(previous);
+        match result {
+            Ok(value) => Ok(value),
+            Err(payload) => match payload.downcast::<Error>() {
+                Ok(error) => Err(*error),
+                Err(payload) => panic::resume_unwind(payload),
+            },
+        }
+    }
+
+    /// Warn error
+    ///
+    /// Prints error information
+    /// as warning to stderr, process
+    /// continues running
+    ///
+    pub fn warn(&self) {
+        eprint!("{}", self.render("warning:", colors::YellowColor));
     }
 }
@@ -0,0 +1,364 @@
+// imports
+use crate::protocol::Writer;
+use serde_json::{Value as Json, json};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use watt_vm::debugger::{DebugFrame, DebugHandler, Resume, StopReason};
+use watt_vm::values::{Unboxed, Value};
+use watt_vm::vm::VM;
+
+/// Thread id, vm is single threaded
+pub const THREAD_ID: u64 = 1;
+
+/// Variables reference
+///
+/// scope of frame, or value,
+/// which fields can be expanded
+///
+#[derive(Debug, Clone, Copy)]
+enum Reference {
+    Locals(usize),
+    Closure(usize),
+    Globals,
+    Value(Value),
+}
+
+/// Dap debug handler
+///
+/// handles requests, that come while
+/// vm is running or stopped
+///
+pub struct DapHandler {
+    requests: Receiver<Json>,
+    writer: Writer,
+    references: Vec<Reference>,
+}
+/// Dap handler implementation
+impl DapHandler {
+    /// New dap handler
+    pub fn new(requests: Receiver<Json>, writer: Writer) -> DapHandler {
+        DapHandler {
+            requests,
+            writer,
+            references: Vec::new(),
+        }
+    }
+
+    /// Adds reference, returns it's id
+    fn reference(&mut self, reference: Reference) -> usize {
+        self.references.push(reference);
+        self.references.len()
+    }
+
+    /// Handles request,
+    /// returns resume command, if vm should
+    /// continue, `stopped` tells vm is stopped
+    unsafe fn handle(&mut self, vm: &mut VM, request: &Json, stopped: bool) -> Option<Resume> {
+        let arguments = &request["arguments"];
+        match request["command"].as_str().unwrap_or_default() {
+            "threads" => {
+                self.writer.response(
+                    request,
+                    json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+                );
+                None
+            }
+            "setBreakpoints" => {
+                let (path, lines, body) = breakpoints(arguments);
+                if let Some(debugger) = &mut vm.debugger {
+                    debugger.set_breakpoints(&path, lines);
+                }
+                self.writer.response(request, body);
+                None
+            }
+            "stackTrace" if stopped => {
+                let frames = self.stack_trace(vm);
+                let total = frames.len();
+                self.writer.response(
+                    request,
+                    json!({ "stackFrames": frames, "totalFrames": total }),
+                );
+                None
+            }
+            "scopes" if stopped => {
+                let frame = arguments["frameId"].as_u64().unwrap_or(0) as usize;
+                let locals = self.reference(Reference::Locals(frame));
+                let closure = self.reference(Reference::Closure(frame));
+                let globals = self.reference(Reference::Globals);
+                self.writer.response(
+                    request,
+                    json!({ "scopes": [
+                        { "name": "Locals", "variablesReference": locals, "expensive": false },
+                        { "name": "Closure", "variablesReference": closure, "expensive": false },
+                        { "name": "Globals", "variablesReference": globals, "expensive": true },
+                    ]}),
+                );
+                None
+            }
+            "variables" if stopped => {
+                let id = arguments["variablesReference"].as_u64().unwrap_or(0) as usize;
+                let variables = self.variables(vm, id);
+                self.writer
+                    .response(request, json!({ "variables": variables }));
+                None
+            }
+            "evaluate" if stopped => {
+                // evaluates variable name in frame
+                let name = arguments["expression"].as_str().unwrap_or_default();
+                let frame = arguments["frameId"].as_u64().unwrap_or(0) as usize;
+                let found = frame_of(vm, frame).and_then(|frame| {
+                    vm.debug_locals(&frame)
+                        .into_iter()
+                        .chain(vm.debug_closure(&frame))
+                        .chain(VM::debug_fields(vm.globals))
+                        .find(|(variable, _)| variable == name)
+                });
+                match found {
+                    Some((_, value)) => {
+                        let variable = self.variable(name.to_string(), value);
+                        self.writer.response(
+                            request,
+                            json!({
+                                "result": variable["value"],
+                                "variablesReference": variable["variablesReference"],
+                            }),
+                        );
+                    }
+                    None => self
+                        .writer
+                        .error(request, &format!("{name} is not defined.")),
+                }
+                None
+            }
+            "continue" => {
+                self.writer
+                    .response(request, json!({ "allThreadsContinued": true }));
+                Some(Resume::Continue)
+            }
+            "next" => {
+                self.writer.response(request, json!({}));
+                Some(Resume::StepOver)
+            }
+            "stepIn" => {
+                self.writer.response(request, json!({}));
+                Some(Resume::StepIn)
+            }
+            "stepOut" => {
+                self.writer.response(request, json!({}));
+                Some(Resume::StepOut)
+            }
+            "pause" => {
+                self.writer.response(request, json!({}));
+                if let Some(debugger) = &mut vm.debugger {
+                    debugger.pause();
+                }
+                None
+            }
+            "disconnect" | "terminate" => {
+                self.writer.response(request, json!({}));
+                std::process::exit(0);
+            }
+            _ => {
+                self.writer.error(request, "unsupported request.");
+                None
+            }
+        }
+    }
+
+    /// Stack frames, innermost first,
+    /// frame id is index in vm debugger frames
+    unsafe fn stack_trace(&self, vm: &VM) -> Vec<Json> {
+        let Some(debugger) = &vm.debugger else {
+            return Vec::new();
+        };
+        debugger
+            .frames()
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(id, frame)| {
+                let source = match &frame.addr.file {
+                    Some(file) => {
+                        let path = std::fs::canonicalize(file).unwrap_or(file.clone());
+                        json!({
+                            "name": file.file_name().map(|x| x.to_string_lossy()),
+                            "path": path,
+                        })
+                    }
+                    None => Json::Null,
+                };
+                json!({
+                    "id": id,
+                    "name": frame.name,
+                    "source": source,
+                    "line": frame.addr.line,
+                    "column": frame.addr.span.start as u64 + 1,
+                })
+            })
+            .collect()
+    }
+
+    /// Variables by reference
+    unsafe fn variables(&mut self, vm: &VM, id: usize) -> Vec<Json> {
+        let Some(reference) = id
+            .checked_sub(1)
+            .and_then(|index| self.references.get(index).copied())
+        else {
+            return Vec::new();
+        };
+
+        // collecting variables
+        let variables: Vec<(String, Value)> = match reference {
+            Reference::Locals(frame) => frame_of(vm, frame)
+                .map(|frame| vm.debug_locals(&frame))
+                .unwrap_or_default(),
+            Reference::Closure(frame) => frame_of(vm, frame)
+                .map(|frame| vm.debug_closure(&frame))
+                .unwrap_or_default(),
+            Reference::Globals => VM::debug_fields(vm.globals),
+            Reference::Value(value) => match value.unbox() {
+                Unboxed::Instance(instance) => VM::debug_fields((*instance).fields),
+                Unboxed::Unit(unit) => VM::debug_fields((*unit).fields),
+                Unboxed::List(list) => (*list)
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| (index.to_string(), *value))
+                    .collect(),
+                _ => Vec::new(),
+            },
+        };
+
+        variables
+            .into_iter()
+            .map(|(name, value)| self.variable(name, value))
+            .collect()
+    }
+
+    /// Variable json, instances, units
+    /// and lists are expandable
+    unsafe fn variable(&mut self, name: String, value: Value) -> Json {
+        let reference = match value.unbox() {
+            Unboxed::Instance(_) | Unboxed::Unit(_) | Unboxed::List(_) => {
+                self.reference(Reference::Value(value))
+            }
+            _ => 0,
+        };
+        json!({
+            "name": name,
+            "value": render(value),
+            "type": kind(value),
+            "variablesReference": reference,
+        })
+    }
+}
+
+/// Debug handler implementation
+impl DebugHandler for DapHandler {
+    unsafe fn poll(&mut self, vm: &mut VM) -> bool {
+        loop {
+            match self.requests.try_recv() {
+                Ok(request) => {
+                    if request["command"] == "pause" {
+                        self.writer.response(&request, json!({}));
+                        return true;
+                    }
+                    self.handle(vm, &request, false);
+                }
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => std::process::exit(0),
+            }
+        }
+    }
+
+    unsafe fn stopped(&mut self, vm: &mut VM, reason: StopReason) -> Resume {
+        // stopped event
+        self.references.clear();
+        let reason = match reason {
+            StopReason::Entry => "entry",
+            StopReason::Breakpoint => "breakpoint",
+            StopReason::Step => "step",
+            StopReason::Pause => "pause",
+        };
+        self.writer.event(
+            "stopped",
+            json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+        );
+
+        // handling requests until resume
+        loop {
+            let Ok(request) = self.requests.recv() else {
+                std::process::exit(0);
+            };
+            if let Some(resume) = self.handle(vm, &request, true) {
+                return resume;
+            }
+        }
+    }
+}
+
+/// Parses `setBreakpoints` arguments,
+/// returns source path, lines and response body
+pub fn breakpoints(arguments: &Json) -> (PathBuf, Vec<u64>, Json) {
+    let path = PathBuf::from(arguments["source"]["path"].as_str().unwrap_or_default());
+    let lines: Vec<u64> = arguments["breakpoints"]
+        .as_array()
+        .map(|breakpoints| {
+            breakpoints
+                .iter()
+                .filter_map(|breakpoint| breakpoint["line"].as_u64())
+                .collect()
+        })
+        .unwrap_or_default();
+    let body = json!({
+        "breakpoints": lines
+            .iter()
+            .map(|line| json!({ "verified": true, "line": line }))
+            .collect::<Vec<Json>>(),
+    });
+    (path, lines, body)
+}
+
+/// Frame by id
+fn frame_of(vm: &VM, id: usize) -> Option<DebugFrame> {
+    vm.debugger
+        .as_ref()
+        .and_then(|debugger| debugger.frames().get(id).cloned())
+}
+
+/// Renders value
+unsafe fn render(value: Value) -> String {
+    match value.unbox() {
+        Unboxed::String(string) => format!("'{}'", *string),
+        Unboxed::Instance(instance) => format!("{} instance", (*(*instance).t).name.name),
+        Unboxed::Unit(unit) => format!("unit {}", (*unit).name.name),
+        Unboxed::Fn(function) => format!("fn {}", (*function).name.name),
+        Unboxed::Native(native) => format!("native {}", (*native).name.name),
+        Unboxed::Type(t) => format!("type {}", (*t).name.name),
+        Unboxed::Trait(t) => format!("trait {}", (*t).name.name),
+        Unboxed::List(list) => format!("list ({} items)", (*list).len()),
+        Unboxed::Null => "null".to_string(),
+        _ => format!("{value:?}"),
+    }
+}
+
+/// Value kind name
+fn kind(value: Value) -> &'static str {
+    match value.unbox() {
+        Unboxed::Float(_) => "float",
+        Unboxed::Int(_) => "int",
+        Unboxed::BigInt(_) => "bigint",
+        Unboxed::String(_) => "string",
+        Unboxed::Bool(_) => "bool",
+        Unboxed::Type(_) => "type",
+        Unboxed::Fn(_) => "fn",
+        Unboxed::Native(_) => "native",
+        Unboxed::Instance(_) => "instance",
+        Unboxed::Unit(_) => "unit",
+        Unboxed::Trait(_) => "trait",
+        Unboxed::List(_) => "list",
+        Unboxed::Any(_) => "any",
+        Unboxed::Enum(_) => "enum",
+        Unboxed::Variant(_) => "variant",
+        Unboxed::Null => "null",
+    }
+}
@@ -0,0 +1,212 @@
+// fixed 24 edition warnings
+#![allow(unsafe_op_in_unsafe_fn)]
+
+// modules
+pub mod handler;
+pub mod output;
+pub mod protocol;
+
+// imports
+use crate::handler::DapHandler;
+use crate::protocol::Writer;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use watt_common::{address::Address, error, errors::Error};
+use watt_vm::debugger::Debugger;
+use watt_vm::permissions::{Grant, Permissions};
+use watt_vm::profiler::ROOT_FRAME;
+use watt_vm::vm::{VM, VmSettings};
+
+/// Launch configuration
+struct Launch {
+    program: PathBuf,
+    stop_on_entry: bool,
+    permissions: Permissions,
+}
+
+/// Permissions from launch arguments
+///
+/// mirrors cli flags: `allowAll`, `allowRun`, `allowEnv`,
+/// `allowFfi` are booleans, `allowRead`, `allowWrite`, `allowNet`
+/// are `true` or list of paths and hosts, everything
+/// is denied by default
+///
+fn permissions(arguments: &serde_json::Value) -> Permissions {
+    // grant from `true` or list
+    fn grant<T: From<String>>(value: &serde_json::Value) -> Grant<T> {
+        match value {
+            serde_json::Value::Bool(true) => Grant::All,
+            serde_json::Value::Array(items) => Grant::Only(
+                items
+                    .iter()
+                    .filter_map(|x| x.as_str())
+                    .map(|x| T::from(x.to_string()))
+                    .collect(),
+            ),
+            _ => Grant::Denied,
+        }
+    }
+    // flag
+    let flag = |name: &str| arguments[name].as_bool().unwrap_or(false);
+    if flag("allowAll") {
+        Permissions::all()
+    } else {
+        Permissions {
+            read: grant(&arguments["allowRead"]),
+            write: grant(&arguments["allowWrite"]),
+            net: grant(&arguments["allowNet"]),
+            run: flag("allowRun"),
+            env: flag("allowEnv"),
+            ffi: flag("allowFfi"),
+        }
+    }
+}
+
+/// Runs debug adapter
+///
+/// speaks debug adapter protocol over stdio,
+/// waits for `launch` and `configurationDone`,
+/// then runs program with debugger
+///
+/// # Safety
+///
+/// should be called once, stdout is
+/// redirected to debug adapter
+///
+pub unsafe fn debug() {
+    // dap output and program output
+    let (out, program_output) = output::redirect_stdout();
+    let writer = Writer::new(out);
+    let forwarding = output::forward(program_output, writer.clone());
+
+    // reading requests
+    let (sender, requests) = mpsc::channel();
+    std::thread::spawn(move || {
+        let stdin = std::io::stdin();
+        let mut reader = stdin.lock();
+        while let Some(message) = protocol::read_message(&mut reader) {
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    // configuration
+    let mut launch: Option<Launch> = None;
+    let mut breakpoints: Vec<(PathBuf, Vec<u64>)> = Vec::new();
+    loop {
+        let Ok(request) = requests.recv() else {
+            return;
+        };
+        let arguments = &request["arguments"];
+        match request["command"].as_str().unwrap_or_default() {
+            "initialize" => {
+                writer.response(
+                    &request,
+                    json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsEvaluateForHovers": true,
+                    }),
+                );
+                writer.event("initialized", json!({}));
+            }
+            "launch" => {
+                // working directory
+                if let Some(cwd) = arguments["cwd"].as_str()
+                    && let Err(e) = std::env::set_current_dir(cwd)
+                {
+                    writer.error(&request, &format!("invalid cwd {cwd}: {e}"));
+                    continue;
+                }
+                // program
+                match arguments["program"].as_str() {
+                    Some(program) => {
+                        launch = Some(Launch {
+                            program: PathBuf::from(program),
+                            stop_on_entry: arguments["stopOnEntry"].as_bool().unwrap_or(false),
+                            permissions: permissions(arguments),
+                        });
+                        writer.response(&request, json!({}));
+                    }
+                    None => writer.error(&request, "program is not specified."),
+                }
+            }
+            "setBreakpoints" => {
+                let (path, lines, body) = handler::breakpoints(arguments);
+                breakpoints.push((path, lines));
+                writer.response(&request, body);
+            }
+            "setExceptionBreakpoints" => writer.response(&request, json!({})),
+            "threads" => writer.response(
+                &request,
+                json!({ "threads": [{ "id": handler::THREAD_ID, "name": "main" }] }),
+            ),
+            "configurationDone" => {
+                writer.response(&request, json!({}));
+                if launch.is_some() {
+                    break;
+                }
+            }
+            "disconnect" | "terminate" => {
+                writer.response(&request, json!({}));
+                return;
+            }
+            _ => writer.error(&request, "unsupported request before launch."),
+        }
+    }
+    let launch = launch.unwrap();
+
+    // compiling program
+    let code = watt::read_file(None, &launch.program);
+    let tokens = watt::lex(
+        &launch.program,
+        &code.chars().collect::<Vec<char>>(),
+        false,
+        false,
+    );
+    let ast = watt::parse(&launch.program, tokens.unwrap(), false, false, &None);
+    let modules = watt::Modules::default();
+    let analyzed = watt::analyze(ast, &modules);
+    let chunk = watt::compile(&analyzed, &modules, false, false);
+
+    // debugger
+    let entry = launch.stop_on_entry.then_some(launch.program.as_path());
+    let mut debugger = Debugger::new(Box::new(DapHandler::new(requests, writer.clone())), entry);
+    for (path, lines) in breakpoints {
+        debugger.set_breakpoints(&path, lines);
+    }
+
+    // vm, with permissions from launch arguments
+    let mut vm = VM::new(VmSettings::new(
+        watt::GC_THRESHOLD,
+        watt::GC_THRESHOLD_GROW_FACTOR,
+        watt::GcMode::default(),
+        false,
+        watt::MAX_CALL_DEPTH,
+        watt::VmLimits::default(),
+        launch.permissions,
+    ));
+    debugger.enter(ROOT_FRAME.to_string(), &Address::unknown(), vm.globals);
+    vm.debugger = Some(debugger);
+    vm.loader = Some(watt::load_module);
+
+    // running
+    if let Err(e) = vm
+        .run(&chunk, vm.globals)
+        .and_then(|_| vm.run_tasks(vm.globals))
+        .and_then(|_| vm.finalize_all(vm.globals))
+    {
+        error!(Error::own_text(
+            Address::unknown(),
+            format!("control flow leak: {e:?}"),
+            "report this error to the developer."
+        ));
+    }
+    vm.cleanup();
+
+    // finishing
+    output::finish(forwarding);
+    writer.event("exited", json!({ "exitCode": 0 }));
+    writer.event("terminated", json!({}));
+}
@@ -0,0 +1,79 @@
+// imports
+use crate::protocol::Writer;
+use serde_json::json;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::thread::JoinHandle;
+
+/// Redirects program stdout
+///
+/// stdout is the dap channel, so natives
+/// output goes to a pipe instead, returns
+/// the original stdout and the pipe reader
+///
+#[cfg(unix)]
+pub fn redirect_stdout() -> (Box<dyn Write + Send>, Option<File>) {
+    use std::os::fd::FromRawFd;
+
+    unsafe {
+        let original = libc::dup(libc::STDOUT_FILENO);
+        let mut fds = [0; 2];
+        if original < 0 || libc::pipe(fds.as_mut_ptr()) != 0 {
+            return (Box::new(std::io::stdout()), None);
+        }
+        libc::dup2(fds[1], libc::STDOUT_FILENO);
+        libc::close(fds[1]);
+        (
+            Box::new(File::from_raw_fd(original)),
+            Some(File::from_raw_fd(fds[0])),
+        )
+    }
+}
+
+/// Redirects program stdout
+///
+/// not supported on this platform,
+/// program output is written as is
+///
+#[cfg(not(unix))]
+pub fn redirect_stdout() -> (Box<dyn Write + Send>, Option<File>) {
+    (Box::new(std::io::stdout()), None)
+}
+
+/// Forwards program output as `output` events
+pub fn forward(reader: Option<File>, writer: Writer) -> Option<JoinHandle<()>> {
+    let mut reader = reader?;
+    Some(std::thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        while let Ok(read) = reader.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            writer.event(
+                "output",
+                json!({
+                    "category": "stdout",
+                    "output": String::from_utf8_lossy(&buffer[..read]),
+                }),
+            );
+        }
+    }))
+}
+
+/// Flushes program output and closes
+/// redirected stdout, then waits for
+/// forwarding to finish
+pub fn finish(handle: Option<JoinHandle<()>>) {
+    let _ = std::io::stdout().flush();
+    #[cfg(unix)]
+    unsafe {
+        let null = libc::open(c"/dev/null".as_ptr(), libc::O_WRONLY);
+        if null >= 0 {
+            libc::dup2(null, libc::STDOUT_FILENO);
+            libc::close(null);
+        }
+    }
+    if let Some(handle) = handle {
+        let _ = handle.join();
+    }
+}
@@ -0,0 +1,100 @@
+// imports
+use serde_json::{Value as Json, json};
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+
+/// Reads dap message
+///
+/// message is `Content-Length` header,
+/// empty line and json body, returns
+/// none on eof or invalid message
+///
+pub fn read_message(reader: &mut impl BufRead) -> Option<Json> {
+    // headers
+    let mut length: Option<usize> = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+
+    // body
+    let mut body = vec![0; length?];
+    reader.read_exact(&mut body).ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+/// Writer inner
+struct WriterInner {
+    out: Box<dyn Write + Send>,
+    seq: u64,
+}
+
+/// Dap messages writer
+///
+/// shared between adapter and
+/// program output forwarding thread
+///
+#[derive(Clone)]
+pub struct Writer {
+    inner: Arc<Mutex<WriterInner>>,
+}
+/// Writer implementation
+impl Writer {
+    /// New writer
+    pub fn new(out: Box<dyn Write + Send>) -> Writer {
+        Writer {
+            inner: Arc::new(Mutex::new(WriterInner { out, seq: 0 })),
+        }
+    }
+
+    /// Sends message with next seq
+    fn send(&self, mut message: Json) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.seq += 1;
+        message["seq"] = json!(inner.seq);
+
+        let body = message.to_string();
+        let _ = write!(inner.out, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let _ = inner.out.flush();
+    }
+
+    /// Sends successful response to request
+    pub fn response(&self, request: &Json, body: Json) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }));
+    }
+
+    /// Sends failed response to request
+    pub fn error(&self, request: &Json, message: &str) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }));
+    }
+
+    /// Sends event
+    pub fn event(&self, event: &str, body: Json) {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }));
+    }
+}
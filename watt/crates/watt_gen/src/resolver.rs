@@ -2,6 +2,7 @@
 use core::cell::RefCell;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::rc::Rc;
 use watt_analyze::analyzer::Analyzer;
 use watt_ast::{ast::Node, import::Import};
 use watt_common::{
@@ -13,6 +14,15 @@ use watt_common::{
 use watt_lex::lexer::Lexer;
 use watt_parse::parser::Parser;
 
+/// Parsed modules
+///
+/// resolved modules by file and full name prefix,
+/// shared by resolvers of type checker and compiler,
+/// so each module is read and parsed once
+///
+#[derive(Default, Clone)]
+pub struct Modules(Rc<RefCell<HashMap<(String, String), Node>>>);
+
 /// Imports resolver structure
 ///
 /// Contains current imported files, contains
@@ -23,6 +33,7 @@ pub struct ImportsResolver<'import_key, 'import_path> {
     imported: RefCell<Vec<String>>,
     libraries: HashMap<&'import_key str, &'import_path str>,
     builtins: Vec<String>,
+    modules: Modules,
 }
 /// Import resolver implementation
 /// todo: add could not resolve error instead of file not found
@@ -30,6 +41,11 @@ pub struct ImportsResolver<'import_key, 'import_path> {
 impl<'import_key, 'import_path> ImportsResolver<'import_key, 'import_path> {
     /// New import resolver
     pub fn new() -> Self {
+        Self::with_modules(Modules::default())
+    }
+
+    /// New import resolver, reusing parsed modules
+    pub fn with_modules(modules: Modules) -> Self {
         ImportsResolver {
             imported: RefCell::new(vec![]),
             libraries: HashMap::from([
@@ -47,8 +63,11 @@ impl<'import_key, 'import_path> ImportsResolver<'import_key, 'import_path> {
                 ("std.json", "./libs/std/std_json.wt"),
                 ("std.ffi", "./libs/std/std_ffi.wt"),
                 ("std.net", "./libs/std/std_net.wt"),
+                ("std.weak", "./libs/std/std_weak.wt"),
+                ("std.thread", "./libs/std/std_thread.wt"),
             ]),
             builtins: vec!["./libs/base.wt".to_string()],
+            modules,
         }
     }
 
@@ -106,6 +125,12 @@ impl<'import_key, 'import_path> ImportsResolver<'import_key, 'import_path> {
             .clone()
             .map_or(delete_extension(file_name), |s| s);
 
+        // already parsed module
+        let key = (file.to_string(), full_name_prefix.clone());
+        if let Some(node) = self.modules.0.borrow().get(&key) {
+            return node.clone();
+        }
+
         // reading
         let code = match fs::read_file(addr.clone(), &path) {
             Ok(code) => code,
@@ -141,7 +166,7 @@ impl<'import_key, 'import_path> ImportsResolver<'import_key, 'import_path> {
         Analyzer::new().analyze(&ast);
 
         // getting necessary nodes
-        match ast {
+        let module = match ast {
             Node::Block { mut body } => {
                 let mut new_body: Vec<Node> = vec![];
                 for node in body.drain(..) {
@@ -151,6 +176,7 @@ impl<'import_key, 'import_path> ImportsResolver<'import_key, 'import_path> {
                         | Node::Type { .. }
                         | Node::Unit { .. }
                         | Node::Trait { .. }
+                        | Node::Enum { .. }
                         | Node::Import { .. } => {
                             new_body.push(node);
                         }
@@ -162,7 +188,9 @@ impl<'import_key, 'import_path> ImportsResolver<'import_key, 'import_path> {
             _ => {
                 panic!("parser returned non-block node as result. report to the developer.");
             }
-        }
+        };
+        self.modules.0.borrow_mut().insert(key, module.clone());
+        module
     }
 
     /// Resolving wrapper
@@ -183,3 +211,9 @@ impl<'import_key, 'import_path> ImportsResolver<'import_key, 'import_path> {
         }
     }
 }
+/// Import resolver default implementation
+impl Default for ImportsResolver<'_, '_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,26 +1,33 @@
 // import
-use crate::resolver::ImportsResolver;
+use crate::resolver::{ImportsResolver, Modules};
 use std::collections::VecDeque;
 use watt_ast::ast::*;
 use watt_ast::import::Import;
 use watt_common::{error, errors::Error};
 use watt_lex::tokens::Token;
-use watt_vm::bytecode::{Chunk, Opcode, OpcodeValue};
+use watt_vm::bytecode::{self, Chunk, InlineCache, MatchArm, Opcode, OpcodeValue};
 use watt_vm::values::*;
 
 /// Visitor
 pub struct CompileVisitor<'visitor> {
     opcodes: VecDeque<Vec<Opcode>>,
     resolver: ImportsResolver<'visitor, 'visitor>,
+    with_depth: usize,
 }
 /// Visitor implementation
 #[allow(unused_variables)]
 impl<'visitor> CompileVisitor<'visitor> {
     /// New visitor
     pub fn new() -> Self {
+        Self::with_modules(Modules::default())
+    }
+
+    /// New visitor, reusing parsed modules
+    pub fn with_modules(modules: Modules) -> Self {
         CompileVisitor {
             opcodes: VecDeque::new(),
-            resolver: ImportsResolver::new(),
+            resolver: ImportsResolver::with_modules(modules),
+            with_depth: 0,
         }
     }
 
@@ -36,6 +43,12 @@ impl<'visitor> CompileVisitor<'visitor> {
     }
 
     /// Compile node
+    ///
+    /// # Safety
+    ///
+    /// imported modules are resolved from
+    /// import paths, that should be valid
+    ///
     pub unsafe fn compile(&mut self, node: &Node) -> Chunk {
         self.push_chunk();
         self.visit_builtins();
@@ -43,6 +56,28 @@ impl<'visitor> CompileVisitor<'visitor> {
         Chunk::new(self.pop_chunk())
     }
 
+    /// Compile import, leaves only
+    /// definitions of imported module
+    ///
+    /// # Safety
+    ///
+    /// imported modules are resolved from
+    /// import paths, that should be valid
+    ///
+    pub unsafe fn compile_import(&mut self, import: Import) -> Chunk {
+        self.push_chunk();
+        self.visit_builtins();
+        self.visit_import(&vec![import]);
+        Chunk::new(self.pop_chunk())
+    }
+
+    /// Visit node into a separate chunk
+    fn visit_chunk(&mut self, node: &Node) -> Chunk {
+        self.push_chunk();
+        self.visit_node(node);
+        Chunk::new(self.pop_chunk())
+    }
+
     /// Push chunk to opcodes chunk VecDeque
     ///
     /// raises error if compile-visitor stack is empty
@@ -156,16 +191,20 @@ impl<'visitor> CompileVisitor<'visitor> {
                 params,
                 body,
                 make_closure,
+                kind,
+                ..
             } => {
-                self.visit_fn_decl(name, full_name, params, body, *make_closure);
+                self.visit_fn_decl(name, full_name, params, body, *make_closure, *kind);
             }
             Node::AnFnDeclaration {
                 location,
                 params,
                 body,
                 make_closure,
+                kind,
+                ..
             } => {
-                self.visit_an_fn_decl(location, params, body, *make_closure);
+                self.visit_an_fn_decl(location, params, body, *make_closure, *kind);
             }
             Node::Break { location } => {
                 self.visit_break(location);
@@ -209,6 +248,16 @@ impl<'visitor> CompileVisitor<'visitor> {
             Node::Ret { location, value } => {
                 self.visit_return(location, value);
             }
+            Node::Yield { location, value } => {
+                self.visit_yield(location, value);
+            }
+            Node::Await {
+                location,
+                value,
+                should_push,
+            } => {
+                self.visit_await(location, value, *should_push);
+            }
             Node::Null { location } => {
                 self.visit_null(location);
             }
@@ -235,15 +284,30 @@ impl<'visitor> CompileVisitor<'visitor> {
             } => {
                 self.visit_for(iterable, variable_name, body);
             }
+            Node::With {
+                variable_name,
+                value,
+                body,
+            } => {
+                self.visit_with(variable_name, value, body);
+            }
             Node::Block { body } => {
                 self.visit_block(body);
             }
             Node::Trait {
                 name,
                 full_name,
+                parents,
                 functions,
             } => {
-                self.visit_trait(name, full_name, functions);
+                self.visit_trait(name, full_name, parents, functions);
+            }
+            Node::Enum {
+                name,
+                full_name,
+                variants,
+            } => {
+                self.visit_enum(name, full_name, variants);
             }
             Node::ErrorPropagation {
                 location,
@@ -255,7 +319,29 @@ impl<'visitor> CompileVisitor<'visitor> {
             Node::Impls { value, trait_name } => {
                 self.visit_impls(value, trait_name);
             }
+            Node::Index {
+                location,
+                value,
+                key,
+                should_push,
+            } => {
+                self.visit_index(location, value, key, *should_push);
+            }
             Node::Range { location, from, to } => self.visit_range(location, from, to),
+            Node::Destructure {
+                location,
+                kind,
+                names,
+                value,
+                is_define,
+            } => self.visit_destructure(location, kind, names, value, *is_define),
+            Node::NamedArg { name, .. } => {
+                error!(Error::new(
+                    name.address.clone(),
+                    "named argument is not supported here.",
+                    "named arguments could be passed only to fn-s."
+                ));
+            }
         }
     }
 
@@ -414,16 +500,76 @@ impl<'visitor> CompileVisitor<'visitor> {
             has_previous = true;
         }
         // args chunk
-        self.push_chunk();
-        self.visit_block(args);
-        let args_chunk = self.pop_chunk();
+        let (args_chunk, named) = self.visit_args(args);
         // push call
         self.push_instr(Opcode::Call {
             addr: name.address.clone(),
             name: name.value.clone(),
-            args: Chunk::new(args_chunk),
+            args: args_chunk,
+            named,
             has_previous,
             should_push,
+            cache: InlineCache::new(),
+        });
+    }
+
+    /// Visit call args
+    ///
+    /// named args are pushed after positional
+    /// ones, returns args chunk and names
+    ///
+    fn visit_args(&mut self, args: &Vec<Node>) -> (Chunk, Vec<String>) {
+        let mut named: Vec<String> = Vec::new();
+        let mut last_named: Option<&Token> = None;
+        self.push_chunk();
+        for arg in args {
+            if let Node::NamedArg { name, value } = arg {
+                if named.contains(&name.value) {
+                    error!(Error::own_text(
+                        name.address.clone(),
+                        format!("argument {} is passed twice.", name.value),
+                        "pass each named argument once."
+                    ));
+                }
+                self.visit_node(value);
+                named.push(name.value.clone());
+                last_named = Some(name);
+            } else {
+                if let Some(last) = last_named {
+                    error!(Error::new(
+                        last.address.clone(),
+                        "positional argument after named argument.",
+                        "pass positional arguments first."
+                    ));
+                }
+                self.visit_node(arg);
+            }
+        }
+        (Chunk::new(self.pop_chunk()), named)
+    }
+
+    /// Visit tail call
+    ///
+    /// same as call, but vm reuses
+    /// the returning fn frame
+    ///
+    fn visit_tail_call(&mut self, previous: Option<&Node>, name: &Token, args: &Vec<Node>) {
+        // previous
+        let mut has_previous = false;
+        if let Some(prev) = previous {
+            self.visit_node(prev);
+            has_previous = true;
+        }
+        // args chunk
+        let (args_chunk, named) = self.visit_args(args);
+        // push tail call
+        self.push_instr(Opcode::TailCall {
+            addr: name.address.clone(),
+            name: name.value.clone(),
+            args: args_chunk,
+            named,
+            has_previous,
+            cache: InlineCache::new(),
         });
     }
 
@@ -432,17 +578,15 @@ impl<'visitor> CompileVisitor<'visitor> {
         &mut self,
         name: &Token,
         full_name: &Option<Token>,
-        parameters: &Vec<Token>,
+        parameters: &[Parameter],
         body: &Node,
         make_closure: bool,
+        kind: FnKind,
     ) {
         // full name
         let full_name = full_name.as_ref().map(|n| n.value.clone());
         // params
-        let mut params = Vec::with_capacity(parameters.len());
-        for param in parameters {
-            params.push(param.value.clone());
-        }
+        let params = self.visit_params(parameters);
         // body chunk
         self.push_chunk();
         self.visit_node(body);
@@ -470,10 +614,20 @@ impl<'visitor> CompileVisitor<'visitor> {
             full_name,
             params,
             make_closure,
+            kind: Self::visit_fn_kind(kind),
             body: Chunk::new(chunk),
         });
     }
 
+    /// Visit fn kind
+    fn visit_fn_kind(kind: FnKind) -> bytecode::FnKind {
+        match kind {
+            FnKind::Plain => bytecode::FnKind::Plain,
+            FnKind::Generator => bytecode::FnKind::Generator,
+            FnKind::Async => bytecode::FnKind::Async,
+        }
+    }
+
     /// Visit break
     fn visit_break(&mut self, location: &Token) {
         self.push_instr(Opcode::EndLoop {
@@ -525,8 +679,10 @@ impl<'visitor> CompileVisitor<'visitor> {
                     addr: location.address.clone(),
                     name: "add".to_string(),
                     args: Chunk::new(chunk),
+                    named: vec![],
                     has_previous: true,
                     should_push: false,
+                    cache: InlineCache::new(),
                 })
             }
         }
@@ -558,8 +714,10 @@ impl<'visitor> CompileVisitor<'visitor> {
                     addr: location.address.clone(),
                     name: "set".to_string(),
                     args: Chunk::new(chunk),
+                    named: vec![],
                     has_previous: true,
                     should_push: false,
+                    cache: InlineCache::new(),
                 })
             }
         }
@@ -591,13 +749,16 @@ impl<'visitor> CompileVisitor<'visitor> {
                     name: iterator_variable_name.clone(),
                     has_previous: false,
                     should_push: true,
+                    cache: InlineCache::new(),
                 },
                 Opcode::Call {
                     addr: variable_name.address.clone(),
                     name: "next".to_string(),
                     args: Chunk::new(vec![]),
+                    named: vec![],
                     has_previous: true,
                     should_push: true,
+                    cache: InlineCache::new(),
                 },
             ]),
             has_previous: false,
@@ -614,13 +775,16 @@ impl<'visitor> CompileVisitor<'visitor> {
                     name: iterator_variable_name.clone(),
                     has_previous: false,
                     should_push: true,
+                    cache: InlineCache::new(),
                 },
                 Opcode::Call {
                     addr: variable_name.address.clone(),
                     name: "has_next".to_string(),
                     args: Chunk::new(vec![]),
+                    named: vec![],
                     has_previous: true,
                     should_push: true,
+                    cache: InlineCache::new(),
                 },
             ]),
             body: Chunk::new(body_chunk),
@@ -642,6 +806,29 @@ impl<'visitor> CompileVisitor<'visitor> {
         })
     }
 
+    /// Visit with
+    fn visit_with(&mut self, variable_name: &Token, value: &Node, body: &Node) {
+        // value chunk
+        self.push_chunk();
+        self.visit_node(value);
+        let value_chunk = self.pop_chunk();
+        // body chunk, calls in it are not
+        // tail calls, because `drop` is called
+        // after the body
+        self.push_chunk();
+        self.with_depth += 1;
+        self.visit_node(body);
+        self.with_depth -= 1;
+        let body_chunk = self.pop_chunk();
+        // push with
+        self.push_instr(Opcode::With {
+            addr: variable_name.address.clone(),
+            name: variable_name.value.clone(),
+            value: Chunk::new(value_chunk),
+            body: Chunk::new(body_chunk),
+        });
+    }
+
     /// Visit match
     fn visit_match(
         &mut self,
@@ -650,63 +837,104 @@ impl<'visitor> CompileVisitor<'visitor> {
         cases: &Vec<MatchCase>,
         default: &Node,
     ) {
-        // result opcode
-        let mut if_op;
-        // default case
+        // matchable chunk
         self.push_chunk();
-        self.visit_node(default);
-        let body_chunk = self.pop_chunk();
-        if_op = Opcode::If {
-            addr: location.address.clone(),
-            cond: Chunk::of(Opcode::Push {
-                addr: location.address.clone(),
-                value: OpcodeValue::Bool(true),
-            }),
-            body: Chunk::new(body_chunk),
-            elif: None,
-        };
+        self.visit_node(matchable);
+        let matchable_chunk = self.pop_chunk();
         // compiling cases
+        let mut arms = Vec::new();
         for case in cases {
-            // logic chunk
-            self.push_chunk();
-            self.visit_node(&case.value);
-            self.visit_node(matchable);
-            self.push_instr(Opcode::Cond {
-                addr: location.address.clone(),
-                op: "==".to_string(),
-            });
-            let logical_chunk = self.pop_chunk();
-
+            // pattern
+            let pattern = self.visit_pattern(&case.pattern);
+            // guard chunk
+            let guard = case.guard.as_ref().map(|guard| self.visit_chunk(guard));
             // body chunk
             self.push_chunk();
             self.visit_node(&case.body);
             let body_chunk = self.pop_chunk();
+            arms.push(MatchArm::new(pattern, guard, Chunk::new(body_chunk)));
+        }
+        // default chunk
+        self.push_chunk();
+        self.visit_node(default);
+        let default_chunk = self.pop_chunk();
+        // push match
+        self.push_instr(Opcode::Match {
+            addr: location.address.clone(),
+            matchable: Chunk::new(matchable_chunk),
+            arms,
+            default: Chunk::new(default_chunk),
+        });
+    }
 
-            if_op = Opcode::If {
-                addr: location.address.clone(),
-                cond: Chunk::new(logical_chunk),
-                body: Chunk::new(body_chunk),
-                elif: Some(Chunk::of(if_op)),
-            }
+    /// Visit match pattern
+    fn visit_pattern(&mut self, pattern: &Pattern) -> bytecode::Pattern {
+        match pattern {
+            Pattern::Wildcard { .. } => bytecode::Pattern::Wildcard,
+            Pattern::Bind { name } => bytecode::Pattern::Bind {
+                name: name.value.clone(),
+            },
+            Pattern::Value { value } => bytecode::Pattern::Value {
+                value: self.visit_chunk(value),
+            },
+            Pattern::Range { from, to, .. } => bytecode::Pattern::Range {
+                from: self.visit_chunk(from),
+                to: self.visit_chunk(to),
+            },
+            Pattern::List { items, rest, .. } => bytecode::Pattern::List {
+                items: items.iter().map(|item| self.visit_pattern(item)).collect(),
+                rest: rest.as_ref().map(|rest| Box::new(self.visit_pattern(rest))),
+            },
+            Pattern::Constructor {
+                name,
+                qualifier,
+                fields,
+                rest,
+            } => bytecode::Pattern::Constructor {
+                name: name.value.clone(),
+                qualifier: qualifier
+                    .as_ref()
+                    .map(|qualifier| self.visit_chunk(qualifier)),
+                fields: fields
+                    .iter()
+                    .map(|field| self.visit_pattern(field))
+                    .collect(),
+                rest: *rest,
+            },
+            Pattern::Or { patterns } => bytecode::Pattern::Or {
+                patterns: patterns.iter().map(|p| self.visit_pattern(p)).collect(),
+            },
         }
+    }
 
-        // push if
-        self.push_instr(if_op);
+    /// Visit fn params
+    fn visit_params(&mut self, parameters: &[Parameter]) -> Vec<Param> {
+        parameters
+            .iter()
+            .map(|param| {
+                Param::new(
+                    param.name.value.clone(),
+                    param
+                        .default
+                        .as_ref()
+                        .map(|default| self.visit_chunk(default)),
+                    param.variadic,
+                )
+            })
+            .collect()
     }
 
     /// Visit anonymous fn declaration
     fn visit_an_fn_decl(
         &mut self,
         location: &Token,
-        parameters: &Vec<Token>,
+        parameters: &[Parameter],
         body: &Node,
         make_closure: bool,
+        kind: FnKind,
     ) {
         // params
-        let mut params = Vec::new();
-        for param in parameters {
-            params.push(param.value.clone());
-        }
+        let params = self.visit_params(parameters);
         // body chunk
         self.push_chunk();
         self.visit_node(body);
@@ -732,6 +960,7 @@ impl<'visitor> CompileVisitor<'visitor> {
             addr: location.address.clone(),
             params,
             make_closure,
+            kind: Self::visit_fn_kind(kind),
             body: Chunk::new(chunk),
         });
     }
@@ -776,21 +1005,35 @@ impl<'visitor> CompileVisitor<'visitor> {
         &mut self,
         name: &Token,
         full_name: &Option<Token>,
-        constructor: &Vec<Token>,
+        constructor: &Vec<Field>,
         body: &Node,
         impl_tokens: &Vec<Token>,
     ) {
         // full name
         let full_name = full_name.as_ref().map(|name| name.value.clone());
-        // constructor
+        // constructor, hints are only checked statically
         let mut constructor_params = Vec::new();
-        for param in constructor {
-            constructor_params.push(param.value.clone());
+        for field in constructor {
+            constructor_params.push(field.name.value.clone());
         }
-        // body chunk
+        // body and methods chunks, methods are
+        // defined once per type, fields per instance
+        let Node::Block { body } = body else {
+            panic!("type body is not a block. report to the developer.")
+        };
+        let (methods, fields): (Vec<&Node>, Vec<&Node>) = body
+            .iter()
+            .partition(|node| matches!(node, Node::FnDeclaration { .. }));
         self.push_chunk();
-        self.visit_node(body);
+        for node in fields {
+            self.visit_node(node);
+        }
         let chunk = self.pop_chunk();
+        self.push_chunk();
+        for node in methods {
+            self.visit_node(node);
+        }
+        let methods_chunk = self.pop_chunk();
         // trait impls
         let mut impls = Vec::with_capacity(impl_tokens.len());
         for i in impl_tokens {
@@ -803,22 +1046,31 @@ impl<'visitor> CompileVisitor<'visitor> {
             full_name,
             constructor: constructor_params,
             body: Chunk::new(chunk),
+            methods: Chunk::new(methods_chunk),
             impls,
         });
     }
 
     /// Visit trait
-    fn visit_trait(&mut self, name: &Token, full_name: &Option<Token>, functions: &[TraitNodeFn]) {
+    fn visit_trait(
+        &mut self,
+        name: &Token,
+        full_name: &Option<Token>,
+        parents: &[Token],
+        functions: &[TraitNodeFn],
+    ) {
         // full name
         let full_name = full_name.as_ref().map(|name| name.value.clone());
+        // parent traits
+        let parents: Vec<String> = parents.iter().map(|parent| parent.value.clone()).collect();
         // trait functions
         let mut trait_functions: Vec<TraitFn> = Vec::new();
         for node_fn in functions {
             // default
-            let default: Option<DefaultTraitFn> = if node_fn.default.is_some() {
+            let default: Option<DefaultTraitFn> = if let Some(default) = &node_fn.default {
                 // body chunk and params
                 self.push_chunk();
-                self.visit_node(node_fn.default.as_ref().unwrap());
+                self.visit_node(default);
                 let chunk = Chunk::new(self.pop_chunk());
                 let params: Vec<String> = node_fn
                     .params
@@ -844,10 +1096,43 @@ impl<'visitor> CompileVisitor<'visitor> {
             addr: name.address.clone(),
             name: name.value.clone(),
             full_name,
+            parents,
             functions: trait_functions,
         });
     }
 
+    /// Visit enum
+    fn visit_enum(
+        &mut self,
+        name: &Token,
+        full_name: &Option<Token>,
+        variants: &[EnumNodeVariant],
+    ) {
+        // full name
+        let full_name = full_name.as_ref().map(|name| name.value.clone());
+        // enum variants
+        let variants = variants
+            .iter()
+            .map(|variant| {
+                EnumVariant::new(
+                    variant.name.value.clone(),
+                    variant
+                        .fields
+                        .iter()
+                        .map(|field| field.value.clone())
+                        .collect(),
+                )
+            })
+            .collect();
+        // push define enum
+        self.push_instr(Opcode::DefineEnum {
+            addr: name.address.clone(),
+            name: name.value.clone(),
+            full_name,
+            variants,
+        });
+    }
+
     /// Visit unit
     fn visit_unit(&mut self, name: &Token, full_name: &Option<Token>, body: &Node) {
         // full name
@@ -896,7 +1181,20 @@ impl<'visitor> CompileVisitor<'visitor> {
     /// Visit return
     fn visit_return(&mut self, location: &Token, value: &Node) {
         self.push_chunk();
-        self.visit_node(value);
+        // `return f(...)` is a call in tail position,
+        // if it's not inside `with` body
+        if let Node::Call {
+            previous,
+            name,
+            args,
+            ..
+        } = value
+            && self.with_depth == 0
+        {
+            self.visit_tail_call(previous.as_deref(), name, args);
+        } else {
+            self.visit_node(value);
+        }
         let chunk = self.pop_chunk();
 
         self.push_instr(Opcode::Ret {
@@ -905,6 +1203,25 @@ impl<'visitor> CompileVisitor<'visitor> {
         });
     }
 
+    /// Visit yield
+    fn visit_yield(&mut self, location: &Token, value: &Node) {
+        let value = self.visit_chunk(value);
+        self.push_instr(Opcode::Yield {
+            addr: location.address.clone(),
+            value,
+        });
+    }
+
+    /// Visit await
+    fn visit_await(&mut self, location: &Token, value: &Node, should_push: bool) {
+        let value = self.visit_chunk(value);
+        self.push_instr(Opcode::Await {
+            addr: location.address.clone(),
+            value,
+            should_push,
+        });
+    }
+
     /// Visit null
     fn visit_null(&mut self, location: &Token) {
         self.push_instr(Opcode::Push {
@@ -930,6 +1247,30 @@ impl<'visitor> CompileVisitor<'visitor> {
         });
     }
 
+    /// Visit destructure
+    fn visit_destructure(
+        &mut self,
+        location: &Token,
+        kind: &DestructureKind,
+        names: &[Token],
+        value: &Node,
+        is_define: bool,
+    ) {
+        // value chunk
+        let value = self.visit_chunk(value);
+        // push destructure
+        self.push_instr(Opcode::Destructure {
+            addr: location.address.clone(),
+            kind: match kind {
+                DestructureKind::List => bytecode::DestructureKind::List,
+                DestructureKind::Fields => bytecode::DestructureKind::Fields,
+            },
+            names: names.iter().map(|name| name.value.clone()).collect(),
+            value,
+            define: is_define,
+        });
+    }
+
     /// Visit assign
     fn visit_assign(&mut self, previous: Option<&Node>, name: &Token, value: &Node) {
         // previous
@@ -965,6 +1306,7 @@ impl<'visitor> CompileVisitor<'visitor> {
             name: name.value.clone(),
             has_previous,
             should_push,
+            cache: InlineCache::new(),
         });
     }
 
@@ -994,6 +1336,16 @@ impl<'visitor> CompileVisitor<'visitor> {
         })
     }
 
+    /// Visit index
+    fn visit_index(&mut self, location: &Token, value: &Node, key: &Node, should_push: bool) {
+        self.visit_node(key);
+        self.visit_node(value);
+        self.push_instr(Opcode::Index {
+            addr: location.address.clone(),
+            should_push,
+        });
+    }
+
     /// Visit range
     fn visit_range(&mut self, location: &Token, from: &Node, to: &Node) {
         // range call args
@@ -1006,8 +1358,16 @@ impl<'visitor> CompileVisitor<'visitor> {
             addr: location.address.clone(),
             name: "_range".to_string(),
             args: Chunk::new(chunk),
+            named: vec![],
             has_previous: false,
             should_push: true,
+            cache: InlineCache::new(),
         })
     }
 }
+/// Visitor default implementation
+impl Default for CompileVisitor<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
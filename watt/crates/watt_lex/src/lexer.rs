@@ -48,9 +48,15 @@ impl<'file_path, 'cursor> Lexer<'file_path, 'cursor> {
             ("null", TokenKind::Null),
             ("return", TokenKind::Ret),
             ("trait", TokenKind::Trait),
+            ("enum", TokenKind::Enum),
             ("impl", TokenKind::Impl),
             ("native", TokenKind::Native),
             ("impls", TokenKind::Impls),
+            ("with", TokenKind::With),
+            ("yield", TokenKind::Yield),
+            ("async", TokenKind::Async),
+            ("await", TokenKind::Await),
+            ("let", TokenKind::Let),
         ]);
         // Lexer
         Lexer {
@@ -113,6 +119,8 @@ impl<'file_path, 'cursor> Lexer<'file_path, 'cursor> {
                 '*' => {
                     if self.is_match('=') {
                         self.add_tk(TokenKind::AssignMul, "*=");
+                    } else if self.is_match('*') {
+                        self.add_tk(TokenKind::Op, "**");
                     } else {
                         self.add_tk(TokenKind::Op, "*");
                     }
@@ -351,7 +359,7 @@ impl<'file_path, 'cursor> Lexer<'file_path, 'cursor> {
         // Number text
         let mut text: String = String::from("0x");
 
-        while self.cursor.peek().is_digit(16) {
+        while self.cursor.peek().is_ascii_hexdigit() {
             text.push(self.advance());
             if self.cursor.is_at_end() {
                 break;
@@ -60,10 +60,15 @@ pub enum TokenKind {
     Native,    // native
     With,      // with
     Trait,     // trait
+    Enum,      // enum
     Impl,      // impl
     Question,  // ?
     Impls,     // impls
     Range,     // ..
+    Yield,     // yield
+    Async,     // async
+    Await,     // await
+    Let,       // let
 }
 
 /// Token structure
@@ -45,10 +45,10 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         self.consume(TokenKind::Lparen);
 
         if !self.check(TokenKind::Rparen) {
-            nodes.push(self.expr());
+            nodes.push(self.arg());
             while !self.is_at_end() && self.check(TokenKind::Comma) {
                 self.consume(TokenKind::Comma);
-                nodes.push(self.expr());
+                nodes.push(self.arg());
             }
         }
         self.consume(TokenKind::Rparen);
@@ -56,6 +56,21 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         nodes
     }
 
+    /// Argument parsing `Node` or `Token = Node`
+    fn arg(&mut self) -> Node {
+        // named argument
+        if self.check(TokenKind::Id) && self.check_next(TokenKind::Assign) {
+            let name = self.consume(TokenKind::Id).clone();
+            self.consume(TokenKind::Assign);
+            Node::NamedArg {
+                name,
+                value: Box::new(self.expr()),
+            }
+        } else {
+            self.expr()
+        }
+    }
+
     /// Parameters parsing `( Token, Token, n )`
     fn params(&mut self) -> Vec<Token> {
         let mut nodes: Vec<Token> = Vec::new();
@@ -73,6 +88,123 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         nodes
     }
 
+    /// Type fields parsing `( Token, Token: TypeHint )`
+    fn fields(&mut self) -> Vec<Field> {
+        let mut fields: Vec<Field> = Vec::new();
+        self.consume(TokenKind::Lparen);
+
+        if !self.check(TokenKind::Rparen) {
+            let name = self.consume(TokenKind::Id).clone();
+            fields.push(Field::new(name, self.hint()));
+            while !self.is_at_end() && self.check(TokenKind::Comma) {
+                self.consume(TokenKind::Comma);
+                let name = self.consume(TokenKind::Id).clone();
+                fields.push(Field::new(name, self.hint()));
+            }
+        }
+        self.consume(TokenKind::Rparen);
+
+        fields
+    }
+
+    /// Optional type hint parsing `: Token` or `: Token?`
+    fn hint(&mut self) -> Option<TypeHint> {
+        if !self.check(TokenKind::Colon) {
+            return None;
+        }
+        self.consume(TokenKind::Colon);
+        Some(self.type_hint())
+    }
+
+    /// Optional return type hint parsing `-> Token`
+    fn returns_hint(&mut self) -> Option<TypeHint> {
+        if !self.check(TokenKind::Arrow) {
+            return None;
+        }
+        self.consume(TokenKind::Arrow);
+        Some(self.type_hint())
+    }
+
+    /// Type hint parsing `Token` or nullable `Token?`
+    fn type_hint(&mut self) -> TypeHint {
+        // `fn` is a keyword, but could be a hint
+        let name = if self.check(TokenKind::Fn) {
+            let location = self.consume(TokenKind::Fn).clone();
+            Token::new(TokenKind::Id, "fn".to_string(), location.address)
+        } else {
+            self.consume(TokenKind::Id).clone()
+        };
+        // nullable
+        let nullable = if self.check(TokenKind::Question) {
+            self.consume(TokenKind::Question);
+            true
+        } else {
+            false
+        };
+        TypeHint::new(name, nullable)
+    }
+
+    /// Fn parameters parsing `( Token, Token = Node, ..Token )`
+    fn fn_params(&mut self) -> Vec<Parameter> {
+        let mut params: Vec<Parameter> = Vec::new();
+        self.consume(TokenKind::Lparen);
+
+        while !self.is_at_end() && !self.check(TokenKind::Rparen) {
+            // variadic
+            let variadic = if self.check(TokenKind::Range) {
+                self.consume(TokenKind::Range);
+                true
+            } else {
+                false
+            };
+            let name = self.consume(TokenKind::Id).clone();
+            // type hint
+            let hint = self.hint();
+            // default value
+            let default = if !variadic && self.check(TokenKind::Assign) {
+                self.consume(TokenKind::Assign);
+                Some(Box::new(self.expr()))
+            } else {
+                None
+            };
+            // checking params order
+            if params.iter().any(|p| p.name.value == name.value) {
+                error!(Error::own_text(
+                    name.address.clone(),
+                    format!("param {} is already defined.", name.value),
+                    "params should have unique names.",
+                ));
+            }
+            if params.last().is_some_and(|p| p.variadic) {
+                error!(Error::new(
+                    name.address.clone(),
+                    "param after variadic param.",
+                    "variadic param should be the last one.",
+                ));
+            }
+            if default.is_none() && !variadic && params.iter().any(|p| p.default.is_some()) {
+                error!(Error::own_text(
+                    name.address.clone(),
+                    format!(
+                        "param {} without default after param with default.",
+                        name.value
+                    ),
+                    "move params with defaults to the end.",
+                ));
+            }
+            params.push(Parameter::new(name, default, variadic, hint));
+            // comma
+            if self.check(TokenKind::Comma) {
+                self.consume(TokenKind::Comma);
+            } else {
+                break;
+            }
+        }
+        self.consume(TokenKind::Rparen);
+
+        params
+    }
+
     /// Converts name to full name, using pattern:
     /// `test_fn` from file test.wt is converted to `test:test_fn`
     fn to_full_name(&self, tk: Token) -> Token {
@@ -153,7 +285,7 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
                     name: identifier.clone(),
                     should_push: true,
                 };
-                return Node::Assign {
+                Node::Assign {
                     previous,
                     name: identifier,
                     value: Box::new(Node::Bin {
@@ -161,12 +293,12 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
                         right: Box::new(self.expr()),
                         op: Token::new(TokenKind::Op, op.to_string(), location.address),
                     }),
-                };
+                }
             }
             // ( args )
             else if self.check(TokenKind::Lparen) {
                 let args = self.args();
-                return if self.check(TokenKind::Question) {
+                if self.check(TokenKind::Question) {
                     self.consume(TokenKind::Question);
                     Node::ErrorPropagation {
                         location: identifier.clone(),
@@ -185,15 +317,15 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
                         args,
                         should_push: true,
                     }
-                };
+                }
             }
             // get
             else {
-                return Node::Get {
+                Node::Get {
                     previous,
                     name: identifier,
                     should_push: true,
-                };
+                }
             }
         }
         // object creation
@@ -207,11 +339,33 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
     /// else should_push will be false
     ///
     fn access(&mut self, is_expr: bool) -> Node {
+        // destructuring `a, b := value`
+        if !is_expr && self.check(TokenKind::Id) && self.check_next(TokenKind::Comma) {
+            return self.destructure_stmt();
+        }
+
         // left
         let mut left = self.access_part(Option::None);
 
-        // by dot
-        while self.check(TokenKind::Dot) {
+        // by dot or index
+        loop {
+            // index `value[key]`, only on the same line,
+            // so list on the next line isn't indexing
+            if self.check(TokenKind::Lbracket) && self.is_same_line() {
+                let location = self.consume(TokenKind::Lbracket).clone();
+                let key = self.expr();
+                self.consume(TokenKind::Rbracket);
+                left = Node::Index {
+                    location,
+                    value: Box::new(left),
+                    key: Box::new(key),
+                    should_push: true,
+                };
+                continue;
+            }
+            if !self.check(TokenKind::Dot) {
+                break;
+            }
             self.consume(TokenKind::Dot);
             let location = self.peek().address.clone();
             left = self.access_part(Option::Some(Box::new(left)));
@@ -241,6 +395,99 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         left
     }
 
+    /// Destructuring parsing
+    ///
+    /// `a, b := value`, `[a, b] := value` takes values
+    /// by position, `{a, b} := value` takes fields by name,
+    /// `a, b = b, a` assigns multiple values at once
+    ///
+    fn destructure_stmt(&mut self) -> Node {
+        let location = self.peek().clone();
+        // targets
+        let kind;
+        let names;
+        if self.check(TokenKind::Lbracket) {
+            self.consume(TokenKind::Lbracket);
+            names = self.destructure_names();
+            self.consume(TokenKind::Rbracket);
+            kind = DestructureKind::List;
+        } else if self.check(TokenKind::Lbrace) {
+            self.consume(TokenKind::Lbrace);
+            names = self.destructure_names();
+            self.consume(TokenKind::Rbrace);
+            kind = DestructureKind::Fields;
+        } else {
+            names = self.destructure_names();
+            kind = DestructureKind::List;
+        }
+        // := or =
+        let operator = if self.check(TokenKind::Walrus) {
+            self.consume(TokenKind::Walrus).clone()
+        } else {
+            self.consume(TokenKind::Assign).clone()
+        };
+        let is_define = operator.tk_type == TokenKind::Walrus;
+        // value or multiple values
+        let mut values = vec![self.expr()];
+        while self.check(TokenKind::Comma) {
+            self.consume(TokenKind::Comma);
+            values.push(self.expr());
+        }
+        let value = if values.len() == 1 {
+            values.pop().unwrap()
+        } else {
+            // checking multiple values
+            if kind == DestructureKind::Fields {
+                error!(Error::new(
+                    operator.address,
+                    "couldn't destructure fields of multiple values.",
+                    "use `a, b := x, y` or `{a, b} := value`.",
+                ));
+            }
+            if values.len() != names.len() {
+                error!(Error::own(
+                    operator.address,
+                    format!(
+                        "invalid values amount: {} to destructure into {} names.",
+                        values.len(),
+                        names.len()
+                    ),
+                    format!("expected {} values.", names.len()),
+                ));
+            }
+            Node::List {
+                location: operator,
+                values,
+            }
+        };
+
+        Node::Destructure {
+            location,
+            kind,
+            names,
+            value: Box::new(value),
+            is_define,
+        }
+    }
+
+    /// Destructuring names parsing `Token, Token, n`
+    fn destructure_names(&mut self) -> Vec<Token> {
+        let mut names: Vec<Token> = vec![self.consume(TokenKind::Id).clone()];
+        while self.check(TokenKind::Comma) {
+            self.consume(TokenKind::Comma);
+            let name = self.consume(TokenKind::Id).clone();
+            if name.value != "_" && names.iter().any(|n| n.value == name.value) {
+                error!(Error::own_text(
+                    name.address.clone(),
+                    format!("{} is destructured twice.", name.value),
+                    "use unique names.",
+                ));
+            }
+            names.push(name);
+        }
+        names
+    }
+
     /// Access expr parsing
     fn access_expr(&mut self) -> Node {
         self.access(true)
@@ -261,14 +508,17 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
 
     /// Anonymous fn parsing
     fn anonymous_fn_expr(&mut self) -> Node {
-        let location = self.consume(TokenKind::Fn).clone();
+        let (location, kind) = self.fn_head();
 
         // params
-        let mut params: Vec<Token> = Vec::new();
+        let mut params: Vec<Parameter> = Vec::new();
         if self.check(TokenKind::Lparen) {
-            params = self.params();
+            params = self.fn_params();
         }
 
+        // return type hint
+        let returns = self.returns_hint();
+
         // body
         self.consume(TokenKind::Lbrace);
         let body = self.block();
@@ -277,8 +527,10 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         Node::AnFnDeclaration {
             location,
             params,
+            returns,
             body: Box::new(body),
             make_closure: false,
+            kind,
         }
     }
 
@@ -287,9 +539,9 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         let location = self.consume(TokenKind::Lambda).clone();
 
         // params
-        let mut params: Vec<Token> = Vec::new();
+        let mut params: Vec<Parameter> = Vec::new();
         if self.check(TokenKind::Lparen) {
-            params = self.params();
+            params = self.fn_params();
         }
 
         // ->
@@ -301,11 +553,13 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         Node::AnFnDeclaration {
             location: location.clone(),
             params,
+            returns: None,
             body: Box::new(Node::Ret {
                 location,
                 value: Box::new(body),
             }),
             make_closure: false,
+            kind: FnKind::Plain,
         }
     }
 
@@ -328,7 +582,7 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
             TokenKind::Null => Node::Null {
                 location: self.consume(TokenKind::Null).clone(),
             },
-            TokenKind::Fn => self.anonymous_fn_expr(),
+            TokenKind::Fn | TokenKind::Async => self.anonymous_fn_expr(),
             TokenKind::Lambda => self.lambda_fn_expr(),
             TokenKind::Match => self.match_expr(),
             _ => error!(Error::own_text(
@@ -343,6 +597,172 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         }
     }
 
+    /// Case pattern parsing `pattern | pattern | n`
+    ///
+    /// `nested` is true inside of list and constructor patterns,
+    /// where plain name binds value. on top level plain name is
+    /// compared with value, and binding is written as `let name`
+    ///
+    fn case_pattern(&mut self, nested: bool) -> Pattern {
+        let first = self.single_pattern(nested);
+        // or pattern
+        if self.check(TokenKind::Op) && self.peek().value == "|" {
+            let mut patterns = vec![first];
+            while self.check(TokenKind::Op) && self.peek().value == "|" {
+                self.consume(TokenKind::Op);
+                patterns.push(self.single_pattern(nested));
+            }
+            Pattern::Or { patterns }
+        } else {
+            first
+        }
+    }
+
+    /// Single pattern parsing
+    fn single_pattern(&mut self, nested: bool) -> Pattern {
+        // wildcard
+        if self.check(TokenKind::Id) && self.peek().value == "_" {
+            return Pattern::Wildcard {
+                location: self.consume(TokenKind::Id).clone(),
+            };
+        }
+        // explicit binding
+        if self.check(TokenKind::Let) {
+            self.consume(TokenKind::Let);
+            return Pattern::Bind {
+                name: self.consume(TokenKind::Id).clone(),
+            };
+        }
+        // list pattern
+        if self.check(TokenKind::Lbracket) {
+            return self.list_pattern();
+        }
+        // binding, constructor or path
+        if self.check(TokenKind::Id) {
+            let mut path = vec![self.consume(TokenKind::Id).clone()];
+            while self.check(TokenKind::Dot) {
+                self.consume(TokenKind::Dot);
+                path.push(self.consume(TokenKind::Id).clone());
+            }
+            let name = path.pop().unwrap();
+            // qualifier node
+            let mut qualifier: Option<Box<Node>> = None;
+            for part in path {
+                qualifier = Some(Box::new(Node::Get {
+                    previous: qualifier,
+                    name: part,
+                    should_push: true,
+                }));
+            }
+            // constructor
+            if self.check(TokenKind::Lparen) {
+                let (fields, rest) = self.constructor_pattern_fields();
+                return Pattern::Constructor {
+                    name,
+                    qualifier,
+                    fields,
+                    rest,
+                };
+            }
+            // binding
+            if nested && qualifier.is_none() {
+                return Pattern::Bind { name };
+            }
+            // variable or path value
+            return Pattern::Value {
+                value: Box::new(Node::Get {
+                    previous: qualifier,
+                    name,
+                    should_push: true,
+                }),
+            };
+        }
+        // value or range
+        let value = self.unary_expr();
+        if self.check(TokenKind::Range) {
+            let location = self.consume(TokenKind::Range).clone();
+            let to = self.unary_expr();
+            Pattern::Range {
+                location,
+                from: Box::new(value),
+                to: Box::new(to),
+            }
+        } else {
+            Pattern::Value {
+                value: Box::new(value),
+            }
+        }
+    }
+
+    /// List pattern parsing `[ pattern, pattern, ..rest ]`
+    fn list_pattern(&mut self) -> Pattern {
+        let location = self.consume(TokenKind::Lbracket).clone();
+        let mut items = vec![];
+        let mut rest = None;
+        while !self.is_at_end() && !self.check(TokenKind::Rbracket) {
+            // rest
+            if self.check(TokenKind::Range) {
+                let range = self.consume(TokenKind::Range).clone();
+                rest = Some(Box::new(if self.check(TokenKind::Id) {
+                    let name = self.consume(TokenKind::Id).clone();
+                    if name.value == "_" {
+                        Pattern::Wildcard { location: name }
+                    } else {
+                        Pattern::Bind { name }
+                    }
+                } else {
+                    Pattern::Wildcard { location: range }
+                }));
+                break;
+            }
+            items.push(self.case_pattern(true));
+            if self.check(TokenKind::Comma) {
+                self.consume(TokenKind::Comma);
+            } else {
+                break;
+            }
+        }
+        self.consume(TokenKind::Rbracket);
+        Pattern::List {
+            location,
+            items,
+            rest,
+        }
+    }
+
+    /// Constructor pattern fields parsing `( pattern, pattern, .. )`
+    fn constructor_pattern_fields(&mut self) -> (Vec<Pattern>, bool) {
+        self.consume(TokenKind::Lparen);
+        let mut fields = vec![];
+        let mut rest = false;
+        while !self.is_at_end() && !self.check(TokenKind::Rparen) {
+            // rest
+            if self.check(TokenKind::Range) {
+                self.consume(TokenKind::Range);
+                rest = true;
+                break;
+            }
+            fields.push(self.case_pattern(true));
+            if self.check(TokenKind::Comma) {
+                self.consume(TokenKind::Comma);
+            } else {
+                break;
+            }
+        }
+        self.consume(TokenKind::Rparen);
+        (fields, rest)
+    }
+
+    /// Case guard parsing `if expr`
+    fn case_guard(&mut self) -> Option<Box<Node>> {
+        if self.check(TokenKind::If) {
+            self.consume(TokenKind::If);
+            Some(Box::new(self.expr()))
+        } else {
+            None
+        }
+    }
+
     /// Match expr parsing
     fn match_expr(&mut self) -> Node {
         let location = self.consume(TokenKind::Match).clone();
@@ -367,8 +787,10 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
                         value: Box::new(Node::AnFnDeclaration {
                             location: location.clone(),
                             params: vec![],
+                            returns: None,
                             body: Box::new(body),
                             make_closure: false,
+                            kind: FnKind::Plain,
                         }),
                     },
                     Node::Call {
@@ -388,13 +810,19 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         // cases body
         self.consume(TokenKind::Lbrace);
         while self.check(TokenKind::Case) {
-            self.consume(TokenKind::Case);
-            let value = self.expr();
+            let case_location = self.consume(TokenKind::Case).clone();
+            let pattern = self.case_pattern(false);
+            let guard = self.case_guard();
 
             // one line
             if self.check(TokenKind::Arrow) {
                 self.consume(TokenKind::Arrow);
-                cases.push(MatchCase::new(Box::new(value), Box::new(self.expr())));
+                cases.push(MatchCase::new(
+                    case_location,
+                    pattern,
+                    guard,
+                    Box::new(self.expr()),
+                ));
             }
             // multi line
             else if self.check(TokenKind::Lbrace) {
@@ -402,7 +830,9 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
                 let body = self.block();
                 self.consume(TokenKind::Rbrace);
                 cases.push(MatchCase::new(
-                    Box::new(value),
+                    case_location,
+                    pattern,
+                    guard,
                     Box::new(make_lambda(location.clone(), body)),
                 ));
             } else {
@@ -526,10 +956,39 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
 
                 Node::Unary {
                     op,
-                    value: Box::new(self.primary_expr()),
+                    value: Box::new(self.power_expr()),
+                }
+            }
+            Token {
+                tk_type: TokenKind::Await,
+                ..
+            } => {
+                let location = self.consume(TokenKind::Await).clone();
+
+                Node::Await {
+                    location,
+                    value: Box::new(self.power_expr()),
+                    should_push: true,
                 }
             }
-            _ => self.primary_expr(),
+            _ => self.power_expr(),
+        }
+    }
+
+    /// Binary operation `**` parsing, right associative
+    fn power_expr(&mut self) -> Node {
+        let left = self.primary_expr();
+
+        if self.check(TokenKind::Op) && self.peek().value == "**" {
+            let op = self.consume(TokenKind::Op).clone();
+            let right = self.unary_expr();
+            Node::Bin {
+                left: Box::new(left),
+                right: Box::new(right),
+                op,
+            }
+        } else {
+            left
         }
     }
 
@@ -685,17 +1144,57 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
     /// Return statement parsing
     fn return_stmt(&mut self) -> Node {
         let location = self.consume(TokenKind::Ret).clone();
-        let value = Box::new(self.expr());
-        Node::Ret { location, value }
+        let value = self.expr();
+        // multiple values are returned as list
+        if self.check(TokenKind::Comma) {
+            let mut values = vec![value];
+            while self.check(TokenKind::Comma) {
+                self.consume(TokenKind::Comma);
+                values.push(self.expr());
+            }
+            return Node::Ret {
+                location: location.clone(),
+                value: Box::new(Node::List { location, values }),
+            };
+        }
+        Node::Ret {
+            location,
+            value: Box::new(value),
+        }
+    }
+
+    /// Yield statement parsing
+    fn yield_stmt(&mut self) -> Node {
+        let location = self.consume(TokenKind::Yield).clone();
+        let value = self.expr();
+        Node::Yield {
+            location,
+            value: Box::new(value),
+        }
+    }
+
+    /// Await statement parsing, awaited
+    /// task result is not used
+    fn await_stmt(&mut self) -> Node {
+        let location = self.consume(TokenKind::Await).clone();
+        let value = self.expr();
+        Node::Await {
+            location,
+            value: Box::new(value),
+            should_push: false,
+        }
     }
 
     /// Single import parsing
     ///
     /// ✔️ With: creates full_name_prefix override
     /// ❌ with: uses default full_name_prefix
+    ///
+    /// `with` followed by not a text is
+    /// a with statement after import
     fn single_import(&mut self) -> Import {
         let name = self.consume(TokenKind::Text).clone();
-        if self.check(TokenKind::With) {
+        if self.check(TokenKind::With) && self.check_next(TokenKind::Text) {
             self.consume(TokenKind::With);
             Import::new(
                 Option::Some(name.address),
@@ -832,19 +1331,30 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         // body
         self.consume(TokenKind::Lbrace);
         while self.check(TokenKind::Case) {
-            self.consume(TokenKind::Case);
-            let value = self.expr();
+            let case_location = self.consume(TokenKind::Case).clone();
+            let pattern = self.case_pattern(false);
+            let guard = self.case_guard();
             // one line
             if self.check(TokenKind::Arrow) {
                 self.consume(TokenKind::Arrow);
-                cases.push(MatchCase::new(Box::new(value), Box::new(self.statement())))
+                cases.push(MatchCase::new(
+                    case_location,
+                    pattern,
+                    guard,
+                    Box::new(self.statement()),
+                ))
             }
             // multi line
             else if self.check(TokenKind::Lbrace) {
                 self.consume(TokenKind::Lbrace);
                 let body = self.block();
                 self.consume(TokenKind::Rbrace);
-                cases.push(MatchCase::new(Box::new(value), Box::new(body)))
+                cases.push(MatchCase::new(
+                    case_location,
+                    pattern,
+                    guard,
+                    Box::new(body),
+                ))
             } else {
                 error!(Error::new(
                     location.address.clone(),
@@ -899,19 +1409,38 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         }
     }
 
+    /// With statement parsing
+    fn with_stmt(&mut self) -> Node {
+        self.consume(TokenKind::With);
+        let name = self.consume(TokenKind::Id).clone();
+        self.consume(TokenKind::Walrus);
+        let value = self.expr();
+        self.consume(TokenKind::Lbrace);
+        let body = self.block();
+        self.consume(TokenKind::Rbrace);
+        Node::With {
+            variable_name: name,
+            value: Box::new(value),
+            body: Box::new(body),
+        }
+    }
+
     /// Fn declaration parsing
     fn function_stmt(&mut self) -> Node {
-        self.consume(TokenKind::Fn);
+        let (_, kind) = self.fn_head();
 
         // fn name
         let name = self.consume(TokenKind::Id).clone();
 
         // params
-        let mut params: Vec<Token> = Vec::new();
+        let mut params: Vec<Parameter> = Vec::new();
         if self.check(TokenKind::Lparen) {
-            params = self.params();
+            params = self.fn_params();
         }
 
+        // return type hint
+        let returns = self.returns_hint();
+
         // body
         self.consume(TokenKind::Lbrace);
         let body = self.block();
@@ -921,8 +1450,36 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
             name: name.clone(),
             full_name: Option::Some(self.to_full_name(name)),
             params,
+            returns,
             body: Box::new(body),
             make_closure: true,
+            kind,
+        }
+    }
+
+    /// Fn head parsing: `fn`, generator `fn*`
+    /// or `async fn`, returns `fn` token and kind
+    fn fn_head(&mut self) -> (Token, FnKind) {
+        // async fn
+        if self.check(TokenKind::Async) {
+            self.consume(TokenKind::Async);
+            let location = self.consume(TokenKind::Fn).clone();
+            if self.check(TokenKind::Op) && self.peek().value == "*" {
+                error!(Error::new(
+                    location.address,
+                    "async generators are not supported.",
+                    "use either `async fn` or `fn*`."
+                ))
+            }
+            return (location, FnKind::Async);
+        }
+        // fn or generator fn
+        let location = self.consume(TokenKind::Fn).clone();
+        if self.check(TokenKind::Op) && self.peek().value == "*" {
+            self.consume(TokenKind::Op);
+            (location, FnKind::Generator)
+        } else {
+            (location, FnKind::Plain)
         }
     }
 
@@ -933,10 +1490,10 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         // type name
         let name = self.consume(TokenKind::Id).clone();
 
-        // params
-        let mut constructor: Vec<Token> = Vec::new();
+        // fields
+        let mut constructor: Vec<Field> = Vec::new();
         if self.check(TokenKind::Lparen) {
-            constructor = self.params();
+            constructor = self.fields();
         }
 
         // traits
@@ -950,23 +1507,31 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
                 impls.push(self.consume(TokenKind::Id).clone());
             }
         }
-        // body
+        // body, `{a, b} := value` after
+        // type is a destructuring statement
         let mut body = Vec::new();
-        if self.check(TokenKind::Lbrace) {
+        if self.check(TokenKind::Lbrace) && !self.is_fields_destructure() {
             self.consume(TokenKind::Lbrace);
             while !self.is_at_end() && !self.check(TokenKind::Rbrace) {
                 let location = self.peek().clone();
                 let mut node = self.statement();
                 match node {
                     Node::FnDeclaration {
-                        name, params, body, ..
+                        name,
+                        params,
+                        returns,
+                        body,
+                        kind,
+                        ..
                     } => {
                         node = Node::FnDeclaration {
                             name,
                             full_name: None,
                             params,
+                            returns,
                             body,
                             make_closure: false,
+                            kind,
                         }
                     }
                     Node::Native { .. }
@@ -1004,6 +1569,17 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
 
         // trait name
         let name = self.consume(TokenKind::Id).clone();
+        // parent traits
+        let mut parents: Vec<Token> = Vec::new();
+        if self.check(TokenKind::Colon) {
+            // parents by comma
+            self.consume(TokenKind::Colon);
+            parents.push(self.consume(TokenKind::Id).clone());
+            while !self.is_at_end() && self.check(TokenKind::Comma) {
+                self.consume(TokenKind::Comma);
+                parents.push(self.consume(TokenKind::Id).clone());
+            }
+        }
         // functions
         let mut functions: Vec<TraitNodeFn> = Vec::new();
         self.consume(TokenKind::Lbrace);
@@ -1046,10 +1622,53 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         Node::Trait {
             name: name.clone(),
             full_name: Some(self.to_full_name(name)),
+            parents,
             functions,
         }
     }
 
+    /// Enum declaration parsing
+    fn enum_stmt(&mut self) -> Node {
+        self.consume(TokenKind::Enum);
+
+        // enum name
+        let name = self.consume(TokenKind::Id).clone();
+        // variants, separated by optional commas
+        let mut variants: Vec<EnumNodeVariant> = Vec::new();
+        self.consume(TokenKind::Lbrace);
+        while !self.is_at_end() && !self.check(TokenKind::Rbrace) {
+            // variant name
+            let variant = self.consume(TokenKind::Id).clone();
+
+            // payload fields
+            let mut fields: Vec<Token> = Vec::new();
+            if self.check(TokenKind::Lparen) {
+                fields = self.params();
+            }
+
+            // checking variant is unique
+            if variants.iter().any(|v| v.name.value == variant.value) {
+                error!(Error::own_text(
+                    variant.address.clone(),
+                    format!("variant {} is already defined.", variant.value),
+                    "you can rename variant.",
+                ));
+            }
+            variants.push(EnumNodeVariant::new(variant, fields));
+
+            if self.check(TokenKind::Comma) {
+                self.consume(TokenKind::Comma);
+            }
+        }
+        self.consume(TokenKind::Rbrace);
+
+        Node::Enum {
+            name: name.clone(),
+            full_name: Some(self.to_full_name(name)),
+            variants,
+        }
+    }
+
     /// Unit declaration parsing
     fn unit_stmt(&mut self) -> Node {
         self.consume(TokenKind::Unit);
@@ -1066,14 +1685,21 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
                 let mut node = self.statement();
                 match node {
                     Node::FnDeclaration {
-                        name, params, body, ..
+                        name,
+                        params,
+                        returns,
+                        body,
+                        kind,
+                        ..
                     } => {
                         node = Node::FnDeclaration {
                             name,
                             full_name: None,
                             params,
+                            returns,
                             body,
                             make_closure: false,
+                            kind,
                         }
                     }
                     Node::Native { .. }
@@ -1125,16 +1751,21 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
             TokenKind::Unit => self.unit_stmt(),
             TokenKind::If => self.if_stmt(),
             TokenKind::New | TokenKind::Id => self.access_stmt(),
+            TokenKind::Lbracket | TokenKind::Lbrace => self.destructure_stmt(),
             TokenKind::Match => self.match_stmt(),
             TokenKind::Continue => self.continue_stmt(),
             TokenKind::Break => self.break_stmt(),
             TokenKind::Ret => self.return_stmt(),
-            TokenKind::Fn => self.function_stmt(),
+            TokenKind::Yield => self.yield_stmt(),
+            TokenKind::Fn | TokenKind::Async => self.function_stmt(),
+            TokenKind::Await => self.await_stmt(),
             TokenKind::Native => self.native_stmt(),
             TokenKind::Import => self.import_stmt(),
             TokenKind::For => self.for_stmt(),
+            TokenKind::With => self.with_stmt(),
             TokenKind::While => self.while_stmt(),
             TokenKind::Trait => self.trait_stmt(),
+            TokenKind::Enum => self.enum_stmt(),
             _ => error!(Error::own_text(
                 tk.address.clone(),
                 format!("unexpected stmt token: {:?}:{}", tk.tk_type, tk.value),
@@ -1187,6 +1818,48 @@ impl<'file_path, 'prefix> Parser<'file_path, 'prefix> {
         }
     }
 
+    /// Check current token is on the line of previous token
+    fn is_same_line(&self) -> bool {
+        match (
+            self.tokens.get(self.current as usize),
+            self.tokens.get((self.current as usize).wrapping_sub(1)),
+        ) {
+            (Some(current), Some(previous)) => current.address.line == previous.address.line,
+            _ => false,
+        }
+    }
+
+    /// Check current tokens are `{a, b} :=` or `{a, b} =`
+    fn is_fields_destructure(&self) -> bool {
+        // names by comma after brace
+        let mut index = self.current as usize + 1;
+        loop {
+            match self.tokens.get(index) {
+                Some(tk) if tk.tk_type == TokenKind::Id => index += 1,
+                _ => return false,
+            }
+            match self.tokens.get(index) {
+                Some(tk) if tk.tk_type == TokenKind::Comma => index += 1,
+                _ => break,
+            }
+        }
+        // closing brace and operator
+        matches!(
+            (self.tokens.get(index), self.tokens.get(index + 1)),
+            (Some(brace), Some(operator))
+                if brace.tk_type == TokenKind::Rbrace
+                    && matches!(operator.tk_type, TokenKind::Walrus | TokenKind::Assign)
+        )
+    }
+
+    /// Check next token type is equal to tk_type
+    fn check_next(&self, tk_type: TokenKind) -> bool {
+        match self.tokens.get(self.current as usize + 1) {
+            Some(tk) => tk.tk_type == tk_type,
+            None => false,
+        }
+    }
+
     /// Peeks current token, if eof raises error
     fn peek(&self) -> &Token {
         match self.tokens.get(self.current as usize) {
@@ -1,5 +1,6 @@
 // imports
-use crate::values::{TraitFn, Value};
+use crate::values::{EnumVariant, Param, TraitFn, Value};
+use std::cell::Cell;
 use watt_common::address::Address;
 
 /// Opcodes chunk
@@ -23,6 +24,130 @@ impl Chunk {
     }
 }
 
+/// Inline cache
+///
+/// remembers method, resolved at call site,
+/// by type shape of the last receiver
+///
+#[derive(Clone, Debug, Default)]
+pub struct InlineCache {
+    entry: Cell<Option<(usize, Value)>>,
+}
+/// Inline cache implementation
+impl InlineCache {
+    /// New empty inline cache
+    pub fn new() -> Self {
+        InlineCache {
+            entry: Cell::new(None),
+        }
+    }
+    /// Gets cached method, if shape matches
+    pub fn get(&self, shape: usize) -> Option<Value> {
+        match self.entry.get() {
+            Some((cached, method)) if cached == shape => Some(method),
+            _ => None,
+        }
+    }
+    /// Caches method of shape
+    pub fn set(&self, shape: usize, method: Value) {
+        self.entry.set(Some((shape, method)));
+    }
+}
+
+/// Destructuring kind
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DestructureKind {
+    List,
+    Fields,
+}
+
+/// Fn kind
+///
+/// `Plain` fn runs body, when called, `Generator`
+/// fn returns generator and `Async` fn returns task
+///
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FnKind {
+    Plain,
+    Generator,
+    Async,
+}
+
+/// Match pattern
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    Wildcard,
+    Bind {
+        name: String,
+    },
+    Value {
+        value: Chunk,
+    },
+    Range {
+        from: Chunk,
+        to: Chunk,
+    },
+    List {
+        items: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+    },
+    Constructor {
+        name: String,
+        qualifier: Option<Chunk>,
+        fields: Vec<Pattern>,
+        rest: bool,
+    },
+    Or {
+        patterns: Vec<Pattern>,
+    },
+}
+/// Pattern implementation
+impl Pattern {
+    /// Nested chunks of pattern
+    pub fn chunks(&self) -> Vec<&Chunk> {
+        match self {
+            Pattern::Wildcard | Pattern::Bind { .. } => vec![],
+            Pattern::Value { value } => vec![value],
+            Pattern::Range { from, to } => vec![from, to],
+            Pattern::List { items, rest } => items
+                .iter()
+                .chain(rest.as_deref())
+                .flat_map(|item| item.chunks())
+                .collect(),
+            Pattern::Constructor {
+                qualifier, fields, ..
+            } => qualifier
+                .iter()
+                .chain(fields.iter().flat_map(|field| field.chunks()))
+                .collect(),
+            Pattern::Or { patterns } => patterns.iter().flat_map(|p| p.chunks()).collect(),
+        }
+    }
+}
+
+/// Match arm
+///
+/// * `pattern`: pattern to match
+/// * `guard`: optional guard condition
+/// * `body`: arm body
+///
+#[derive(Clone, Debug)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Chunk>,
+    pub body: Chunk,
+}
+/// Match arm implementation
+impl MatchArm {
+    pub fn new(pattern: Pattern, guard: Option<Chunk>, body: Chunk) -> Self {
+        Self {
+            pattern,
+            guard,
+            body,
+        }
+    }
+}
+
 /// Opcode value
 #[derive(Clone, Debug)]
 pub enum OpcodeValue {
@@ -70,6 +195,12 @@ pub enum Opcode {
         body: Chunk,
         elif: Option<Chunk>,
     },
+    Match {
+        addr: Address,
+        matchable: Chunk,
+        arms: Vec<MatchArm>,
+        default: Chunk,
+    },
     Loop {
         addr: Address,
         body: Chunk,
@@ -78,15 +209,17 @@ pub enum Opcode {
         addr: Address,
         name: String,
         full_name: Option<String>,
-        params: Vec<String>,
+        params: Vec<Param>,
         body: Chunk,
         make_closure: bool,
+        kind: FnKind,
     },
     AnonymousFn {
         addr: Address,
-        params: Vec<String>,
+        params: Vec<Param>,
         body: Chunk,
         make_closure: bool,
+        kind: FnKind,
     },
     DefineType {
         addr: Address,
@@ -94,6 +227,7 @@ pub enum Opcode {
         full_name: Option<String>,
         constructor: Vec<String>,
         body: Chunk,
+        methods: Chunk,
         impls: Vec<String>,
     },
     DefineUnit {
@@ -106,8 +240,15 @@ pub enum Opcode {
         addr: Address,
         name: String,
         full_name: Option<String>,
+        parents: Vec<String>,
         functions: Vec<TraitFn>,
     },
+    DefineEnum {
+        addr: Address,
+        name: String,
+        full_name: Option<String>,
+        variants: Vec<EnumVariant>,
+    },
     Define {
         addr: Address,
         name: String,
@@ -125,13 +266,24 @@ pub enum Opcode {
         name: String,
         has_previous: bool,
         should_push: bool,
+        cache: InlineCache,
     },
     Call {
         addr: Address,
         name: String,
         args: Chunk,
+        named: Vec<String>,
         has_previous: bool,
         should_push: bool,
+        cache: InlineCache,
+    },
+    TailCall {
+        addr: Address,
+        name: String,
+        args: Chunk,
+        named: Vec<String>,
+        has_previous: bool,
+        cache: InlineCache,
     },
     Duplicate {
         addr: Address,
@@ -150,6 +302,15 @@ pub enum Opcode {
         addr: Address,
         value: Chunk,
     },
+    Yield {
+        addr: Address,
+        value: Chunk,
+    },
+    Await {
+        addr: Address,
+        value: Chunk,
+        should_push: bool,
+    },
     Native {
         addr: Address,
         fn_name: String,
@@ -164,13 +325,126 @@ pub enum Opcode {
         value: Chunk,
         trait_name: String,
     },
+    Index {
+        addr: Address,
+        should_push: bool,
+    },
     DeleteLocal {
         addr: Address,
         name: String,
     },
+    Destructure {
+        addr: Address,
+        kind: DestructureKind,
+        names: Vec<String>,
+        value: Chunk,
+        define: bool,
+    },
+    With {
+        addr: Address,
+        name: String,
+        value: Chunk,
+        body: Chunk,
+    },
 }
 /// Opcode Implementation
 impl Opcode {
+    /// Opcode address
+    pub fn address(&self) -> &Address {
+        match self {
+            Opcode::Push { addr, .. }
+            | Opcode::Pop { addr }
+            | Opcode::Bin { addr, .. }
+            | Opcode::Neg { addr }
+            | Opcode::Bang { addr }
+            | Opcode::Cond { addr, .. }
+            | Opcode::Logic { addr, .. }
+            | Opcode::If { addr, .. }
+            | Opcode::Match { addr, .. }
+            | Opcode::Loop { addr, .. }
+            | Opcode::DefineFn { addr, .. }
+            | Opcode::AnonymousFn { addr, .. }
+            | Opcode::DefineType { addr, .. }
+            | Opcode::DefineUnit { addr, .. }
+            | Opcode::DefineTrait { addr, .. }
+            | Opcode::DefineEnum { addr, .. }
+            | Opcode::Define { addr, .. }
+            | Opcode::Set { addr, .. }
+            | Opcode::Load { addr, .. }
+            | Opcode::Call { addr, .. }
+            | Opcode::TailCall { addr, .. }
+            | Opcode::Duplicate { addr }
+            | Opcode::Instance { addr, .. }
+            | Opcode::EndLoop { addr, .. }
+            | Opcode::Ret { addr, .. }
+            | Opcode::Yield { addr, .. }
+            | Opcode::Await { addr, .. }
+            | Opcode::Native { addr, .. }
+            | Opcode::ErrorPropagation { addr, .. }
+            | Opcode::Impls { addr, .. }
+            | Opcode::Index { addr, .. }
+            | Opcode::DeleteLocal { addr, .. }
+            | Opcode::Destructure { addr, .. }
+            | Opcode::With { addr, .. } => addr,
+        }
+    }
+
+    /// Nested chunks of opcode
+    pub fn chunks(&self) -> Vec<&Chunk> {
+        match self {
+            Opcode::Logic { a, b, .. } => vec![a, b],
+            Opcode::If {
+                cond, body, elif, ..
+            } => {
+                let mut chunks = vec![cond, body];
+                if let Some(elif) = elif {
+                    chunks.push(elif);
+                }
+                chunks
+            }
+            Opcode::Match {
+                matchable,
+                arms,
+                default,
+                ..
+            } => {
+                let mut chunks = vec![matchable];
+                for arm in arms {
+                    chunks.extend(arm.pattern.chunks());
+                    chunks.extend(arm.guard.as_ref());
+                    chunks.push(&arm.body);
+                }
+                chunks.push(default);
+                chunks
+            }
+            Opcode::DefineFn { params, body, .. } | Opcode::AnonymousFn { params, body, .. } => {
+                let mut chunks: Vec<&Chunk> =
+                    params.iter().filter_map(|p| p.default.as_ref()).collect();
+                chunks.push(body);
+                chunks
+            }
+            Opcode::Loop { body, .. } | Opcode::DefineUnit { body, .. } => vec![body],
+            Opcode::DefineType { body, methods, .. } => vec![body, methods],
+            Opcode::DefineTrait { functions, .. } => functions
+                .iter()
+                .filter_map(|function| function.default.as_ref().map(|default| &default.chunk))
+                .collect(),
+            Opcode::Define { value, .. }
+            | Opcode::Set { value, .. }
+            | Opcode::Destructure { value, .. }
+            | Opcode::Ret { value, .. }
+            | Opcode::Yield { value, .. }
+            | Opcode::Await { value, .. }
+            | Opcode::ErrorPropagation { value, .. }
+            | Opcode::Impls { value, .. } => vec![value],
+            Opcode::With { value, body, .. } => vec![value, body],
+            Opcode::Call { args, .. }
+            | Opcode::TailCall { args, .. }
+            | Opcode::Instance { args, .. } => vec![args],
+            _ => vec![],
+        }
+    }
+
     /// Prints opcode
     /// with nested opcodes
     ///
@@ -224,6 +498,27 @@ impl Opcode {
                 print_indent(indent + 1, "body:");
                 print_chunk(indent + 2, body);
             }
+            Opcode::Match {
+                matchable,
+                arms,
+                default,
+                ..
+            } => {
+                print_indent(indent, "match");
+                print_indent(indent + 1, "matchable:");
+                print_chunk(indent + 2, matchable);
+                for arm in arms {
+                    print_indent(indent + 1, format!("case {:?}", arm.pattern).as_str());
+                    if let Some(guard) = &arm.guard {
+                        print_indent(indent + 2, "guard:");
+                        print_chunk(indent + 3, guard);
+                    }
+                    print_indent(indent + 2, "body:");
+                    print_chunk(indent + 3, &arm.body);
+                }
+                print_indent(indent + 1, "default:");
+                print_chunk(indent + 2, default);
+            }
             Opcode::Loop { body, .. } => {
                 print_indent(indent, "loop");
                 print_indent(indent + 1, "body:");
@@ -234,9 +529,18 @@ impl Opcode {
                 full_name,
                 params,
                 body,
+                kind,
                 ..
             } => {
-                print_indent(indent, format!("fn '{name}' '{full_name:?}'").as_str());
+                let keyword = match kind {
+                    FnKind::Plain => "fn",
+                    FnKind::Generator => "fn*",
+                    FnKind::Async => "async fn",
+                };
+                print_indent(
+                    indent,
+                    format!("{keyword} '{name}' '{full_name:?}'").as_str(),
+                );
                 print_indent(indent + 1, "params:");
                 for param in params {
                     print_indent(indent + 2, param.to_string().as_str());
@@ -244,8 +548,14 @@ impl Opcode {
                 print_indent(indent + 1, "body:");
                 print_chunk(indent + 2, body);
             }
-            Opcode::AnonymousFn { params, body, .. } => {
-                print_indent(indent, "anonymous_fn");
+            Opcode::AnonymousFn {
+                params, body, kind, ..
+            } => {
+                match kind {
+                    FnKind::Plain => print_indent(indent, "anonymous_fn"),
+                    FnKind::Generator => print_indent(indent, "anonymous_fn*"),
+                    FnKind::Async => print_indent(indent, "async anonymous_fn"),
+                }
                 print_indent(indent + 1, "params:");
                 for param in params {
                     print_indent(indent + 2, param.to_string().as_str());
@@ -258,6 +568,7 @@ impl Opcode {
                 full_name,
                 constructor,
                 body,
+                methods,
                 ..
             } => {
                 print_indent(
@@ -270,6 +581,8 @@ impl Opcode {
                 }
                 print_indent(indent + 1, "body:");
                 print_chunk(indent + 2, body);
+                print_indent(indent + 1, "methods:");
+                print_chunk(indent + 2, methods);
             }
             Opcode::DefineUnit {
                 name,
@@ -287,18 +600,34 @@ impl Opcode {
             Opcode::DefineTrait {
                 name,
                 full_name,
+                parents,
                 functions,
                 ..
             } => {
                 print_indent(
                     indent,
-                    format!("define_trait '{name}' '{full_name:?}'").as_str(),
+                    format!("define_trait '{name}' '{full_name:?}' parents:{parents:?}").as_str(),
                 );
                 print_indent(indent + 1, "functions:");
                 for function in functions {
                     print_indent(indent + 2, format!("{function:?}").as_str());
                 }
             }
+            Opcode::DefineEnum {
+                name,
+                full_name,
+                variants,
+                ..
+            } => {
+                print_indent(
+                    indent,
+                    format!("define_enum '{name}' '{full_name:?}'").as_str(),
+                );
+                print_indent(indent + 1, "variants:");
+                for variant in variants {
+                    print_indent(indent + 2, format!("{variant:?}").as_str());
+                }
+            }
             Opcode::Define {
                 name,
                 value,
@@ -338,6 +667,7 @@ impl Opcode {
                 has_previous,
                 should_push,
                 args,
+                named,
                 ..
             } => {
                 print_indent(
@@ -345,6 +675,20 @@ impl Opcode {
                     format!("call '{name}', should_push:{should_push}").as_str(),
                 );
                 print_indent(indent + 1, format!("has_previous:{has_previous}").as_str());
+                print_indent(indent + 1, format!("named:{named:?}").as_str());
+                print_indent(indent + 1, "args:");
+                print_chunk(indent + 2, args);
+            }
+            Opcode::TailCall {
+                name,
+                has_previous,
+                args,
+                named,
+                ..
+            } => {
+                print_indent(indent, format!("tail_call '{name}'").as_str());
+                print_indent(indent + 1, format!("has_previous:{has_previous}").as_str());
+                print_indent(indent + 1, format!("named:{named:?}").as_str());
                 print_indent(indent + 1, "args:");
                 print_chunk(indent + 2, args);
             }
@@ -377,6 +721,18 @@ impl Opcode {
                 print_indent(indent + 1, "value:");
                 print_chunk(indent + 2, value);
             }
+            Opcode::Yield { value, .. } => {
+                print_indent(indent, "yield");
+                print_indent(indent + 1, "value:");
+                print_chunk(indent + 2, value);
+            }
+            Opcode::Await {
+                value, should_push, ..
+            } => {
+                print_indent(indent, format!("await should_push:{should_push}").as_str());
+                print_indent(indent + 1, "value:");
+                print_chunk(indent + 2, value);
+            }
             Opcode::Native { fn_name, .. } => {
                 print_indent(indent, format!("native {fn_name}").as_str());
             }
@@ -392,9 +748,35 @@ impl Opcode {
                 print_indent(indent + 1, "value:");
                 print_chunk(indent + 2, value);
             }
+            Opcode::Index { should_push, .. } => {
+                print_indent(indent, format!("index should_push:{should_push}").as_str());
+            }
             Opcode::DeleteLocal { name, .. } => {
                 print_indent(indent, format!("delete_local {name}").as_str());
             }
+            Opcode::Destructure {
+                kind,
+                names,
+                value,
+                define,
+                ..
+            } => {
+                print_indent(
+                    indent,
+                    format!("destructure {kind:?} {names:?}, define:{define}").as_str(),
+                );
+                print_indent(indent + 1, "value:");
+                print_chunk(indent + 2, value);
+            }
+            Opcode::With {
+                name, value, body, ..
+            } => {
+                print_indent(indent, format!("with '{name}'").as_str());
+                print_indent(indent + 1, "value:");
+                print_chunk(indent + 2, value);
+                print_indent(indent + 1, "body:");
+                print_chunk(indent + 2, body);
+            }
         }
     }
 }
@@ -0,0 +1,87 @@
+// imports
+use crate::bytecode::Chunk;
+use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use watt_common::address::Address;
+
+/// Coverage
+///
+/// Records executed lines per file,
+/// lines, that have no opcodes, are
+/// not instrumented and not reported
+///
+#[derive(Debug, Default)]
+pub struct Coverage {
+    files: FxHashMap<PathBuf, BTreeMap<u64, u64>>,
+}
+/// Coverage implementation
+impl Coverage {
+    /// New coverage
+    pub fn new() -> Coverage {
+        Coverage {
+            files: FxHashMap::default(),
+        }
+    }
+
+    /// Registers lines of all chunk
+    /// opcodes, including nested chunks
+    pub fn instrument(&mut self, chunk: &Chunk) {
+        for op in chunk.opcodes() {
+            let addr = op.address();
+            if let Some(file) = &addr.file
+                && addr.line > 0
+            {
+                self.files
+                    .entry(file.clone())
+                    .or_default()
+                    .entry(addr.line)
+                    .or_insert(0);
+            }
+            for nested in op.chunks() {
+                self.instrument(nested);
+            }
+        }
+    }
+
+    /// Records line hit
+    pub fn hit(&mut self, addr: &Address) {
+        if let Some(file) = &addr.file
+            && let Some(lines) = self.files.get_mut(file)
+        {
+            *lines.entry(addr.line).or_insert(0) += 1;
+        }
+    }
+
+    /// Hits by line of file
+    pub fn lines(&self, file: &Path) -> Option<&BTreeMap<u64, u64>> {
+        self.files.get(file)
+    }
+
+    /// Writes lcov report
+    pub fn write_lcov(&self, path: &Path) -> std::io::Result<()> {
+        // sorting files by absolute path
+        let mut files: Vec<(PathBuf, &BTreeMap<u64, u64>)> = self
+            .files
+            .iter()
+            .map(|(file, lines)| (std::fs::canonicalize(file).unwrap_or(file.clone()), lines))
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // writing records
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "TN:")?;
+        for (file, lines) in files {
+            writeln!(writer, "SF:{}", file.display())?;
+            for (line, hits) in lines {
+                writeln!(writer, "DA:{line},{hits}")?;
+            }
+            writeln!(writer, "LF:{}", lines.len())?;
+            writeln!(writer, "LH:{}", lines.values().filter(|x| **x > 0).count())?;
+            writeln!(writer, "end_of_record")?;
+        }
+        writer.flush()
+    }
+}
@@ -0,0 +1,349 @@
+// imports
+use crate::table::Table;
+use crate::values::Value;
+use crate::vm::VM;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::path::{Path, PathBuf};
+use watt_common::address::Address;
+
+/// Debug frame
+///
+/// fn call, with the last executed
+/// address and table, where it executed
+///
+#[derive(Debug, Clone)]
+pub struct DebugFrame {
+    pub name: String,
+    pub addr: Address,
+    /// call table of frame
+    pub frame_table: *mut Table,
+    /// current table, maybe nested
+    /// `if` or `loop` table
+    pub table: *mut Table,
+}
+
+/// Stop reason
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StopReason {
+    Entry,
+    Breakpoint,
+    Step,
+    Pause,
+}
+
+/// Resume command,
+/// returned by debug handler
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Resume {
+    Continue,
+    StepIn,
+    StepOver,
+    StepOut,
+}
+
+/// Debug handler
+///
+/// protocol side of the debugger,
+/// `poll` is called periodically while
+/// running, `stopped` blocks until resume
+///
+pub trait DebugHandler {
+    /// Returns true, if pause requested
+    ///
+    /// # Safety
+    ///
+    /// called by vm between instructions, `vm` is
+    /// alive and it's values are rooted during call
+    ///
+    unsafe fn poll(&mut self, vm: &mut VM) -> bool;
+    /// Called, when vm stopped
+    ///
+    /// # Safety
+    ///
+    /// called by vm, stopped at instruction, `vm`
+    /// is alive and it's values are rooted during call
+    ///
+    unsafe fn stopped(&mut self, vm: &mut VM, reason: StopReason) -> Resume;
+}
+
+/// Step mode
+#[derive(Debug, Clone, Copy)]
+enum StepMode {
+    Run,
+    StepIn,
+    StepOver(usize),
+    StepOut(usize),
+}
+
+/// Ops amount between handler polls
+const POLL_INTERVAL: usize = 1024;
+
+/// Debugger
+///
+/// Checks breakpoints by (file, line),
+/// tracks frames and step modes
+///
+pub struct Debugger {
+    handler: Option<Box<dyn DebugHandler>>,
+    breakpoints: FxHashMap<PathBuf, FxHashSet<u64>>,
+    frames: Vec<DebugFrame>,
+    normalized: FxHashMap<PathBuf, PathBuf>,
+    mode: StepMode,
+    entry: Option<PathBuf>,
+    pause_requested: bool,
+    last_line: Option<(u64, usize)>,
+    ops: usize,
+}
+/// Debugger debug implementation
+impl std::fmt::Debug for Debugger {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("Debugger")
+            .field("breakpoints", &self.breakpoints)
+            .field("frames", &self.frames)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+/// Debugger implementation
+impl Debugger {
+    /// New debugger
+    ///
+    /// * `entry`: if set, vm stops on
+    ///   the first op of this file
+    ///
+    pub fn new(handler: Box<dyn DebugHandler>, entry: Option<&Path>) -> Debugger {
+        Debugger {
+            handler: Some(handler),
+            breakpoints: FxHashMap::default(),
+            frames: Vec::new(),
+            normalized: FxHashMap::default(),
+            mode: StepMode::Run,
+            entry: entry.map(Self::normalize),
+            pause_requested: false,
+            last_line: None,
+            ops: 0,
+        }
+    }
+
+    /// Replaces breakpoints of file,
+    /// path is canonicalized if possible
+    pub fn set_breakpoints(&mut self, file: &Path, lines: Vec<u64>) {
+        let file = Self::normalize(file);
+        if lines.is_empty() {
+            self.breakpoints.remove(&file);
+        } else {
+            self.breakpoints.insert(file, lines.into_iter().collect());
+        }
+    }
+
+    /// Requests pause on next op
+    pub fn pause(&mut self) {
+        self.pause_requested = true;
+    }
+
+    /// Frames from the outermost
+    /// to the innermost
+    pub fn frames(&self) -> &[DebugFrame] {
+        &self.frames
+    }
+
+    /// Enters frame
+    pub fn enter(&mut self, name: String, addr: &Address, table: *mut Table) {
+        self.frames.push(DebugFrame {
+            name,
+            addr: addr.clone(),
+            frame_table: table,
+            table,
+        });
+    }
+
+    /// Leaves frame
+    pub fn leave(&mut self) {
+        self.frames.pop();
+    }
+
+    /// Normalizes path to compare
+    /// breakpoints with addresses
+    fn normalize(file: &Path) -> PathBuf {
+        std::fs::canonicalize(file).unwrap_or(file.to_path_buf())
+    }
+
+    /// Normalized path of address file,
+    /// cached, because it's checked on every line
+    fn normalized(&mut self, file: &Path) -> &PathBuf {
+        if !self.normalized.contains_key(file) {
+            self.normalized
+                .insert(file.to_path_buf(), Self::normalize(file));
+        }
+        &self.normalized[file]
+    }
+
+    /// Checks, breakpoint exists at line of file
+    fn is_breakpoint(&mut self, file: &Path, line: u64) -> bool {
+        if self.breakpoints.is_empty() {
+            return false;
+        }
+        let file = self.normalized(file).clone();
+        self.breakpoints
+            .get(&file)
+            .is_some_and(|lines| lines.contains(&line))
+    }
+
+    /// Checks, file is entry file
+    fn is_entry(&mut self, file: &Path) -> bool {
+        match &self.entry {
+            Some(entry) => {
+                let entry = entry.clone();
+                *self.normalized(file) == entry
+            }
+            None => false,
+        }
+    }
+
+    /// Checks, vm should stop at address,
+    /// only the first op of line is checked
+    fn should_stop(&mut self, addr: &Address) -> Option<StopReason> {
+        let depth = self.frames.len();
+
+        // the same line
+        let Some(file) = &addr.file else {
+            return None;
+        };
+        let line = (addr.line, depth);
+        if self.last_line == Some(line) {
+            return None;
+        }
+        self.last_line = Some(line);
+
+        // checking stop reasons
+        if self.is_entry(file) {
+            self.entry = None;
+            Some(StopReason::Entry)
+        } else if self.pause_requested {
+            self.pause_requested = false;
+            Some(StopReason::Pause)
+        } else if self.is_breakpoint(file, addr.line) {
+            Some(StopReason::Breakpoint)
+        } else {
+            match self.mode {
+                StepMode::Run => None,
+                StepMode::StepIn => Some(StopReason::Step),
+                StepMode::StepOver(from) if depth <= from => Some(StopReason::Step),
+                StepMode::StepOut(from) if depth < from => Some(StopReason::Step),
+                _ => None,
+            }
+        }
+    }
+
+    /// Sets step mode from resume command
+    fn resume(&mut self, resume: Resume) {
+        let depth = self.frames.len();
+        self.mode = match resume {
+            Resume::Continue => StepMode::Run,
+            Resume::StepIn => StepMode::StepIn,
+            Resume::StepOver => StepMode::StepOver(depth),
+            Resume::StepOut => StepMode::StepOut(depth),
+        };
+    }
+}
+
+/// Vm debugger hooks
+impl VM {
+    /// Debug hook, called before
+    /// every op, when debugger is set
+    pub(crate) unsafe fn debug_hook(&mut self, addr: &Address, table: *mut Table) {
+        let Some(debugger) = self.debugger.as_mut() else {
+            return;
+        };
+
+        // updating current frame
+        if let Some(frame) = debugger.frames.last_mut() {
+            frame.addr = addr.clone();
+            frame.table = table;
+        }
+
+        // polling handler
+        debugger.ops += 1;
+        if debugger.ops % POLL_INTERVAL == 0
+            && let Some(mut handler) = debugger.handler.take()
+        {
+            let pause = handler.poll(self);
+            let debugger = self.debugger.as_mut().unwrap();
+            debugger.handler = Some(handler);
+            if pause {
+                debugger.pause();
+            }
+        }
+
+        // checking stop
+        let debugger = self.debugger.as_mut().unwrap();
+        if let Some(reason) = debugger.should_stop(addr)
+            && let Some(mut handler) = debugger.handler.take()
+        {
+            let resume = handler.stopped(self, reason);
+            let debugger = self.debugger.as_mut().unwrap();
+            debugger.handler = Some(handler);
+            debugger.resume(resume);
+        }
+    }
+
+    /// Local variables of frame,
+    /// walks tables from the current one
+    /// by roots up to the frame call table
+    ///
+    /// # Safety
+    ///
+    /// `frame` should be a frame of this vm,
+    /// while it's stopped
+    ///
+    pub unsafe fn debug_locals(&self, frame: &DebugFrame) -> Vec<(String, Value)> {
+        let mut variables = Vec::new();
+        let mut table = frame.table;
+        while !table.is_null() {
+            variables.extend(Self::debug_fields(table));
+            if table == frame.frame_table || table == self.globals {
+                break;
+            }
+            table = (*table).root;
+        }
+        variables
+    }
+
+    /// Closure variables of frame,
+    /// walks closure tables chain
+    ///
+    /// # Safety
+    ///
+    /// `frame` should be a frame of this vm,
+    /// while it's stopped
+    ///
+    pub unsafe fn debug_closure(&self, frame: &DebugFrame) -> Vec<(String, Value)> {
+        let mut variables = Vec::new();
+        let mut table = if frame.frame_table.is_null() {
+            std::ptr::null_mut()
+        } else {
+            (*frame.frame_table).closure
+        };
+        while !table.is_null() {
+            variables.extend(Self::debug_fields(table));
+            table = (*table).closure;
+        }
+        variables
+    }
+
+    /// Sorted fields of table
+    ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
+    pub unsafe fn debug_fields(table: *mut Table) -> Vec<(String, Value)> {
+        let mut fields: Vec<(String, Value)> = (*table)
+            .fields
+            .iter()
+            .map(|(name, value)| (name.clone(), *value))
+            .collect();
+        fields.sort_by(|a, b| a.0.cmp(&b.0));
+        fields
+    }
+}
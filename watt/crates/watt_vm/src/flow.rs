@@ -1,5 +1,6 @@
 // imports
-use crate::values::Value;
+use crate::values::{FnOwner, Value};
+use watt_common::address::Address;
 
 /// ControlFlow structure
 ///
@@ -11,4 +12,16 @@ pub enum ControlFlow {
     Return(Value),
     Continue,
     Break,
+    /// Tail call, propagated up to the
+    /// `VM::call` of the returning fn,
+    /// args are already pushed to stack,
+    /// last `named.len()` of them are named
+    TailCall {
+        addr: Address,
+        name: String,
+        callable: Value,
+        receiver: Option<FnOwner>,
+        passed_amount: usize,
+        named: Box<[String]>,
+    },
 }
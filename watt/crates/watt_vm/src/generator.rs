@@ -0,0 +1,95 @@
+// imports
+use crate::flow::ControlFlow;
+use crate::table::Table;
+use crate::values::{Function, Value};
+use crate::vm::{VM, try_free_table};
+use corosensei::stack::DefaultStack;
+use corosensei::{Coroutine, Yielder};
+
+/// Generator coroutine, resumed with vm,
+/// yields values of generator, returns body result
+pub type GeneratorCoroutine = Coroutine<*mut VM, Value, Result<Value, ControlFlow>, DefaultStack>;
+
+/// Generator yielder, used by `yield`
+/// to suspend generator
+pub type GeneratorYielder = Yielder<*mut VM, Value>;
+
+/// Generator
+///
+/// body of `fn*` runs on it's own host stack segment,
+/// so it's frames are kept, when generator is suspended.
+/// segment is taken by first resume and returned to pool,
+/// when body returns or generator is dropped.
+/// `async fn` body runs on generator too, it yields
+/// tasks, it awaits, to scheduler.
+///
+/// * `coroutine`: body coroutine
+/// * `yielder`: yielder of running body
+/// * `vm`: vm, body runs in
+/// * `function`: generator fn
+/// * `table`: call table, args are defined in
+/// * `suspended`: innermost table of body, when suspended
+/// * `stack`: vm stack values of suspended body
+/// * `guard`: gc guard values of suspended body
+/// * `peeked`: yielded value, not taken by `next` yet
+/// * `returned`: value, returned by body
+/// * `task`: generator runs `async fn` body
+/// * `stack_limit`: host stack limit of suspended body
+/// * `running`: body is running now
+/// * `done`: body returned
+///
+pub struct Generator {
+    pub coroutine: Option<GeneratorCoroutine>,
+    pub yielder: *const GeneratorYielder,
+    pub vm: *mut VM,
+    pub function: *mut Function,
+    pub table: *mut Table,
+    pub suspended: *mut Table,
+    pub stack: Vec<Value>,
+    pub guard: Vec<Value>,
+    pub peeked: Option<Value>,
+    pub returned: Option<Value>,
+    pub task: bool,
+    pub stack_limit: usize,
+    pub running: bool,
+    pub done: bool,
+}
+/// Generator implementation
+impl Generator {
+    /// New generator, call table
+    /// is captured by generator
+    pub unsafe fn new(function: *mut Function, table: *mut Table) -> Generator {
+        (*table).captures += 1;
+        Generator {
+            coroutine: None,
+            yielder: std::ptr::null(),
+            vm: std::ptr::null_mut(),
+            function,
+            table,
+            suspended: std::ptr::null_mut(),
+            stack: Vec::new(),
+            guard: Vec::new(),
+            peeked: None,
+            returned: None,
+            task: false,
+            stack_limit: usize::MAX,
+            running: false,
+            done: false,
+        }
+    }
+}
+/// Generator drop implementation
+impl Drop for Generator {
+    fn drop(&mut self) {
+        unsafe {
+            // body is finished by it's vm, so locals of
+            // suspended frames are dropped
+            if let Some(coroutine) = self.coroutine.take() {
+                let guard = std::mem::take(&mut self.guard);
+                (*self.vm).finish_body(coroutine, guard);
+            }
+            (*self.table).captures -= 1;
+            try_free_table(self.table);
+        }
+    }
+}
@@ -6,9 +6,17 @@
 
 // modules
 pub mod bytecode;
+pub mod coverage;
+pub mod debugger;
 pub(crate) mod flow;
+pub(crate) mod generator;
 pub(crate) mod memory;
 pub(crate) mod natives;
+pub mod permissions;
+pub mod profiler;
+pub(crate) mod reactor;
+pub(crate) mod stack;
 pub(crate) mod table;
+pub(crate) mod task;
 pub mod values;
 pub mod vm;
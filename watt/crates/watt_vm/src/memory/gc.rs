@@ -1,17 +1,58 @@
 // imports
-use crate::memory::memory;
+use crate::bytecode::Opcode;
+use crate::generator::Generator;
+use crate::memory::memory::{self, WideArena};
+use crate::memory::weak::{WeakRef, WeakTable};
 use crate::table::Table;
-use crate::values::{FnOwner, Value};
-use crate::vm::VM;
+use crate::task::{Task, TaskKind};
+use crate::values::{
+    AnyValue, Enum, EnumVariant, FnOwner, Function, Instance, Native, Trait, Type, Unboxed, Unit,
+    Value, Variant,
+};
+use crate::vm::{GcMode, GcStats, VM};
+use num_bigint::BigInt;
 use rustc_hash::FxHashSet;
 use std::borrow::Cow;
+use std::time::{Duration, Instant};
+use watt_common::address::Address;
+
+/// Gray object, reachable,
+/// but not traced yet
+#[derive(Debug, Clone, Copy)]
+enum Gray {
+    Value(Value),
+    Table(*mut Table),
+}
+
+/// Minimal wide int cells amount,
+/// full collection is run after
+const WIDE_THRESHOLD: usize = 64 * 1024;
 
 /// Garbage collector
 ///
 /// * `objects`: contains all ever allocated values, what alive.
 /// * `marked`: contains all marked values during collect_garbage.
 /// * `marked_tables`: contains all marked tables during collect_garbage.
+/// * `gray`: reachable objects, that are not traced yet.
+/// * `tracing`: gray objects are traced, marks are just queued.
 /// * `guard`: contains all guarded from garbage collection objects.
+/// * `generational`: enable/disable young generation.
+/// * `young`: contains objects allocated after last collection.
+/// * `remembered_tables`: old tables, young values were written in.
+/// * `remembered_values`: old lists and fns, young values were written in.
+/// * `closures`: old fns with closure, their closures are minor roots.
+/// * `coroutines`: old started generators and tasks, their frames are minor roots.
+/// * `finalizers`: unreachable instances, waiting for their `drop` call.
+/// * `finalized`: instances, which `drop` was already called or queued.
+/// * `weak`: any values, holding weak references and weak tables.
+/// * `minor`: minor collection is running.
+/// * `bytes`: approximate size of alive objects.
+/// * `collections`: amount of collections run.
+/// * `minor_collections`: amount of minor collections run.
+/// * `last_pause`, `total_pause`, `max_pause`: collections timing.
+/// * `max_objects`, `max_bytes`: alive objects limits.
+/// * `wide`: cells of ints, that don't fit value payload.
+/// * `wide_threshold`: wide int cells amount, collection is run after.
 /// * `debug`: enable/disable debug messages
 ///
 #[derive(Debug)]
@@ -19,19 +60,69 @@ pub struct GC {
     objects: FxHashSet<Value>,
     marked: FxHashSet<Value>,
     marked_tables: FxHashSet<*mut Table>,
+    gray: Vec<Gray>,
+    tracing: bool,
     guard: Vec<Value>,
+    generational: bool,
+    young: FxHashSet<Value>,
+    remembered_tables: Vec<*mut Table>,
+    remembered_values: FxHashSet<Value>,
+    closures: Vec<Value>,
+    coroutines: Vec<Value>,
+    finalizers: Vec<Value>,
+    finalized: FxHashSet<Value>,
+    weak: FxHashSet<Value>,
+    minor: bool,
+    bytes: usize,
+    collections: u64,
+    minor_collections: u64,
+    last_pause: Duration,
+    total_pause: Duration,
+    max_pause: Duration,
+    max_objects: Option<usize>,
+    max_bytes: Option<usize>,
+    wide: WideArena,
+    wide_threshold: usize,
     debug: bool,
 }
 
-/// Mark & sweep garbage collector implementation
+/// Mark & sweep garbage collector implementation,
+/// with optional young generation
 impl GC {
     /// New gc
-    pub fn new(debug: bool) -> GC {
+    pub fn new(
+        debug: bool,
+        mode: GcMode,
+        max_objects: Option<usize>,
+        max_bytes: Option<usize>,
+    ) -> GC {
         GC {
             objects: FxHashSet::default(),
             marked: FxHashSet::default(),
             marked_tables: FxHashSet::default(),
+            gray: Vec::new(),
+            tracing: false,
             guard: Vec::new(),
+            generational: matches!(mode, GcMode::Generational { .. }),
+            young: FxHashSet::default(),
+            remembered_tables: Vec::new(),
+            remembered_values: FxHashSet::default(),
+            closures: Vec::new(),
+            coroutines: Vec::new(),
+            finalizers: Vec::new(),
+            finalized: FxHashSet::default(),
+            weak: FxHashSet::default(),
+            minor: false,
+            bytes: 0,
+            collections: 0,
+            minor_collections: 0,
+            last_pause: Duration::ZERO,
+            total_pause: Duration::ZERO,
+            max_pause: Duration::ZERO,
+            max_objects,
+            max_bytes,
+            wide: WideArena::default(),
+            wide_threshold: WIDE_THRESHOLD,
             debug,
         }
     }
@@ -53,23 +144,96 @@ impl GC {
     /// Marks value
     ///
     /// Mark will be affected only on the
-    /// reference types except Type && Trait
+    /// reference types
     ///
-    #[allow(unused_parens)]
     pub fn mark_value(&mut self, value: Value) {
+        self.gray.push(Gray::Value(value));
+        self.trace_gray();
+    }
+
+    /// Marks table
+    /// if it's not already marked
+    unsafe fn mark_table(&mut self, table: *mut Table) {
+        self.gray.push(Gray::Table(table));
+        self.trace_gray();
+    }
+
+    /// Traces gray objects, until none is left
+    ///
+    /// references of traced objects are queued,
+    /// instead of recursion, so deep tables
+    /// chains don't exhaust host stack
+    ///
+    fn trace_gray(&mut self) {
+        if self.tracing {
+            return;
+        }
+        self.tracing = true;
+        while let Some(gray) = self.gray.pop() {
+            match gray {
+                Gray::Value(value) => self.trace_value(value),
+                Gray::Table(table) => unsafe { self.trace_table(table) },
+            }
+        }
+        self.tracing = false;
+    }
+
+    /// Traces value references
+    #[allow(unused_parens)]
+    fn trace_value(&mut self, value: Value) {
+        // marking wide int cell, cells
+        // are swept only by full collection
+        if let Some(wide) = value.wide() {
+            if !self.minor {
+                unsafe { (*wide).marked = true };
+            }
+            return;
+        }
         // if value is already marked, skip
         if self.marked.contains(&value) {
             return;
         }
+        // during minor collection old values are not traced,
+        // their young references are remembered by barriers
+        if self.minor && !self.young.contains(&value) {
+            return;
+        }
         // logging marking value
         self.log(|| Cow::Owned(format!("gc :: mark :: value = {value:?}")));
         // marking reference types
-        match value {
-            Value::Instance(instance) => unsafe {
+        match value.unbox() {
+            Unboxed::Instance(instance) => unsafe {
+                self.marked.insert(value);
+                self.mark_value(Value::Type((*instance).t));
                 self.mark_table((*instance).fields);
+            },
+            Unboxed::Type(t) => unsafe {
+                self.marked.insert(value);
+                self.mark_table((*t).methods);
+                for _trait in (*t).traits.clone() {
+                    self.mark_value(Value::Trait(_trait));
+                }
+            },
+            Unboxed::Trait(_trait) => unsafe {
+                self.marked.insert(value);
+                for parent in (*_trait).parents.clone() {
+                    self.mark_value(Value::Trait(parent));
+                }
+            },
+            Unboxed::Enum(e) => unsafe {
+                self.marked.insert(value);
+                for constant in (*e).constants.clone() {
+                    self.mark_value(constant);
+                }
+            },
+            Unboxed::Variant(variant) => unsafe {
                 self.marked.insert(value);
+                self.mark_value(Value::Enum((*variant).e));
+                for value in (*variant).values.clone() {
+                    self.mark_value(value);
+                }
             },
-            Value::Fn(f) => unsafe {
+            Unboxed::Fn(f) => unsafe {
                 self.marked.insert(value);
                 self.mark_table((*f).closure);
                 if (*f).owner.is_some() {
@@ -80,38 +244,112 @@ impl GC {
                         FnOwner::Instance(unit) => {
                             self.mark_value(Value::Instance(unit));
                         }
+                        FnOwner::Type(t) => {
+                            self.mark_value(Value::Type(t));
+                        }
                     }
                 }
             },
-            Value::Unit(unit) => unsafe {
+            Unboxed::Unit(unit) => unsafe {
                 self.mark_table((*unit).fields);
                 self.marked.insert(value);
             },
-            Value::Native(_) => {
+            Unboxed::Native(_) => {
                 self.marked.insert(value);
             }
-            Value::String(_) => {
+            Unboxed::String(_) => {
                 self.marked.insert(value);
             }
-            Value::List(list) => unsafe {
+            Unboxed::List(list) => unsafe {
                 for value in &*list {
                     self.mark_value(*value);
                 }
                 self.marked.insert(value);
             },
-            Value::Any(_) => {
+            Unboxed::Any(any) => unsafe {
+                self.marked.insert(value);
+                self.mark_coroutine(any);
+                self.mark_weak_ints(any);
+            },
+            Unboxed::BigInt(_) => {
                 self.marked.insert(value);
             }
             _ => {}
         }
     }
 
-    /// Marks table
+    /// Marks generator or task
+    /// of any value, if it's one
+    unsafe fn mark_coroutine(&mut self, any: *mut AnyValue) {
+        if let Some(generator) = (*any).downcast_mut::<Generator>() {
+            self.mark_generator(generator);
+        } else if let Some(task) = (*any).downcast_mut::<Task>() {
+            self.mark_task(task);
+        }
+    }
+
+    /// Marks wide int cells, held by weak reference
+    /// or weak table, ints are not swept by weak sweep
+    unsafe fn mark_weak_ints(&mut self, any: *mut AnyValue) {
+        if let Some(weak) = (*any).downcast_ref::<WeakRef>() {
+            if weak.target.wide().is_some() {
+                self.mark_value(weak.target);
+            }
+        } else if let Some(table) = (*any).downcast_ref::<WeakTable>() {
+            let ints: Vec<Value> = table
+                .entries
+                .values()
+                .filter(|value| value.wide().is_some())
+                .copied()
+                .collect();
+            for value in ints {
+                self.mark_value(value);
+            }
+        }
+    }
+
+    /// Marks task body, result, waiters and refs
+    unsafe fn mark_task(&mut self, task: *mut Task) {
+        if let TaskKind::Body(body) = &mut (*task).kind {
+            self.mark_generator(body);
+        }
+        if let Some(value) = (*task).result {
+            self.mark_value(value);
+        }
+        for value in (*task).waiters.clone() {
+            self.mark_value(value);
+        }
+        for value in (*task).refs.clone() {
+            self.mark_value(value);
+        }
+    }
+
+    /// Marks generator fn, tables
+    /// and values of suspended body
+    unsafe fn mark_generator(&mut self, generator: *mut Generator) {
+        self.mark_value(Value::Fn((*generator).function));
+        self.mark_table((*generator).table);
+        self.mark_table((*generator).suspended);
+        for value in (*generator).stack.clone() {
+            self.mark_value(value);
+        }
+        for value in (*generator).guard.clone() {
+            self.mark_value(value);
+        }
+        if let Some(value) = (*generator).peeked {
+            self.mark_value(value);
+        }
+        if let Some(value) = (*generator).returned {
+            self.mark_value(value);
+        }
+    }
+
+    /// Traces table
     /// if it's not already marked
     ///
     /// Marks values inside
     ///
-    unsafe fn mark_table(&mut self, table: *mut Table) {
+    unsafe fn trace_table(&mut self, table: *mut Table) {
         // checking pointer is not null
         if table.is_null() {
             return;
@@ -158,80 +396,479 @@ impl GC {
                 false
             }
         });
+        // forgetting remembered set, before
+        // old tables are freed
+        self.forget_remembered();
         // freeing this objects
         for value in to_free {
+            self.young.remove(&value);
+            self.finalized.remove(&value);
             self.free_value(value);
         }
+        // clearing weak references
+        self.sweep_weak();
+        // all survived objects are old now
+        for value in std::mem::take(&mut self.young) {
+            self.promote(value);
+        }
+        self.closures.retain(|value| self.marked.contains(value));
+        self.coroutines.retain(|value| self.marked.contains(value));
+        // recounting bytes of alive objects
+        self.bytes = self
+            .objects
+            .iter()
+            .map(|value| unsafe { Self::size_of(*value) })
+            .sum();
     }
 
-    /// Adding object to allocated list
-    /// Necessary for all reference type
-    /// values except Type && Trait
+    /// Sweeps up young trash
+    /// Freeing unmarked young objects during
+    /// minor mark phase, promoting marked
     ///
-    pub fn add_object(&mut self, value: Value) {
-        match value {
-            Value::Instance(_)
-            | Value::Fn(_)
-            | Value::Native(_)
-            | Value::String(_)
-            | Value::Unit(_)
-            | Value::List(_)
-            | Value::Any(_) => {
-                if !self.objects.contains(&value) {
-                    self.objects.insert(value);
+    fn sweep_young(&mut self) {
+        // logging sweep is running
+        self.log(|| Cow::Borrowed("gc :: minor :: sweep :: running"));
+        // promoting marked and freeing unmarked objects
+        for value in std::mem::take(&mut self.young) {
+            if self.marked.contains(&value) {
+                self.promote(value);
+            } else {
+                self.objects.remove(&value);
+                self.finalized.remove(&value);
+                self.bytes = self.bytes.saturating_sub(unsafe { Self::size_of(value) });
+                self.free_value(value);
+            }
+        }
+        self.forget_remembered();
+        // clearing weak references
+        self.sweep_weak();
+    }
+
+    /// Promotes value to old generation,
+    /// old tables are write barriered
+    fn promote(&mut self, value: Value) {
+        unsafe {
+            match value.unbox() {
+                Unboxed::Instance(instance) => (*(*instance).fields).old = true,
+                Unboxed::Unit(unit) => (*(*unit).fields).old = true,
+                Unboxed::Type(t) => (*(*t).methods).old = true,
+                Unboxed::Fn(function) if !(*function).closure.is_null() => {
+                    self.closures.push(value)
+                }
+                Unboxed::Any(any) if Self::has_frames(any) => self.coroutines.push(value),
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks value is task, or generator with body
+    /// started and not finished, since frames of
+    /// suspended body are written without barrier
+    unsafe fn has_frames(any: *mut AnyValue) -> bool {
+        match (*any).downcast_ref::<Generator>() {
+            Some(generator) => generator.coroutine.is_some() && !generator.done,
+            None => (*any).is::<Task>(),
+        }
+    }
+
+    /// Write barrier of generator, should be
+    /// called before its body is started
+    ///
+    /// remembers old generator, its
+    /// frames become minor roots
+    ///
+    pub fn barrier_coroutine(&mut self, generator: Value) {
+        if self.generational
+            && self.objects.contains(&generator)
+            && !self.young.contains(&generator)
+        {
+            self.coroutines.push(generator);
+        }
+    }
+
+    /// Forgets remembered set
+    fn forget_remembered(&mut self) {
+        for table in self.remembered_tables.drain(..) {
+            unsafe { (*table).remembered = false };
+        }
+        self.remembered_values.clear();
+    }
+
+    /// Write barrier of table
+    ///
+    /// remembers old table, when reference
+    /// value is written in it
+    ///
+    pub unsafe fn barrier_table(&mut self, table: *mut Table, value: Value) {
+        if (*table).old && !(*table).remembered && Self::is_tracked(value) {
+            (*table).remembered = true;
+            self.remembered_tables.push(table);
+        }
+    }
+
+    /// Write barrier of list, fn or type
+    ///
+    /// remembers old `container`, when
+    /// reference value is written in it
+    ///
+    pub fn barrier_value(&mut self, container: Value, value: Value) {
+        if self.generational && Self::is_tracked(value) && !self.young.contains(&container) {
+            self.remembered_values.insert(container);
+        }
+    }
+
+    /// Traces references of remembered value
+    unsafe fn trace_remembered(&mut self, value: Value) {
+        match value.unbox() {
+            Unboxed::List(list) => {
+                for value in (*list).clone() {
+                    self.mark_value(value);
+                }
+            }
+            Unboxed::Fn(function) => match (*function).owner.clone() {
+                Some(FnOwner::Unit(unit)) => self.mark_value(Value::Unit(unit)),
+                Some(FnOwner::Instance(instance)) => self.mark_value(Value::Instance(instance)),
+                Some(FnOwner::Type(t)) => self.mark_value(Value::Type(t)),
+                None => {}
+            },
+            Unboxed::Type(t) => {
+                for _trait in (*t).traits.clone() {
+                    self.mark_value(Value::Trait(_trait));
+                }
+            }
+            Unboxed::Enum(e) => {
+                for constant in (*e).constants.clone() {
+                    self.mark_value(constant);
                 }
             }
             _ => {}
         }
     }
 
+    /// Is value tracked by gc,
+    /// all reference type values
+    fn is_tracked(value: Value) -> bool {
+        matches!(
+            value.unbox(),
+            Unboxed::Instance(_)
+                | Unboxed::Fn(_)
+                | Unboxed::Native(_)
+                | Unboxed::String(_)
+                | Unboxed::Unit(_)
+                | Unboxed::List(_)
+                | Unboxed::Any(_)
+                | Unboxed::BigInt(_)
+                | Unboxed::Type(_)
+                | Unboxed::Trait(_)
+                | Unboxed::Enum(_)
+                | Unboxed::Variant(_)
+        )
+    }
+
+    /// Adding object to allocated list
+    /// Necessary for all reference
+    /// type values
+    ///
+    /// returns true, if alive objects
+    /// exceed limits after adding
+    ///
+    pub fn add_object(&mut self, value: Value) -> bool {
+        if Self::is_tracked(value) && !self.objects.contains(&value) {
+            self.objects.insert(value);
+            self.bytes += unsafe { Self::size_of(value) };
+            if self.generational {
+                self.young.insert(value);
+            }
+        }
+        self.exceeds_limits()
+    }
+
+    /// Checks alive objects amount
+    /// or bytes exceed limits
+    fn exceeds_limits(&self) -> bool {
+        self.max_objects.is_some_and(|max| self.objects.len() > max)
+            || self.max_bytes.is_some_and(|max| self.bytes > max)
+    }
+
+    /// Raises limit error, if alive objects amount or
+    /// bytes exceed limits, garbage should be collected
+    /// before the check
+    pub fn check_limits(&self, addr: &Address) {
+        if let Some(max) = self.max_objects
+            && self.objects.len() > max
+        {
+            VM::limit_exceeded(addr, "gc objects", max, "max-objects");
+        }
+        if let Some(max) = self.max_bytes
+            && self.bytes > max
+        {
+            VM::limit_exceeded(addr, "gc bytes", max, "max-bytes");
+        }
+    }
+
+    /// Wide ints arena
+    pub fn wide(&mut self) -> &mut WideArena {
+        &mut self.wide
+    }
+
+    /// Is wide int cells amount exceeds threshold
+    pub fn exceeds_wide(&self) -> bool {
+        self.wide.len() > self.wide_threshold
+    }
+
+    /// Approximate size of value in bytes,
+    /// table fields are not counted
+    unsafe fn size_of(value: Value) -> usize {
+        match value.unbox() {
+            Unboxed::Instance(_) => size_of::<Instance>() + size_of::<Table>(),
+            Unboxed::Fn(_) => size_of::<Function>(),
+            Unboxed::Native(_) => size_of::<Native>(),
+            Unboxed::String(string) => size_of::<String>() + (*string).capacity(),
+            Unboxed::Unit(_) => size_of::<Unit>() + size_of::<Table>(),
+            Unboxed::List(list) => {
+                size_of::<Vec<Value>>() + (*list).capacity() * size_of::<Value>()
+            }
+            Unboxed::Any(_) => size_of::<Value>(),
+            Unboxed::BigInt(i) => size_of::<BigInt>() + (*i).bits().div_ceil(8) as usize,
+            Unboxed::Type(t) => {
+                size_of::<Type>()
+                    + size_of::<Table>()
+                    + (*(*t).body).opcodes().len() * size_of::<Opcode>()
+            }
+            Unboxed::Trait(_) => size_of::<Trait>(),
+            Unboxed::Enum(e) => size_of::<Enum>() + (*e).variants.len() * size_of::<EnumVariant>(),
+            Unboxed::Variant(variant) => {
+                size_of::<Variant>() + (*variant).values.capacity() * size_of::<Value>()
+            }
+            _ => 0,
+        }
+    }
+
     /// Freeing value
     fn free_value(&self, value: Value) {
-        // logging value is freeing
-        self.log(|| Cow::Owned(format!("gc :: free :: value = {value:?}")));
+        // logging value is freeing, variant is logged
+        // by address, its enum may be already freed
+        self.log(|| match value.unbox() {
+            Unboxed::Variant(v) => Cow::Owned(format!("gc :: free :: value = Variant{v:?}")),
+            _ => Cow::Owned(format!("gc :: free :: value = {value:?}")),
+        });
         // free
-        match value {
-            Value::Fn(f) => {
+        match value.unbox() {
+            Unboxed::Fn(f) => {
                 if !f.is_null() {
                     memory::free_value(f);
                 }
             }
-            Value::Instance(i) => {
+            Unboxed::Instance(i) => {
                 if !i.is_null() {
                     memory::free_value(i);
                 }
             }
-            Value::String(s) => {
+            Unboxed::String(s) => {
                 if !s.is_null() {
                     memory::free_const_value(s);
                 }
             }
-            Value::Native(n) => {
+            Unboxed::Native(n) => {
                 if !n.is_null() {
                     memory::free_value(n);
                 }
             }
-            Value::Unit(u) => {
+            Unboxed::Unit(u) => {
                 if !u.is_null() {
                     memory::free_value(u);
                 }
             }
-            Value::List(l) => {
+            Unboxed::List(l) => {
                 if !l.is_null() {
                     memory::free_value(l);
                 }
             }
-            Value::Any(a) => {
+            Unboxed::Any(a) => {
                 if !a.is_null() {
                     memory::free_value(a);
                 }
             }
+            Unboxed::BigInt(i) => {
+                if !i.is_null() {
+                    memory::free_value(i);
+                }
+            }
+            Unboxed::Enum(e) => {
+                if !e.is_null() {
+                    memory::free_value(e);
+                }
+            }
+            Unboxed::Variant(v) => {
+                if !v.is_null() {
+                    memory::free_value(v);
+                }
+            }
+            Unboxed::Type(t) => {
+                if !t.is_null() {
+                    memory::free_value(t);
+                }
+            }
+            Unboxed::Trait(t) => {
+                if !t.is_null() {
+                    memory::free_value(t);
+                }
+            }
             _ => {
                 println!("unexpected gc value = {value:?}.");
             }
         }
     }
 
+    /// Registers any value, holding
+    /// weak reference or weak table
+    pub fn add_weak(&mut self, value: Value) {
+        self.weak.insert(value);
+    }
+
+    /// Is value alive during mark phase: it's
+    /// marked, not tracked or it's old during
+    /// minor collection
+    fn is_alive(&self, value: &Value) -> bool {
+        !Self::is_tracked(*value)
+            || self.marked.contains(value)
+            || (self.minor && !self.young.contains(value))
+    }
+
+    /// Marks weak tables values
+    ///
+    /// value is marked only if it's key is
+    /// alive, marking runs until fixpoint, since
+    /// marked value can make other keys alive
+    ///
+    unsafe fn mark_ephemerons(&mut self) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for handle in self.weak.clone() {
+                if !self.is_alive(&handle) {
+                    continue;
+                }
+                let Unboxed::Any(any) = handle.unbox() else {
+                    continue;
+                };
+                let Some(table) = (*any).downcast_ref::<WeakTable>() else {
+                    continue;
+                };
+                let reachable: Vec<Value> = table
+                    .entries
+                    .iter()
+                    .filter(|(key, value)| self.is_alive(key) && !self.is_alive(value))
+                    .map(|(_, value)| *value)
+                    .collect();
+                for value in reachable {
+                    self.mark_value(value);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    /// Clears weak references
+    ///
+    /// forgets freed weak values, sets swept
+    /// targets to null and deletes entries
+    /// with swept keys
+    ///
+    fn sweep_weak(&mut self) {
+        self.weak.retain(|value| self.objects.contains(value));
+        let is_swept = |value: &Value| Self::is_tracked(*value) && !self.objects.contains(value);
+        for handle in &self.weak {
+            let Unboxed::Any(any) = handle.unbox() else {
+                continue;
+            };
+            unsafe {
+                if let Some(weak) = (**any).downcast_mut::<WeakRef>() {
+                    if is_swept(&weak.target) {
+                        weak.target = Value::Null;
+                    }
+                } else if let Some(table) = (**any).downcast_mut::<WeakTable>() {
+                    table.entries.retain(|key, _| !is_swept(key));
+                }
+            }
+        }
+    }
+
+    /// Is instance finalizable, it's
+    /// type impls `Drop` trait
+    pub unsafe fn is_finalizable(instance: *mut Instance) -> bool {
+        (*(*instance).t).impls_named("Drop")
+    }
+
+    /// Queues unmarked finalizable instances
+    ///
+    /// queued instances are resurrected: they and
+    /// everything they reference are marked, so
+    /// `drop` can be called before they are freed
+    /// by the next collection
+    ///
+    unsafe fn queue_finalizers(&mut self) {
+        // finding unmarked instances
+        let candidates = if self.minor {
+            &self.young
+        } else {
+            &self.objects
+        };
+        let unreachable: Vec<Value> = candidates
+            .iter()
+            .filter(|value| match value.unbox() {
+                Unboxed::Instance(instance) => {
+                    !self.marked.contains(value)
+                        && !self.finalized.contains(value)
+                        && Self::is_finalizable(instance)
+                }
+                _ => false,
+            })
+            .copied()
+            .collect();
+        // resurrecting them
+        for value in unreachable {
+            self.log(|| Cow::Owned(format!("gc :: finalize :: value = {value:?}")));
+            self.finalized.insert(value);
+            self.finalizers.push(value);
+            self.mark_value(value);
+        }
+    }
+
+    /// Queues all alive finalizable instances,
+    /// used before vm is cleaned up
+    pub unsafe fn queue_all_finalizers(&mut self) {
+        for value in self.objects.clone() {
+            if let Unboxed::Instance(instance) = value.unbox()
+                && Self::is_finalizable(instance)
+                && self.finalized.insert(value)
+            {
+                self.finalizers.push(value);
+            }
+        }
+    }
+
+    /// Marks instance as finalized, returns
+    /// false if it was already finalized
+    pub fn set_finalized(&mut self, value: Value) -> bool {
+        self.finalized.insert(value)
+    }
+
+    /// Pops pending finalizer
+    pub fn pop_finalizer(&mut self) -> Option<Value> {
+        self.finalizers.pop()
+    }
+
+    /// Has pending finalizers
+    pub fn has_finalizers(&self) -> bool {
+        !self.finalizers.is_empty()
+    }
+
+    /// Pending finalizers
+    pub fn pending_finalizers(&self) -> &[Value] {
+        &self.finalizers
+    }
+
     /// Push value to guard stack
     /// Protects the value from being freed during
     /// sweep phase
@@ -245,6 +882,23 @@ impl GC {
         self.guard.pop();
     }
 
+    /// Guarded values
+    pub fn guarded(&self) -> &[Value] {
+        &self.guard
+    }
+
+    /// Splits off guard stack values
+    /// after `at`, used to suspend generator
+    pub fn split_guards(&mut self, at: usize) -> Vec<Value> {
+        self.guard.split_off(at)
+    }
+
+    /// Restores guard stack values,
+    /// used to resume generator
+    pub fn restore_guards(&mut self, values: Vec<Value>) {
+        self.guard.extend(values);
+    }
+
     /// Collect garbage
     /// Collects unused values
     ///
@@ -253,8 +907,91 @@ impl GC {
     pub unsafe fn collect_garbage(&mut self, vm: &mut VM, table: *mut Table) {
         // logging gc is triggered
         self.log(|| Cow::Borrowed("gc :: triggered"));
+        let start = Instant::now();
 
         // mark phase
+        self.mark_roots(vm, table);
+        self.mark_ephemerons();
+        self.queue_finalizers();
+        self.mark_ephemerons();
+
+        // sweep phase
+        self.sweep();
+        self.wide.sweep();
+        self.wide_threshold = (self.wide.len() * 2).max(WIDE_THRESHOLD);
+
+        // reset gc mark vectors
+        self.reset();
+
+        // timing
+        self.record_pause(start);
+
+        // log gc ended
+        self.log(|| Cow::Borrowed("gc :: end"));
+    }
+
+    /// Collect young garbage
+    /// Collects unused young values
+    ///
+    /// Has low runtime cost, old values are
+    /// not traced, except remembered ones
+    /// and closures of old fns
+    ///
+    pub unsafe fn collect_young(&mut self, vm: &mut VM, table: *mut Table) {
+        // logging minor gc is triggered
+        self.log(|| Cow::Borrowed("gc :: minor :: triggered"));
+        let start = Instant::now();
+
+        // mark phase
+        self.minor = true;
+        self.mark_roots(vm, table);
+        // > remembered tables
+        for table in self.remembered_tables.clone() {
+            self.mark_table(table);
+        }
+        // > remembered values
+        for value in self.remembered_values.clone() {
+            self.trace_remembered(value);
+        }
+        // > closures of old fns
+        for value in self.closures.clone() {
+            if let Unboxed::Fn(function) = value.unbox() {
+                self.mark_table((*function).closure);
+            }
+        }
+        // > frames of old generators and tasks,
+        // finished generators are marked last time
+        for value in self.coroutines.clone() {
+            if let Unboxed::Any(any) = value.unbox() {
+                self.mark_coroutine(any);
+            }
+        }
+        self.coroutines.retain(|value| match value.unbox() {
+            Unboxed::Any(any) => Self::has_frames(any),
+            _ => false,
+        });
+        self.mark_ephemerons();
+        self.queue_finalizers();
+        self.mark_ephemerons();
+        self.minor = false;
+
+        // sweep phase
+        self.sweep_young();
+
+        // reset gc mark vectors
+        self.reset();
+
+        // timing
+        self.minor_collections += 1;
+        self.record_pause(start);
+
+        // log minor gc ended
+        self.log(|| Cow::Borrowed("gc :: minor :: end"));
+    }
+
+    /// Marks roots: stack, units, natives, types, traits,
+    /// table, guard, pending finalizers and scheduled tasks
+    unsafe fn mark_roots(&mut self, vm: &mut VM, table: *mut Table) {
         // > stack
         for val in &vm.stack {
             self.mark_value(*val)
@@ -263,21 +1000,36 @@ impl GC {
         self.mark_table(vm.units);
         // > natives
         self.mark_table(vm.natives);
+        // > types and traits
+        self.mark_table(vm.types);
+        self.mark_table(vm.traits);
         // > table
         self.mark_table(table);
         // > guard
         for value in self.guard.clone() {
             self.mark_value(value);
         }
+        // > pending finalizers
+        for value in self.finalizers.clone() {
+            self.mark_value(value);
+        }
+        // > scheduled tasks
+        for value in vm.scheduler.values() {
+            self.mark_value(*value);
+        }
+    }
 
-        // sweep phase
-        self.sweep();
-
-        // reset gc mark vectors
-        self.reset();
+    /// Records collection pause
+    fn record_pause(&mut self, start: Instant) {
+        self.collections += 1;
+        self.last_pause = start.elapsed();
+        self.total_pause += self.last_pause;
+        self.max_pause = self.max_pause.max(self.last_pause);
+    }
 
-        // log gc ended
-        self.log(|| Cow::Borrowed("gc :: end"));
+    /// Young values amount
+    pub fn young_amount(&self) -> usize {
+        self.young.len()
     }
 
     /// Allocated values amount
@@ -285,6 +1037,45 @@ impl GC {
         self.objects.len()
     }
 
+    /// Approximate allocated values size in bytes,
+    /// recounted after every collection
+    pub fn bytes_amount(&self) -> usize {
+        self.bytes
+    }
+
+    /// Gc statistics snapshot,
+    /// `threshold` is current vm threshold
+    pub fn stats(&self, threshold: usize) -> GcStats {
+        let mut stats = GcStats {
+            collections: self.collections,
+            minor_collections: self.minor_collections,
+            objects: self.objects.len(),
+            bytes: self.bytes,
+            last_pause: self.last_pause,
+            total_pause: self.total_pause,
+            max_pause: self.max_pause,
+            threshold,
+            ..GcStats::default()
+        };
+        // counting objects by kind
+        for value in &self.objects {
+            match value.unbox() {
+                Unboxed::Instance(_) | Unboxed::Variant(_) => stats.instances += 1,
+                Unboxed::List(_) => stats.lists += 1,
+                Unboxed::String(_) => stats.strings += 1,
+                Unboxed::Fn(_) => stats.fns += 1,
+                Unboxed::Native(_) => stats.natives += 1,
+                Unboxed::Unit(_) => stats.units += 1,
+                Unboxed::Any(_) => stats.any += 1,
+                Unboxed::Type(_) | Unboxed::Enum(_) => stats.types += 1,
+                Unboxed::Trait(_) => stats.traits += 1,
+                Unboxed::BigInt(_) => stats.bigints += 1,
+                _ => {}
+            }
+        }
+        stats
+    }
+
     /// Full garbage collector cleanup
     /// Freeing all allocated values
     pub fn cleanup(&mut self) {
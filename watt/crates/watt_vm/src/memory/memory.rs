@@ -1,3 +1,7 @@
+// imports
+use crate::values::{AnyValue, WideInt};
+use std::any::Any;
+
 /// Allocates value in heap using box
 ///
 /// returns raw pointer
@@ -6,6 +10,14 @@ pub fn alloc_value<T>(value: T) -> *mut T {
     Box::into_raw(Box::new(value))
 }
 
+/// Allocates any value in heap
+///
+/// returns thin raw pointer
+///
+pub fn alloc_any<T: Any>(value: T) -> *mut AnyValue {
+    alloc_value(Box::new(value) as AnyValue)
+}
+
 /// Frees ptr value using box
 pub fn free_value<T: ?Sized>(ptr: *mut T) {
     if ptr.is_null() {
@@ -23,3 +35,58 @@ pub fn free_const_value<T>(ptr: *const T) {
     }
     free_value(ptr as *mut T);
 }
+
+/// Wide ints arena
+///
+/// cells of ints, that don't fit value payload,
+/// owned by gc of vm, unmarked cells are freed
+/// by full collection
+///
+#[derive(Debug, Default)]
+pub struct WideArena {
+    cells: Vec<*mut WideInt>,
+}
+/// Wide ints arena implementation
+impl WideArena {
+    /// Allocates wide int cell
+    pub fn alloc(&mut self, value: i64) -> *mut WideInt {
+        let cell = alloc_value(WideInt {
+            value,
+            marked: false,
+        });
+        self.cells.push(cell);
+        cell
+    }
+
+    /// Frees unmarked cells, resets marks
+    pub fn sweep(&mut self) {
+        self.cells.retain(|cell| unsafe {
+            let cell = *cell;
+            if (*cell).marked {
+                (*cell).marked = false;
+                true
+            } else {
+                free_value(cell);
+                false
+            }
+        });
+    }
+
+    /// Cells amount
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Is arena empty
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+/// Drop of wide ints arena, frees all cells
+impl Drop for WideArena {
+    fn drop(&mut self) {
+        for cell in self.cells.drain(..) {
+            free_value(cell);
+        }
+    }
+}
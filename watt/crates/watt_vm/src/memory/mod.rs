@@ -1,2 +1,4 @@
 pub mod gc;
 pub mod memory;
+pub mod snapshot;
+pub mod weak;
@@ -0,0 +1,396 @@
+// imports
+use crate::table::Table;
+use crate::values::{
+    Enum, EnumVariant, FnOwner, Function, Instance, Native, Trait, Type, Unboxed, Unit, Value,
+    Variant,
+};
+use crate::vm::VM;
+use num_bigint::BigInt;
+use rustc_hash::FxHashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Snapshot node
+///
+/// synthetic root, gc tracked
+/// value or variables table
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum Node {
+    Root,
+    Value(Value),
+    Table(*mut Table),
+}
+
+/// Heap snapshot
+///
+/// object graph, reachable from gc
+/// roots, with retained sizes computed
+/// by dominator tree
+///
+struct Snapshot {
+    nodes: Vec<Node>,
+    ids: FxHashMap<Node, usize>,
+    edges: Vec<(usize, usize, String)>,
+}
+/// Heap snapshot implementation
+impl Snapshot {
+    /// New snapshot with root node
+    fn new() -> Snapshot {
+        let mut ids = FxHashMap::default();
+        ids.insert(Node::Root, 0);
+        Snapshot {
+            nodes: vec![Node::Root],
+            ids,
+            edges: Vec::new(),
+        }
+    }
+
+    /// Node id, adds node if
+    /// it's not added yet
+    fn id(&mut self, node: Node) -> usize {
+        match self.ids.get(&node) {
+            Some(id) => *id,
+            None => {
+                self.nodes.push(node);
+                self.ids.insert(node, self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Adds edge to value, if it's
+    /// tracked by garbage collector
+    fn edge_value(&mut self, from: usize, value: Value, name: String) {
+        match value.unbox() {
+            Unboxed::Instance(_)
+            | Unboxed::Fn(_)
+            | Unboxed::Native(_)
+            | Unboxed::String(_)
+            | Unboxed::Unit(_)
+            | Unboxed::List(_)
+            | Unboxed::Any(_)
+            | Unboxed::BigInt(_)
+            | Unboxed::Type(_)
+            | Unboxed::Trait(_)
+            | Unboxed::Enum(_)
+            | Unboxed::Variant(_) => {
+                let to = self.id(Node::Value(value));
+                self.edges.push((from, to, name));
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds edge to table, if it's not null
+    fn edge_table(&mut self, from: usize, table: *mut Table, name: &str) {
+        if !table.is_null() {
+            let to = self.id(Node::Table(table));
+            self.edges.push((from, to, name.to_string()));
+        }
+    }
+
+    /// Walks graph, same way as gc marks,
+    /// nodes are added during the walk
+    unsafe fn walk(&mut self) {
+        let mut next = 1;
+        while next < self.nodes.len() {
+            let id = next;
+            next += 1;
+            match self.nodes[id] {
+                Node::Root => {}
+                Node::Table(table) => {
+                    for (name, value) in &(*table).fields {
+                        self.edge_value(id, *value, name.clone());
+                    }
+                    self.edge_table(id, (*table).closure, "closure");
+                    self.edge_table(id, (*table).root, "root");
+                    self.edge_table(id, (*table).parent, "parent");
+                }
+                Node::Value(value) => match value.unbox() {
+                    Unboxed::Instance(instance) => {
+                        self.edge_value(id, Value::Type((*instance).t), "type".to_string());
+                        self.edge_table(id, (*instance).fields, "fields")
+                    }
+                    Unboxed::Type(t) => {
+                        self.edge_table(id, (*t).methods, "methods");
+                        for (name, _trait) in (*t).impls.iter().zip((*t).traits.clone()) {
+                            self.edge_value(id, Value::Trait(_trait), name.clone());
+                        }
+                    }
+                    Unboxed::Trait(_trait) => {
+                        for parent in (*_trait).parents.clone() {
+                            self.edge_value(id, Value::Trait(parent), "parent".to_string());
+                        }
+                    }
+                    Unboxed::Unit(unit) => self.edge_table(id, (*unit).fields, "fields"),
+                    Unboxed::Fn(function) => {
+                        self.edge_table(id, (*function).closure, "closure");
+                        match (*function).owner {
+                            Some(FnOwner::Unit(unit)) => {
+                                self.edge_value(id, Value::Unit(unit), "owner".to_string())
+                            }
+                            Some(FnOwner::Instance(instance)) => {
+                                self.edge_value(id, Value::Instance(instance), "owner".to_string())
+                            }
+                            Some(FnOwner::Type(t)) => {
+                                self.edge_value(id, Value::Type(t), "owner".to_string())
+                            }
+                            None => {}
+                        }
+                    }
+                    Unboxed::List(list) => {
+                        for (index, value) in (*list).iter().enumerate() {
+                            self.edge_value(id, *value, format!("[{index}]"));
+                        }
+                    }
+                    Unboxed::Enum(e) => {
+                        for (variant, constant) in (*e).variants.iter().zip((*e).constants.clone())
+                        {
+                            self.edge_value(id, constant, variant.name.clone());
+                        }
+                    }
+                    Unboxed::Variant(variant) => {
+                        self.edge_value(id, Value::Enum((*variant).e), "enum".to_string());
+                        let fields = (*variant).declaration().fields.clone();
+                        for (field, value) in fields.into_iter().zip((*variant).values.clone()) {
+                            self.edge_value(id, value, field);
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    /// Immediate dominators and postorder, computed
+    /// with Cooper, Harvey and Kennedy algorithm
+    fn dominators(&self) -> (Vec<usize>, Vec<usize>) {
+        // successors and predecessors
+        let mut successors = vec![Vec::new(); self.nodes.len()];
+        let mut predecessors = vec![Vec::new(); self.nodes.len()];
+        for (from, to, _) in &self.edges {
+            successors[*from].push(*to);
+            predecessors[*to].push(*from);
+        }
+
+        // postorder
+        let mut postorder = Vec::with_capacity(self.nodes.len());
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((node, child)) = stack.pop() {
+            if let Some(next) = successors[node].get(child).copied() {
+                stack.push((node, child + 1));
+                if !visited[next] {
+                    visited[next] = true;
+                    stack.push((next, 0));
+                }
+            } else {
+                postorder.push(node);
+            }
+        }
+        let mut order = vec![usize::MAX; self.nodes.len()];
+        for (index, node) in postorder.iter().enumerate() {
+            order[*node] = index;
+        }
+
+        // iterating until fixpoint
+        let mut idom = vec![usize::MAX; self.nodes.len()];
+        idom[0] = 0;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node in postorder.iter().rev().skip(1) {
+                let mut new_idom = usize::MAX;
+                for predecessor in &predecessors[*node] {
+                    if idom[*predecessor] == usize::MAX {
+                        continue;
+                    }
+                    new_idom = if new_idom == usize::MAX {
+                        *predecessor
+                    } else {
+                        intersect(&idom, &order, *predecessor, new_idom)
+                    };
+                }
+                if idom[*node] != new_idom {
+                    idom[*node] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        (idom, postorder)
+    }
+
+    /// Node kind and name
+    unsafe fn describe(node: Node) -> (&'static str, String) {
+        match node {
+            Node::Root => ("root", "(gc roots)".to_string()),
+            Node::Table(_) => ("table", "table".to_string()),
+            Node::Value(value) => match value.unbox() {
+                Unboxed::Instance(instance) => ("instance", (*(*instance).t).name.name.clone()),
+                Unboxed::Fn(function) => ("fn", (*function).name.name.clone()),
+                Unboxed::Native(native) => ("native", (*native).name.name.clone()),
+                Unboxed::Unit(unit) => ("unit", (*unit).name.name.clone()),
+                Unboxed::String(string) => ("string", (*string).chars().take(32).collect()),
+                Unboxed::List(list) => ("list", format!("list ({} items)", (*list).len())),
+                Unboxed::Any(_) => ("any", "any".to_string()),
+                Unboxed::BigInt(i) => ("bigint", (*i).to_string()),
+                Unboxed::Type(t) => ("type", (*t).name.name.clone()),
+                Unboxed::Trait(t) => ("trait", (*t).name.name.clone()),
+                Unboxed::Enum(e) => ("enum", (*e).name.name.clone()),
+                Unboxed::Variant(variant) => ("variant", (*variant).declaration().name.clone()),
+                _ => ("value", format!("{value:?}")),
+            },
+        }
+    }
+
+    /// Node self size in bytes,
+    /// approximate like gc bytes
+    unsafe fn self_size(node: Node) -> usize {
+        match node {
+            Node::Root => 0,
+            Node::Table(table) => {
+                size_of::<Table>()
+                    + (*table)
+                        .fields
+                        .keys()
+                        .map(|key| size_of::<String>() + key.capacity() + size_of::<Value>())
+                        .sum::<usize>()
+            }
+            Node::Value(value) => match value.unbox() {
+                Unboxed::Instance(_) => size_of::<Instance>(),
+                Unboxed::Fn(_) => size_of::<Function>(),
+                Unboxed::Native(_) => size_of::<Native>(),
+                Unboxed::String(string) => size_of::<String>() + (*string).capacity(),
+                Unboxed::Unit(_) => size_of::<Unit>(),
+                Unboxed::List(list) => {
+                    size_of::<Vec<Value>>() + (*list).capacity() * size_of::<Value>()
+                }
+                Unboxed::Any(_) => size_of::<Value>(),
+                Unboxed::BigInt(i) => size_of::<BigInt>() + (*i).bits().div_ceil(8) as usize,
+                Unboxed::Type(_) => size_of::<Type>(),
+                Unboxed::Trait(_) => size_of::<Trait>(),
+                Unboxed::Enum(e) => {
+                    size_of::<Enum>() + (*e).variants.len() * size_of::<EnumVariant>()
+                }
+                Unboxed::Variant(variant) => {
+                    size_of::<Variant>() + (*variant).values.capacity() * size_of::<Value>()
+                }
+                _ => 0,
+            },
+        }
+    }
+
+    /// Renders snapshot json
+    unsafe fn to_json(&self) -> String {
+        // sizes
+        let self_sizes: Vec<usize> = self.nodes.iter().map(|n| Self::self_size(*n)).collect();
+        // retained sizes, dominated nodes
+        // precede their dominator in postorder
+        let mut retained = self_sizes.clone();
+        let (idom, postorder) = self.dominators();
+        for node in postorder {
+            if node != 0 {
+                retained[idom[node]] += retained[node];
+            }
+        }
+
+        // nodes
+        let mut json = String::from("{\"nodes\":[");
+        for (id, node) in self.nodes.iter().enumerate() {
+            let (kind, name) = Self::describe(*node);
+            if id > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "{{\"id\":{id},\"kind\":\"{kind}\",\"name\":\"{}\",\"self_size\":{},\"retained_size\":{}}}",
+                escape(&name),
+                self_sizes[id],
+                retained[id]
+            );
+        }
+
+        // edges
+        json.push_str("],\"edges\":[");
+        for (index, (from, to, name)) in self.edges.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "{{\"from\":{from},\"to\":{to},\"name\":\"{}\"}}",
+                escape(name)
+            );
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// Intersects dominators of two nodes
+fn intersect(idom: &[usize], order: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while order[a] < order[b] {
+            a = idom[a];
+        }
+        while order[b] < order[a] {
+            b = idom[b];
+        }
+    }
+    a
+}
+
+/// Escapes json string
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes heap snapshot
+///
+/// walks the same roots as gc: stack,
+/// units, natives, types, traits, current
+/// table, guard stack, pending finalizers
+/// and scheduled tasks, writes json graph to path
+///
+pub unsafe fn write_snapshot(vm: &VM, table: *mut Table, path: &Path) -> std::io::Result<()> {
+    let mut snapshot = Snapshot::new();
+
+    // roots
+    for (index, value) in vm.stack.iter().enumerate() {
+        snapshot.edge_value(0, *value, format!("stack[{index}]"));
+    }
+    snapshot.edge_table(0, vm.units, "units");
+    snapshot.edge_table(0, vm.natives, "natives");
+    snapshot.edge_table(0, vm.types, "types");
+    snapshot.edge_table(0, vm.traits, "traits");
+    snapshot.edge_table(0, table, "table");
+    for (index, value) in (*vm.gc).guarded().iter().enumerate() {
+        snapshot.edge_value(0, *value, format!("guard[{index}]"));
+    }
+    for (index, value) in (*vm.gc).pending_finalizers().iter().enumerate() {
+        snapshot.edge_value(0, *value, format!("finalizer[{index}]"));
+    }
+    for (index, value) in vm.scheduler.values().enumerate() {
+        snapshot.edge_value(0, *value, format!("task[{index}]"));
+    }
+
+    // graph
+    snapshot.walk();
+    std::fs::write(path, snapshot.to_json())
+}
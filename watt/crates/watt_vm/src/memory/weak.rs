@@ -0,0 +1,38 @@
+// imports
+use crate::values::Value;
+use rustc_hash::FxHashMap;
+
+/// Weak reference
+///
+/// target is not traced by gc, and
+/// is set to null after it's swept
+///
+#[derive(Debug)]
+pub struct WeakRef {
+    pub target: Value,
+}
+/// Weak reference implementation
+impl WeakRef {
+    /// New weak reference
+    pub fn new(target: Value) -> WeakRef {
+        WeakRef { target }
+    }
+}
+
+/// Weak table
+///
+/// keyed by instance identity, value is
+/// alive while it's key is alive, entries
+/// are deleted after their keys are swept
+///
+#[derive(Debug, Default)]
+pub struct WeakTable {
+    pub entries: FxHashMap<Value, Value>,
+}
+/// Weak table implementation
+impl WeakTable {
+    /// New weak table
+    pub fn new() -> WeakTable {
+        WeakTable::default()
+    }
+}
@@ -4,10 +4,14 @@ pub mod natives_crypto;
 pub mod natives_ffi;
 pub mod natives_fs;
 pub mod natives_gc;
+pub mod natives_generator;
 pub mod natives_io;
 pub mod natives_list;
 pub mod natives_math;
 pub mod natives_net;
 pub mod natives_strings;
 pub mod natives_system;
+pub mod natives_task;
+pub mod natives_thread;
 pub mod natives_time;
+pub mod natives_weak;
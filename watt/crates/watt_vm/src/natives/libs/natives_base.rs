@@ -3,7 +3,7 @@ use crate::bytecode::OpcodeValue;
 use crate::natives::natives;
 use crate::natives::utils;
 use crate::table::Table;
-use crate::values::Value;
+use crate::values::Unboxed;
 use crate::vm::VM;
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
@@ -37,46 +37,55 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                 return Ok(());
             }
 
-            match value {
-                Value::Float(_) => {
+            match value.unbox() {
+                Unboxed::Float(_) => {
                     vm.op_push(OpcodeValue::String("f64".to_string()), table)?;
                 }
-                Value::Int(_) => {
+                Unboxed::Int(_) => {
                     vm.op_push(OpcodeValue::String("i64".to_string()), table)?;
                 }
-                Value::String(_) => {
+                Unboxed::BigInt(_) => {
+                    vm.op_push(OpcodeValue::String("bigint".to_string()), table)?;
+                }
+                Unboxed::String(_) => {
                     vm.op_push(OpcodeValue::String("string".to_string()), table)?;
                 }
-                Value::Bool(_) => {
+                Unboxed::Bool(_) => {
                     vm.op_push(OpcodeValue::String("bool".to_string()), table)?;
                 }
-                Value::Type(_) => {
+                Unboxed::Type(_) => {
                     vm.op_push(OpcodeValue::String("type".to_string()), table)?;
                 }
-                Value::Fn(_) => {
+                Unboxed::Fn(_) => {
                     vm.op_push(OpcodeValue::String("fn".to_string()), table)?;
                 }
-                Value::Native(_) => {
+                Unboxed::Native(_) => {
                     vm.op_push(OpcodeValue::String("native".to_string()), table)?;
                 }
-                Value::Instance(i) => {
+                Unboxed::Instance(i) => {
                     vm.op_push(OpcodeValue::String((*(*i).t).name.name.clone()), table)?;
                 }
-                Value::Unit(u) => {
+                Unboxed::Unit(u) => {
                     vm.op_push(OpcodeValue::String((*u).name.name.clone()), table)?;
                 }
-                Value::Trait(t) => {
+                Unboxed::Trait(t) => {
                     vm.op_push(OpcodeValue::String((*t).name.name.clone()), table)?;
                 }
-                Value::List(l) => {
+                Unboxed::List(l) => {
                     vm.op_push(OpcodeValue::String("list".to_string()), table)?;
                 }
-                Value::Null => {
+                Unboxed::Null => {
                     vm.op_push(OpcodeValue::String("null".to_string()), table)?;
                 }
-                Value::Any(_) => {
+                Unboxed::Any(_) => {
                     vm.op_push(OpcodeValue::String("any".to_string()), table)?;
                 }
+                Unboxed::Enum(_) => {
+                    vm.op_push(OpcodeValue::String("enum".to_string()), table)?;
+                }
+                Unboxed::Variant(v) => {
+                    vm.op_push(OpcodeValue::String((*(*v).e).name.name.clone()), table)?;
+                }
             }
             Ok(())
         },
@@ -93,29 +102,32 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                 return Ok(());
             }
 
-            match value {
-                Value::Float(_) => {
+            match value.unbox() {
+                Unboxed::Float(_) => {
                     vm.op_push(OpcodeValue::String("watt:f64".to_string()), table)?;
                 }
-                Value::Int(_) => {
+                Unboxed::Int(_) => {
                     vm.op_push(OpcodeValue::String("watt:i64".to_string()), table)?;
                 }
-                Value::String(_) => {
+                Unboxed::BigInt(_) => {
+                    vm.op_push(OpcodeValue::String("watt:bigint".to_string()), table)?;
+                }
+                Unboxed::String(_) => {
                     vm.op_push(OpcodeValue::String("watt:string".to_string()), table)?;
                 }
-                Value::Bool(_) => {
+                Unboxed::Bool(_) => {
                     vm.op_push(OpcodeValue::String("watt:bool".to_string()), table)?;
                 }
-                Value::Type(_) => {
+                Unboxed::Type(_) => {
                     vm.op_push(OpcodeValue::String("watt:type".to_string()), table)?;
                 }
-                Value::Fn(_) => {
+                Unboxed::Fn(_) => {
                     vm.op_push(OpcodeValue::String("watt:fn".to_string()), table)?;
                 }
-                Value::Native(_) => {
+                Unboxed::Native(_) => {
                     vm.op_push(OpcodeValue::String("watt:native".to_string()), table)?;
                 }
-                Value::Instance(i) => {
+                Unboxed::Instance(i) => {
                     let symbol = (*(*i).t).name.clone();
                     match symbol.full_name {
                         Some(full_name) => {
@@ -126,7 +138,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         }
                     }
                 }
-                Value::Unit(u) => {
+                Unboxed::Unit(u) => {
                     let symbol = (*u).name.clone();
 
                     match symbol.full_name {
@@ -138,7 +150,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         }
                     }
                 }
-                Value::Trait(t) => {
+                Unboxed::Trait(t) => {
                     let symbol = (*t).name.clone();
 
                     match symbol.full_name {
@@ -150,15 +162,30 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         }
                     }
                 }
-                Value::List(l) => {
+                Unboxed::List(l) => {
                     vm.op_push(OpcodeValue::String("watt:list".to_string()), table)?;
                 }
-                Value::Null => {
+                Unboxed::Null => {
                     vm.op_push(OpcodeValue::String("watt:null".to_string()), table)?;
                 }
-                Value::Any(any) => {
+                Unboxed::Any(any) => {
                     vm.op_push(OpcodeValue::String("watt:any".to_string()), table)?;
                 }
+                Unboxed::Enum(_) => {
+                    vm.op_push(OpcodeValue::String("watt:enum".to_string()), table)?;
+                }
+                Unboxed::Variant(v) => {
+                    let symbol = (*(*v).e).name.clone();
+
+                    match symbol.full_name {
+                        Some(full_name) => {
+                            vm.op_push(OpcodeValue::String(full_name), table)?;
+                        }
+                        None => {
+                            vm.op_push(OpcodeValue::String(symbol.name), table)?;
+                        }
+                    }
+                }
             }
             Ok(())
         },
@@ -175,8 +202,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                 return Ok(());
             }
 
-            match value {
-                Value::Instance(_) => {
+            match value.unbox() {
+                Unboxed::Instance(_) => {
                     vm.op_push(OpcodeValue::Bool(true), table)?;
                 }
                 _ => {
@@ -1,9 +1,12 @@
 // imports
 use crate::bytecode::OpcodeValue;
+use crate::memory::memory;
 use crate::natives::natives;
 use crate::table::Table;
-use crate::values::Value;
+use crate::values::{Unboxed, Value};
 use crate::vm::VM;
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, ToPrimitive};
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
@@ -19,23 +22,37 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let value = vm.pop(&addr);
 
-            match value {
-                Value::Float(f) => {
+            match value.unbox() {
+                Unboxed::Float(f) => {
                     if should_push {
-                        vm.push(Value::Int(f as i64));
+                        vm.op_push(OpcodeValue::Int(f as i64), table)?;
                     }
                 }
-                Value::Int(i) => {
+                Unboxed::Int(i) => {
                     if should_push {
-                        vm.push(Value::Int(i));
+                        vm.op_push(OpcodeValue::Int(i), table)?;
                     }
                 }
-                Value::String(s) => {
+                Unboxed::BigInt(i) => match (*i).to_i64() {
+                    Some(i) => {
+                        if should_push {
+                            vm.op_push(OpcodeValue::Int(i), table)?;
+                        }
+                    }
+                    None => {
+                        error!(Error::own_text(
+                            addr,
+                            format!("could not cast bigint: {} to i64", *i),
+                            "bigint is out of i64 range."
+                        ));
+                    }
+                },
+                Unboxed::String(s) => {
                     let result = (*s).parse::<i64>();
                     match result {
                         Ok(i) => {
                             if should_push {
-                                vm.push(Value::Int(i));
+                                vm.op_push(OpcodeValue::Int(i), table)?;
                             }
                         }
                         Err(_) => {
@@ -47,18 +64,18 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         }
                     }
                 }
-                Value::Bool(b) => {
+                Unboxed::Bool(b) => {
                     if b {
                         if should_push {
-                            vm.push(Value::Int(1));
+                            vm.op_push(OpcodeValue::Int(1), table)?;
                         }
                     } else if should_push {
-                        vm.push(Value::Int(0));
+                        vm.op_push(OpcodeValue::Int(0), table)?;
                     }
                 }
-                Value::Null => {
+                Unboxed::Null => {
                     if should_push {
-                        vm.push(Value::Int(0));
+                        vm.op_push(OpcodeValue::Int(0), table)?;
                     }
                 }
                 _ => {
@@ -80,18 +97,23 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let value = vm.pop(&addr);
 
-            match value {
-                Value::Float(f) => {
+            match value.unbox() {
+                Unboxed::Float(f) => {
                     if should_push {
                         vm.push(Value::Float(f));
                     }
                 }
-                Value::Int(i) => {
+                Unboxed::Int(i) => {
                     if should_push {
                         vm.push(Value::Float(i as f64));
                     }
                 }
-                Value::String(s) => {
+                Unboxed::BigInt(i) => {
+                    if should_push {
+                        vm.push(Value::Float((*i).to_f64().unwrap_or(f64::NAN)));
+                    }
+                }
+                Unboxed::String(s) => {
                     let result = (*s).parse::<f64>();
                     match result {
                         Ok(f) => {
@@ -108,7 +130,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         }
                     }
                 }
-                Value::Bool(b) => {
+                Unboxed::Bool(b) => {
                     if b {
                         if should_push {
                             vm.push(Value::Float(1f64));
@@ -117,9 +139,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         vm.push(Value::Float(0f64));
                     }
                 }
-                Value::Null => {
+                Unboxed::Null => {
                     if should_push {
-                        vm.push(Value::Int(0));
+                        vm.op_push(OpcodeValue::Int(0), table)?;
                     }
                 }
                 _ => {
@@ -156,8 +178,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let value = vm.pop(&addr);
 
-            match value {
-                Value::Float(f) => {
+            match value.unbox() {
+                Unboxed::Float(f) => {
                     if should_push {
                         if f == 1f64 {
                             vm.push(Value::Bool(true));
@@ -166,7 +188,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         }
                     }
                 }
-                Value::Int(i) => {
+                Unboxed::Int(i) => {
                     if should_push {
                         if i == 1 {
                             vm.push(Value::Bool(true));
@@ -175,7 +197,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         }
                     }
                 }
-                Value::String(s) => {
+                Unboxed::String(s) => {
                     let result = (*s).parse::<bool>();
                     match result {
                         Ok(b) => {
@@ -192,12 +214,12 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         }
                     }
                 }
-                Value::Bool(b) => {
+                Unboxed::Bool(b) => {
                     vm.push(Value::Bool(b));
                 }
-                Value::Null => {
+                Unboxed::Null => {
                     if should_push {
-                        vm.push(Value::Int(0));
+                        vm.op_push(OpcodeValue::Int(0), table)?;
                     }
                 }
                 _ => {
@@ -211,5 +233,52 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             Ok(())
         },
     );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "convert@to_bigint",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let value = vm.pop(&addr);
+
+            let result = match value.unbox() {
+                Unboxed::Int(i) => BigInt::from(i),
+                Unboxed::BigInt(i) => (*i).clone(),
+                Unboxed::Float(f) => match BigInt::from_f64(f) {
+                    Some(i) => i,
+                    None => {
+                        error!(Error::own_text(
+                            addr,
+                            format!("could not cast float: {f} to bigint"),
+                            "you can convert only finite float to bigint."
+                        ));
+                    }
+                },
+                Unboxed::String(s) => match (*s).parse::<BigInt>() {
+                    Ok(i) => i,
+                    Err(_) => {
+                        error!(Error::own_text(
+                            addr,
+                            format!("could not cast string: {} to bigint", *s),
+                            "you can convert only number string to bigint."
+                        ));
+                    }
+                },
+                _ => {
+                    error!(Error::own_text(
+                        addr,
+                        format!("could not cast value: {value:?} to bigint"),
+                        "check your value"
+                    ));
+                }
+            };
+
+            if should_push {
+                let bigint = Value::BigInt(memory::alloc_value(result));
+                vm.op_push(OpcodeValue::Raw(bigint), table)?;
+            }
+            Ok(())
+        },
+    );
     Ok(())
 }
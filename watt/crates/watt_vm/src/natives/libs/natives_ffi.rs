@@ -4,7 +4,7 @@ use crate::memory::memory;
 use crate::natives::natives;
 use crate::natives::utils;
 use crate::table::Table;
-use crate::values::Value;
+use crate::values::{Unboxed, Value};
 use crate::vm::VM;
 use libffi::middle::{Arg, Cif, CodePtr, Type};
 use libloading::{Library, Symbol};
@@ -34,8 +34,8 @@ pub union FFIValue {
 impl FFIValue {
     /// Creates i8 FFIValue from Value
     pub fn i8(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { i8: i as i8 },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { i8: i as i8 },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -47,8 +47,8 @@ impl FFIValue {
     }
     /// Creates u8 FFIValue from Value
     pub fn u8(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { u8: i as u8 },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { u8: i as u8 },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -60,8 +60,8 @@ impl FFIValue {
     }
     /// Creates i16 FFIValue from Value
     pub fn i16(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { i16: i as i16 },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { i16: i as i16 },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -73,8 +73,8 @@ impl FFIValue {
     }
     /// Creates u16 FFIValue from Value
     pub fn u16(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { u16: i as u16 },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { u16: i as u16 },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -86,8 +86,8 @@ impl FFIValue {
     }
     /// Creates i32 FFIValue from Value
     pub fn i32(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { i32: i as i32 },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { i32: i as i32 },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -99,8 +99,8 @@ impl FFIValue {
     }
     /// Creates u32 FFIValue from Value
     pub fn u32(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { u32: i as u32 },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { u32: i as u32 },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -112,8 +112,8 @@ impl FFIValue {
     }
     /// Creates i64 FFIValue from Value
     pub fn i64(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { i64: i },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { i64: i },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -125,8 +125,8 @@ impl FFIValue {
     }
     /// Creates u64 FFIValue from Value
     pub fn u64(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { u64: i as u64 },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { u64: i as u64 },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -138,8 +138,8 @@ impl FFIValue {
     }
     /// Creates isize FFIValue from Value
     pub fn isize(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { isize: i as isize },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { isize: i as isize },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -151,8 +151,8 @@ impl FFIValue {
     }
     /// Creates usize FFIValue from Value
     pub fn usize(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Int(i) => FFIValue { usize: i as usize },
+        match value.unbox() {
+            Unboxed::Int(i) => FFIValue { usize: i as usize },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -164,8 +164,8 @@ impl FFIValue {
     }
     /// Creates f32 FFIValue from Value
     pub fn f32(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Float(f) => FFIValue { f32: f as f32 },
+        match value.unbox() {
+            Unboxed::Float(f) => FFIValue { f32: f as f32 },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -177,8 +177,8 @@ impl FFIValue {
     }
     /// Creates f64 FFIValue from Value
     pub fn f64(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Float(f) => FFIValue { f64: f },
+        match value.unbox() {
+            Unboxed::Float(f) => FFIValue { f64: f },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -190,8 +190,8 @@ impl FFIValue {
     }
     /// Creates bool FFIValue from Value
     pub fn bool(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Bool(b) => FFIValue { bool: b },
+        match value.unbox() {
+            Unboxed::Bool(b) => FFIValue { bool: b },
             _ => {
                 error!(Error::own_text(
                     address.clone(),
@@ -203,11 +203,18 @@ impl FFIValue {
     }
     /// Creates ptr FFIValue from Value
     pub fn ptr(address: &Address, value: Value) -> Self {
-        match value {
-            Value::Any(a) => FFIValue {
-                ptr: a as *const c_void,
+        match value.unbox() {
+            // foreign pointers are passed as is,
+            // other any values by their data address
+            Unboxed::Any(a) => unsafe {
+                match (**a).downcast_ref::<*mut c_void>() {
+                    Some(foreign) => FFIValue { ptr: *foreign },
+                    None => FFIValue {
+                        ptr: &**a as *const dyn std::any::Any as *const c_void,
+                    },
+                }
             },
-            Value::String(s) => FFIValue {
+            Unboxed::String(s) => FFIValue {
                 ptr: s as *const c_void,
             },
             _ => {
@@ -455,35 +462,35 @@ impl FFILibrary {
         match func.out {
             FFIType::I8 => {
                 let result = func.cif.call::<i8>(func.ptr, &call_args);
-                Value::Int(result as i64)
+                vm.int(result as i64)
             }
             FFIType::U8 => {
                 let result = func.cif.call::<u8>(func.ptr, &call_args);
-                Value::Int(result as i64)
+                vm.int(result as i64)
             }
             FFIType::I16 => {
                 let result = func.cif.call::<i16>(func.ptr, &call_args);
-                Value::Int(result as i64)
+                vm.int(result as i64)
             }
             FFIType::U16 => {
                 let result = func.cif.call::<u16>(func.ptr, &call_args);
-                Value::Int(result as i64)
+                vm.int(result as i64)
             }
             FFIType::I32 => {
                 let result = func.cif.call::<i32>(func.ptr, &call_args);
-                Value::Int(result as i64)
+                vm.int(result as i64)
             }
             FFIType::U32 => {
                 let result = func.cif.call::<u32>(func.ptr, &call_args);
-                Value::Int(result as i64)
+                vm.int(result as i64)
             }
             FFIType::I64 => {
                 let result = func.cif.call::<i64>(func.ptr, &call_args);
-                Value::Int(result)
+                vm.int(result)
             }
             FFIType::U64 => {
                 let result = func.cif.call::<i64>(func.ptr, &call_args);
-                Value::Int(result)
+                vm.int(result)
             }
             FFIType::F32 => {
                 let result = func.cif.call::<f32>(func.ptr, &call_args);
@@ -499,7 +506,7 @@ impl FFILibrary {
             }
             FFIType::Pointer => {
                 let result = func.cif.call::<*mut c_void>(func.ptr, &call_args);
-                let value = Value::Any(result);
+                let value = Value::Any(memory::alloc_any(result));
                 vm.gc_guard(value);
                 vm.gc_register(value, table);
                 vm.gc_unguard();
@@ -515,11 +522,11 @@ impl FFILibrary {
             }
             FFIType::Isize => {
                 let result = func.cif.call::<isize>(func.ptr, &call_args);
-                Value::Int(result as i64)
+                vm.int(result as i64)
             }
             FFIType::Usize => {
                 let result = func.cif.call::<usize>(func.ptr, &call_args);
-                Value::Int(result as i64)
+                vm.int(result as i64)
             }
             FFIType::Bool => {
                 let result = func.cif.call::<bool>(func.ptr, &call_args);
@@ -538,6 +545,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         1,
         "ffi@load",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            vm.permissions().check_ffi(&addr);
             let name = utils::expect_cloned_string(&addr, vm.pop(&addr));
             let path = utils::expect_cloned_string(&addr, vm.pop(&addr));
 
@@ -552,7 +560,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                 }
                 let unwrapped_lib = lib.unwrap();
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(FFILibrary::new(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(FFILibrary::new(
                         unwrapped_lib,
                     )))),
                     table,
@@ -5,21 +5,23 @@ use crate::memory::memory;
 use crate::natives::natives;
 use crate::natives::utils;
 use crate::table::Table;
+use crate::task::{TaskKind, Wake, blocking_poll};
 use crate::values::Value;
 use crate::vm::VM;
 use std::io::{Read, Seek, Write};
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
-/// Gets file from stack
-unsafe fn pop_file<'vm>(
+/// Gets raw file handle from stack,
+/// `None` if file is closed
+unsafe fn pop_handle<'vm>(
     vm: &'vm mut VM,
     addr: &Address,
-) -> Result<&'vm mut std::fs::File, ControlFlow> {
+) -> Result<&'vm mut Option<std::fs::File>, ControlFlow> {
     // getting a raw file
     let raw_file = utils::expect_any(addr, vm.pop(addr), None);
 
-    if !(*raw_file).is::<std::fs::File>() {
+    if !(*raw_file).is::<Option<std::fs::File>>() {
         error!(Error::new(
             addr.clone(),
             "internal type in std.fs.File is not a Rust's `std::io::File`!",
@@ -30,6 +32,21 @@ unsafe fn pop_file<'vm>(
     Ok((*raw_file).downcast_mut().unwrap())
 }
 
+/// Gets file from stack
+unsafe fn pop_file<'vm>(
+    vm: &'vm mut VM,
+    addr: &Address,
+) -> Result<&'vm mut std::fs::File, ControlFlow> {
+    match pop_handle(vm, addr)? {
+        Some(file) => Ok(file),
+        None => error!(Error::new(
+            addr.clone(),
+            "attempted to operate with closed file.",
+            "check file is not closed."
+        )),
+    }
+}
+
 /// Provides
 pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Error> {
     natives::provide(
@@ -41,11 +58,16 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // file name
             let filename = utils::expect_cloned_string(&addr, vm.pop(&addr));
 
+            // checking permissions, file is
+            // writable only with write permission
+            vm.permissions().check_read(&addr, &filename);
+            let writable = vm.permissions().can_write(&filename);
+
             if should_push {
                 // opening file for reading
                 let file = match std::fs::OpenOptions::new()
                     .read(true)
-                    .write(true)
+                    .write(writable)
                     .open(filename)
                 {
                     Ok(file) => file,
@@ -54,7 +76,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         return Ok(());
                     }
                 };
-                let file = memory::alloc_value(file);
+                let file = memory::alloc_any(Some(file));
                 vm.op_push(OpcodeValue::Raw(Value::Any(file)), table)?;
             }
 
@@ -70,10 +92,15 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // file name
             let filename = utils::expect_cloned_string(&addr, vm.pop(&addr));
 
+            // checking permissions, file is
+            // readable only with read permission
+            vm.permissions().check_write(&addr, &filename);
+            let readable = vm.permissions().can_read(&filename);
+
             if should_push {
                 // opening file for reading, writing, creating
                 let file = match std::fs::OpenOptions::new()
-                    .read(true)
+                    .read(readable)
                     .write(true)
                     .create(true)
                     .truncate(false)
@@ -86,13 +113,29 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                         return Ok(());
                     }
                 };
-                let file = memory::alloc_value(file);
+                let file = memory::alloc_any(Some(file));
                 vm.op_push(OpcodeValue::Raw(Value::Any(file)), table)?;
             }
 
             Ok(())
         },
     );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "fs@close",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            // closing file, by dropping handle
+            pop_handle(vm, &addr)?.take();
+
+            if should_push {
+                vm.op_push(OpcodeValue::Raw(Value::Null), table)?;
+            }
+
+            Ok(())
+        },
+    );
     // continue providing
     natives::provide(
         vm,
@@ -191,6 +234,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             // getting directory name
             let name = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_write(&addr, &name);
 
             // creating directory
             let result = std::fs::create_dir(name);
@@ -213,6 +257,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             // getting directory name
             let name = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_write(&addr, &name);
 
             // deleting directory
             let result = std::fs::remove_dir(name);
@@ -235,6 +280,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             // getting directory name
             let name = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_write(&addr, &name);
 
             // deleting directory tree
             let result = std::fs::remove_dir_all(name);
@@ -257,6 +303,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             // getting path and checking existence
             let path = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_read(&addr, &path);
             let result = std::fs::exists(path);
 
             if should_push {
@@ -279,6 +326,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             // getting path
             let path = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_read(&addr, &path);
 
             // reading directory
             if should_push {
@@ -310,6 +358,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             // getting path
             let path = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_read(&addr, &path);
 
             if should_push {
                 let result = std::fs::metadata(path);
@@ -326,5 +375,70 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             Ok(())
         },
     );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "fs@read_async",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            // getting file name
+            let name = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_read(&addr, &name);
+
+            // reading file on blocking pool,
+            // null is pushed, if it's failed
+            let waker = vm.reactor(&addr).waker();
+            let poll = blocking_poll(
+                waker,
+                move || std::fs::read_to_string(name),
+                |vm: &mut VM, _: &Address, table: *mut Table, result| match result {
+                    Ok(text) => vm.op_push(OpcodeValue::String(text), table),
+                    Err(_) => vm.op_push(OpcodeValue::Raw(Value::Null), table),
+                },
+            );
+            let poll = utils::expect_io(&addr, poll);
+            utils::push_task(
+                vm,
+                &addr,
+                should_push,
+                table,
+                TaskKind::Poll(Wake::Waker, poll),
+            )
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "fs@write_async",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            // getting data and file name
+            let data = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            let name = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_write(&addr, &name);
+
+            // writing file on blocking pool,
+            // os error code is pushed, if it's failed
+            let waker = vm.reactor(&addr).waker();
+            let poll = blocking_poll(
+                waker,
+                move || std::fs::write(name, data),
+                |vm: &mut VM, _: &Address, table: *mut Table, result| match result {
+                    Ok(()) => vm.op_push(OpcodeValue::Raw(Value::Null), table),
+                    Err(e) => {
+                        vm.op_push(OpcodeValue::Int(e.raw_os_error().unwrap_or(0) as _), table)
+                    }
+                },
+            );
+            let poll = utils::expect_io(&addr, poll);
+            utils::push_task(
+                vm,
+                &addr,
+                should_push,
+                table,
+                TaskKind::Poll(Wake::Waker, poll),
+            )
+        },
+    );
     Ok(())
 }
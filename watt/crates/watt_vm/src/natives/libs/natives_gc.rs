@@ -1,10 +1,13 @@
 // imports
+use crate::bytecode::OpcodeValue;
+use crate::memory::memory;
 use crate::natives::natives;
+use crate::natives::utils;
 use crate::table::Table;
 use crate::values::Value;
 use crate::vm::VM;
 use watt_common::address::Address;
-use watt_common::errors::Error;
+use watt_common::{error, errors::Error};
 
 /// Provides
 #[allow(unused_variables)]
@@ -22,5 +25,64 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             Ok(())
         },
     );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        0,
+        "gc@stats",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            if should_push {
+                // stats list, in order of std.gc.GcStats fields
+                let stats = vm.gc_stats();
+                let list = vec![
+                    vm.int(stats.collections as i64),
+                    vm.int(stats.objects as i64),
+                    vm.int(stats.instances as i64),
+                    vm.int(stats.lists as i64),
+                    vm.int(stats.strings as i64),
+                    vm.int(stats.fns as i64),
+                    vm.int(stats.natives as i64),
+                    vm.int(stats.units as i64),
+                    vm.int(stats.any as i64),
+                    vm.int(stats.bytes as i64),
+                    Value::Float(stats.last_pause.as_secs_f64() * 1000f64),
+                    Value::Float(stats.total_pause.as_secs_f64() * 1000f64),
+                    vm.int(stats.threshold as i64),
+                    vm.int(stats.minor_collections as i64),
+                    Value::Float(stats.max_pause.as_secs_f64() * 1000f64),
+                    vm.int(stats.types as i64),
+                    vm.int(stats.traits as i64),
+                    vm.int(stats.bigints as i64),
+                ];
+                let list = Value::List(memory::alloc_value(list));
+                vm.op_push(OpcodeValue::Raw(list), table)?;
+            }
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "gc@snapshot",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            // snapshot path
+            let path = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_write(&addr, &path);
+
+            // writing snapshot
+            if let Err(e) = vm.write_heap_snapshot(table, std::path::Path::new(&path)) {
+                error!(Error::own_text(
+                    addr,
+                    format!("failed to write heap snapshot {path}: {e}"),
+                    "check path is writable."
+                ));
+            }
+            if should_push {
+                vm.push(Value::Null)
+            }
+            Ok(())
+        },
+    );
     Ok(())
 }
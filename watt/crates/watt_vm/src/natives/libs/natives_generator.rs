@@ -0,0 +1,78 @@
+// imports
+use crate::generator::Generator;
+use crate::natives::natives;
+use crate::natives::utils;
+use crate::table::Table;
+use crate::values::Value;
+use crate::vm::VM;
+use watt_common::address::Address;
+use watt_common::{error, errors::Error};
+
+/// Gets generator from stack, old generator
+/// is remembered by gc, before body is started
+unsafe fn pop_generator(vm: &mut VM, addr: &Address) -> *mut Generator {
+    // getting a raw generator
+    let value = vm.pop(addr);
+    let raw_generator = utils::expect_any(addr, value, None);
+
+    match (*raw_generator).downcast_mut::<Generator>() {
+        Some(generator) => {
+            if generator.coroutine.is_none() && !generator.done {
+                vm.gc_barrier_coroutine(value);
+            }
+            generator
+        }
+        None => error!(Error::new(
+            addr.clone(),
+            "internal type in Generator is not a generator!",
+            "please, file an issue at https://github.com/vyacheslavhere/watt"
+        )),
+    }
+}
+
+/// Provides
+#[allow(unused_variables)]
+pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Error> {
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "generator@has_next",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let generator = pop_generator(vm, &addr);
+            vm.resume_generator(&addr, generator, table)?;
+
+            if should_push {
+                vm.push(Value::Bool((*generator).peeked.is_some()));
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "generator@next",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let generator = pop_generator(vm, &addr);
+            vm.resume_generator(&addr, generator, table)?;
+
+            let Some(value) = (*generator).peeked.take() else {
+                error!(Error::new(
+                    addr.clone(),
+                    "could not use next.",
+                    "generator has no values left."
+                ))
+            };
+
+            if should_push {
+                vm.push(value);
+            }
+
+            Ok(())
+        },
+    );
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use crate::bytecode::OpcodeValue;
 use crate::memory::memory;
 use crate::natives::natives;
 use crate::table::Table;
-use crate::values::Value;
+use crate::values::{Unboxed, Value};
 use crate::vm::VM;
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
@@ -40,8 +40,10 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // список
             let list_value = vm.pop(&addr);
             // проверяем
-            if let Value::List(list) = list_value {
+            if let Unboxed::List(list) = list_value.unbox() {
                 (*list).push(value);
+                // барьер записи gc
+                vm.gc_barrier_value(list_value, value);
             } else {
                 error!(Error::own_text(
                     addr.clone(),
@@ -70,9 +72,11 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // список
             let list_value = vm.pop(&addr);
             // проверяем
-            if let Value::List(list) = list_value {
-                if let Value::Int(index) = index_value {
+            if let Unboxed::List(list) = list_value.unbox() {
+                if let Unboxed::Int(index) = index_value.unbox() {
                     (*list)[index as usize] = value;
+                    // барьер записи gc
+                    vm.gc_barrier_value(list_value, value);
                 } else {
                     error!(Error::own_text(
                         addr.clone(),
@@ -108,8 +112,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // список
             let list_value = vm.pop(&addr);
             // проверяем
-            if let Value::List(list) = list_value {
-                if let Value::Int(index) = index_value {
+            if let Unboxed::List(list) = list_value.unbox() {
+                if let Unboxed::Int(index) = index_value.unbox() {
                     // проверка на боунды
                     if index < 0 || index as usize >= (*list).len() {
                         error!(Error::own_text(
@@ -155,8 +159,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // список
             let list_value = vm.pop(&addr);
             // проверяем
-            if let Value::List(list) = list_value {
-                if let Value::Int(index) = index_value {
+            if let Unboxed::List(list) = list_value.unbox() {
+                if let Unboxed::Int(index) = index_value.unbox() {
                     // проверка на боунды
                     if index < 0 || index as usize > (*list).len() {
                         error!(Error::own_text(
@@ -202,7 +206,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // список
             let list_value = vm.pop(&addr);
             // проверяем
-            if let Value::List(list) = list_value {
+            if let Unboxed::List(list) = list_value.unbox() {
                 for (index, element) in (*list).iter().enumerate() {
                     if *element == value {
                         (*list).remove(index);
@@ -231,11 +235,11 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // список
             let list_value = vm.pop(&addr);
             // проверяем
-            if let Value::List(list) = list_value {
+            if let Unboxed::List(list) = list_value.unbox() {
                 // если надо пушить
                 if should_push {
                     let position = (*list).iter().position(|v| *v == value);
-                    vm.push(Value::Int(position.unwrap_or(0) as i64))
+                    vm.op_push(OpcodeValue::Int(position.unwrap_or(0) as i64), table)?
                 }
             } else {
                 error!(Error::own_text(
@@ -257,10 +261,10 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             // список
             let list_value = vm.pop(&addr);
             // проверяем
-            if let Value::List(list) = list_value {
+            if let Unboxed::List(list) = list_value.unbox() {
                 // если надо пушить
                 if should_push {
-                    vm.push(Value::Int((*list).len() as i64));
+                    vm.op_push(OpcodeValue::Int((*list).len() as i64), table)?;
                 }
             } else {
                 error!(Error::own_text(
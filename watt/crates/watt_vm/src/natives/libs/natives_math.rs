@@ -1,7 +1,7 @@
 // imports
 use crate::natives::natives;
 use crate::table::Table;
-use crate::values::Value;
+use crate::values::{Unboxed, Value};
 use crate::vm::VM;
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
@@ -18,9 +18,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = vm.pop(&addr);
 
             if should_push {
-                match value {
-                    Value::Float(f64) => vm.push(Value::Float(f64::sin(f64))),
-                    Value::Int(i64) => vm.push(Value::Float(f64::sin(i64 as f64))),
+                match value.unbox() {
+                    Unboxed::Float(f64) => vm.push(Value::Float(f64::sin(f64))),
+                    Unboxed::Int(i64) => vm.push(Value::Float(f64::sin(i64 as f64))),
                     _ => {
                         error!(Error::own_text(
                             addr,
@@ -43,9 +43,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = vm.pop(&addr);
 
             if should_push {
-                match value {
-                    Value::Float(f64) => vm.push(Value::Float(f64::cos(f64))),
-                    Value::Int(i64) => vm.push(Value::Float(f64::cos(i64 as f64))),
+                match value.unbox() {
+                    Unboxed::Float(f64) => vm.push(Value::Float(f64::cos(f64))),
+                    Unboxed::Int(i64) => vm.push(Value::Float(f64::cos(i64 as f64))),
                     _ => {
                         error!(Error::own_text(
                             addr,
@@ -68,9 +68,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = vm.pop(&addr);
 
             if should_push {
-                match value {
-                    Value::Float(f64) => vm.push(Value::Float(f64::asin(f64))),
-                    Value::Int(i64) => vm.push(Value::Float(f64::asin(i64 as f64))),
+                match value.unbox() {
+                    Unboxed::Float(f64) => vm.push(Value::Float(f64::asin(f64))),
+                    Unboxed::Int(i64) => vm.push(Value::Float(f64::asin(i64 as f64))),
                     _ => {
                         error!(Error::own_text(
                             addr,
@@ -93,9 +93,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = vm.pop(&addr);
 
             if should_push {
-                match value {
-                    Value::Float(f64) => vm.push(Value::Float(f64::acos(f64))),
-                    Value::Int(i64) => vm.push(Value::Float(f64::acos(i64 as f64))),
+                match value.unbox() {
+                    Unboxed::Float(f64) => vm.push(Value::Float(f64::acos(f64))),
+                    Unboxed::Int(i64) => vm.push(Value::Float(f64::acos(i64 as f64))),
                     _ => {
                         error!(Error::own_text(
                             addr,
@@ -118,9 +118,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = vm.pop(&addr);
 
             if should_push {
-                match value {
-                    Value::Float(f64) => vm.push(Value::Float(f64::atan(f64))),
-                    Value::Int(i64) => vm.push(Value::Float(f64::atan(i64 as f64))),
+                match value.unbox() {
+                    Unboxed::Float(f64) => vm.push(Value::Float(f64::atan(f64))),
+                    Unboxed::Int(i64) => vm.push(Value::Float(f64::atan(i64 as f64))),
                     _ => {
                         error!(Error::own_text(
                             addr,
@@ -143,9 +143,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = vm.pop(&addr);
 
             if should_push {
-                match value {
-                    Value::Float(f64) => vm.push(Value::Float(f64::tan(f64))),
-                    Value::Int(i64) => vm.push(Value::Float(f64::tan(i64 as f64))),
+                match value.unbox() {
+                    Unboxed::Float(f64) => vm.push(Value::Float(f64::tan(f64))),
+                    Unboxed::Int(i64) => vm.push(Value::Float(f64::tan(i64 as f64))),
                     _ => {
                         error!(Error::own_text(
                             addr,
@@ -168,9 +168,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = vm.pop(&addr);
 
             if should_push {
-                match value {
-                    Value::Float(f64) => vm.push(Value::Float(1.0 / f64::tan(f64))),
-                    Value::Int(i64) => vm.push(Value::Float(1.0 / f64::tan(i64 as f64))),
+                match value.unbox() {
+                    Unboxed::Float(f64) => vm.push(Value::Float(1.0 / f64::tan(f64))),
+                    Unboxed::Int(i64) => vm.push(Value::Float(1.0 / f64::tan(i64 as f64))),
                     _ => {
                         error!(Error::own_text(
                             addr,
@@ -193,9 +193,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = vm.pop(&addr);
 
             if should_push {
-                match value {
-                    Value::Float(f64) => vm.push(Value::Float(f64::tanh(f64))),
-                    Value::Int(i64) => vm.push(Value::Float(f64::tanh(i64 as f64))),
+                match value.unbox() {
+                    Unboxed::Float(f64) => vm.push(Value::Float(f64::tanh(f64))),
+                    Unboxed::Int(i64) => vm.push(Value::Float(f64::tanh(i64 as f64))),
                     _ => {
                         error!(Error::own_text(
                             addr,
@@ -5,8 +5,12 @@ use crate::memory::memory;
 use crate::natives::natives;
 use crate::natives::utils;
 use crate::table::Table;
+use crate::task::{TaskKind, Wake, blocking_poll};
 use crate::values::Value;
 use crate::vm::VM;
+use mio::Interest;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
@@ -29,12 +33,16 @@ unsafe fn pop_request(vm: &mut VM, addr: &Address) -> Result<minreq::Request, Co
         .clone())
 }
 
-/// Gets response from stack
-unsafe fn pop_response(vm: &mut VM, addr: &Address) -> Result<minreq::Response, ControlFlow> {
+/// Gets raw response handle from stack,
+/// `None` if response is closed
+unsafe fn pop_response_handle<'vm>(
+    vm: &'vm mut VM,
+    addr: &Address,
+) -> Result<&'vm mut Option<minreq::Response>, ControlFlow> {
     // getting a raw request
     let raw_request = utils::expect_any(addr, vm.pop(addr), None);
 
-    if !(*raw_request).is::<minreq::Response>() {
+    if !(*raw_request).is::<Option<minreq::Response>>() {
         error!(Error::new(
             addr.clone(),
             "internal builder in std.net.Response is not a `minreq::Response`!",
@@ -42,10 +50,135 @@ unsafe fn pop_response(vm: &mut VM, addr: &Address) -> Result<minreq::Response,
         ));
     }
 
-    Ok((*raw_request)
-        .downcast_mut::<minreq::Response>()
-        .unwrap()
-        .clone())
+    Ok((*raw_request).downcast_mut().unwrap())
+}
+
+/// Gets response from stack
+unsafe fn pop_response(vm: &mut VM, addr: &Address) -> Result<minreq::Response, ControlFlow> {
+    match pop_response_handle(vm, addr)? {
+        Some(response) => Ok(response.clone()),
+        None => error!(Error::new(
+            addr.clone(),
+            "attempted to operate with closed response.",
+            "check response is not closed."
+        )),
+    }
+}
+
+/// Gets listener handle from stack,
+/// `None` if listener is closed
+unsafe fn pop_listener_handle<'vm>(
+    vm: &'vm mut VM,
+    addr: &Address,
+) -> &'vm mut Option<TcpListener> {
+    // getting a raw listener
+    let raw_listener = utils::expect_any(addr, vm.pop(addr), None);
+
+    match (*raw_listener).downcast_mut() {
+        Some(listener) => listener,
+        None => error!(Error::new(
+            addr.clone(),
+            "internal handle in std.net.Listener is not a `std::net::TcpListener`!",
+            "please file an issue at https://github.com/vyacheslavhere/watt"
+        )),
+    }
+}
+
+/// Gets listener from stack
+unsafe fn pop_listener<'vm>(vm: &'vm mut VM, addr: &Address) -> &'vm mut TcpListener {
+    match pop_listener_handle(vm, addr) {
+        Some(listener) => listener,
+        None => error!(Error::new(
+            addr.clone(),
+            "attempted to operate with closed listener.",
+            "check listener is not closed."
+        )),
+    }
+}
+
+/// Gets socket handle from stack,
+/// `None` if socket is closed
+unsafe fn pop_socket_handle<'vm>(vm: &'vm mut VM, addr: &Address) -> &'vm mut Option<TcpStream> {
+    // getting a raw socket
+    let raw_socket = utils::expect_any(addr, vm.pop(addr), None);
+
+    match (*raw_socket).downcast_mut() {
+        Some(socket) => socket,
+        None => error!(Error::new(
+            addr.clone(),
+            "internal handle in std.net.Socket is not a `std::net::TcpStream`!",
+            "please file an issue at https://github.com/vyacheslavhere/watt"
+        )),
+    }
+}
+
+/// Gets socket from stack, cloned to be
+/// owned by task, that polls it
+unsafe fn pop_socket(vm: &mut VM, addr: &Address) -> TcpStream {
+    let socket = match pop_socket_handle(vm, addr) {
+        Some(socket) => socket.try_clone(),
+        None => error!(Error::new(
+            addr.clone(),
+            "attempted to operate with closed socket.",
+            "check socket is not closed."
+        )),
+    };
+    match socket {
+        Ok(socket) => socket,
+        Err(err) => error!(Error::own_text(
+            addr.clone(),
+            format!("failed to use socket: {err}"),
+            "check your socket."
+        )),
+    }
+}
+
+/// Starts connecting to next of resolved `addresses`,
+/// raises error, if all of them are failed
+fn connect_next(
+    addr: &Address,
+    address: &str,
+    addresses: &mut Vec<SocketAddr>,
+    mut last: Option<std::io::Error>,
+) -> mio::net::TcpStream {
+    while let Some(next) = addresses.pop() {
+        match mio::net::TcpStream::connect(next) {
+            Ok(socket) => return socket,
+            Err(err) => last = Some(err),
+        }
+    }
+    match last {
+        Some(err) => error!(Error::own_text(
+            addr.clone(),
+            format!("failed to connect {address}: {err}"),
+            "check address is valid and listened."
+        )),
+        None => error!(Error::own_text(
+            addr.clone(),
+            format!("failed to connect {address}: no addresses resolved."),
+            "check address is valid."
+        )),
+    }
+}
+
+/// Pushes non-blocking socket handle
+unsafe fn push_socket(
+    vm: &mut VM,
+    addr: &Address,
+    table: *mut Table,
+    socket: TcpStream,
+) -> Result<(), ControlFlow> {
+    if let Err(err) = socket.set_nonblocking(true) {
+        error!(Error::own_text(
+            addr.clone(),
+            format!("failed to use socket: {err}"),
+            "check your socket."
+        ))
+    }
+    vm.op_push(
+        OpcodeValue::Raw(Value::Any(memory::alloc_any(Some(socket)))),
+        table,
+    )
 }
 
 /// Provides
@@ -58,7 +191,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "net@get",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let url = utils::expect_cloned_string(&addr, vm.pop(&addr));
-            let requst = memory::alloc_value(minreq::get(url));
+            vm.permissions().check_net(&addr, &url);
+            let requst = memory::alloc_any(minreq::get(url));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(requst)), table)?;
@@ -74,7 +208,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "net@post",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let url = utils::expect_cloned_string(&addr, vm.pop(&addr));
-            let requst = memory::alloc_value(minreq::post(url));
+            vm.permissions().check_net(&addr, &url);
+            let requst = memory::alloc_any(minreq::post(url));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(requst)), table)?;
@@ -90,7 +225,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "net@put",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let url = utils::expect_cloned_string(&addr, vm.pop(&addr));
-            let requst = memory::alloc_value(minreq::put(url));
+            vm.permissions().check_net(&addr, &url);
+            let requst = memory::alloc_any(minreq::put(url));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(requst)), table)?;
@@ -106,7 +242,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "net@options",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let url = utils::expect_cloned_string(&addr, vm.pop(&addr));
-            let requst = memory::alloc_value(minreq::options(url));
+            vm.permissions().check_net(&addr, &url);
+            let requst = memory::alloc_any(minreq::options(url));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(requst)), table)?;
@@ -122,7 +259,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "net@delete",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let url = utils::expect_cloned_string(&addr, vm.pop(&addr));
-            let requst = memory::alloc_value(minreq::delete(url));
+            vm.permissions().check_net(&addr, &url);
+            let requst = memory::alloc_any(minreq::delete(url));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(requst)), table)?;
@@ -138,7 +276,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "net@patch",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let url = utils::expect_cloned_string(&addr, vm.pop(&addr));
-            let requst = memory::alloc_value(minreq::patch(url));
+            vm.permissions().check_net(&addr, &url);
+            let requst = memory::alloc_any(minreq::patch(url));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(requst)), table)?;
@@ -154,7 +293,8 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "net@head",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let url = utils::expect_cloned_string(&addr, vm.pop(&addr));
-            let requst = memory::alloc_value(minreq::head(url));
+            vm.permissions().check_net(&addr, &url);
+            let requst = memory::alloc_any(minreq::head(url));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(requst)), table)?;
@@ -172,7 +312,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let value = utils::expect_cloned_string(&addr, vm.pop(&addr));
             let key = utils::expect_cloned_string(&addr, vm.pop(&addr));
             let cloned_request: minreq::Request = pop_request(vm, &addr)?;
-            let request = memory::alloc_value(cloned_request.with_header(key, value));
+            let request = memory::alloc_any(cloned_request.with_header(key, value));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(request)), table)?;
@@ -189,7 +329,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let data = utils::expect_cloned_string(&addr, vm.pop(&addr));
             let cloned_request: minreq::Request = pop_request(vm, &addr)?;
-            let request = memory::alloc_value(cloned_request.with_body(data));
+            let request = memory::alloc_any(cloned_request.with_body(data));
 
             if should_push {
                 vm.op_push(OpcodeValue::Raw(Value::Any(request)), table)?;
@@ -209,9 +349,10 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
 
             if should_push {
                 match result {
-                    Ok(ok) => {
-                        vm.op_push(OpcodeValue::Raw(Value::Any(memory::alloc_value(ok))), table)?
-                    }
+                    Ok(ok) => vm.op_push(
+                        OpcodeValue::Raw(Value::Any(memory::alloc_any(Some(ok)))),
+                        table,
+                    )?,
                     Err(err) => error!(Error::own_text(
                         addr.clone(),
                         format!("failed the request: {err}"),
@@ -297,9 +438,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
 
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(
-                        response.as_bytes().to_vec(),
-                    ))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(response.as_bytes().to_vec()))),
                     table,
                 )?;
             }
@@ -307,5 +446,358 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             Ok(())
         },
     );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@response_close",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            // closing response, by dropping it
+            pop_response_handle(vm, &addr)?.take();
+
+            if should_push {
+                vm.push(Value::Null);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@send_async",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let request: minreq::Request = pop_request(vm, &addr)?;
+
+            // sending request on blocking pool
+            let waker = vm.reactor(&addr).waker();
+            let poll = blocking_poll(
+                waker,
+                move || request.send(),
+                |vm: &mut VM, addr: &Address, table: *mut Table, result| match result {
+                    Ok(ok) => vm.op_push(
+                        OpcodeValue::Raw(Value::Any(memory::alloc_any(Some(ok)))),
+                        table,
+                    ),
+                    Err(err) => error!(Error::own_text(
+                        addr.clone(),
+                        format!("failed the request: {err}"),
+                        "check your request."
+                    )),
+                },
+            );
+            let poll = utils::expect_io(&addr, poll);
+            utils::push_task(
+                vm,
+                &addr,
+                should_push,
+                table,
+                TaskKind::Poll(Wake::Waker, poll),
+            )
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@listen",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let address = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_net(&addr, &address);
+
+            // binding non-blocking listener
+            let listener = match TcpListener::bind(&address)
+                .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            {
+                Ok(listener) => listener,
+                Err(err) => error!(Error::own_text(
+                    addr.clone(),
+                    format!("failed to listen {address}: {err}"),
+                    "check address is valid and not in use."
+                )),
+            };
+
+            if should_push {
+                vm.op_push(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(Some(listener)))),
+                    table,
+                )?;
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@listener_address",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let listener = pop_listener(vm, &addr);
+
+            if should_push {
+                match listener.local_addr() {
+                    Ok(address) => vm.op_push(OpcodeValue::String(address.to_string()), table)?,
+                    Err(err) => error!(Error::own_text(
+                        addr.clone(),
+                        format!("failed to get listener address: {err}"),
+                        "check your listener."
+                    )),
+                }
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@accept",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let listener = match pop_listener(vm, &addr).try_clone() {
+                Ok(listener) => listener,
+                Err(err) => error!(Error::own_text(
+                    addr.clone(),
+                    format!("failed to use listener: {err}"),
+                    "check your listener."
+                )),
+            };
+
+            // polling incoming connection, when listener is readable
+            let mut listener = utils::register(
+                vm,
+                &addr,
+                mio::net::TcpListener::from_std(listener),
+                Interest::READABLE,
+            );
+            let wake = Wake::Io(listener.token);
+            let poll =
+                Box::new(move |vm: &mut VM, addr: &Address, table: *mut Table| {
+                    match listener.get().accept() {
+                        Ok((socket, _)) => {
+                            push_socket(vm, addr, table, TcpStream::from(socket))?;
+                            Ok(true)
+                        }
+                        Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(false),
+                        Err(err) => error!(Error::own_text(
+                            addr.clone(),
+                            format!("failed to accept connection: {err}"),
+                            "check your listener."
+                        )),
+                    }
+                });
+            utils::push_task(vm, &addr, should_push, table, TaskKind::Poll(wake, poll))
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@listener_close",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            // closing listener, by dropping it
+            pop_listener_handle(vm, &addr).take();
+
+            if should_push {
+                vm.push(Value::Null);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@connect",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let address = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            vm.permissions().check_net(&addr, &address);
+
+            // resolving address
+            let mut addresses: Vec<SocketAddr> = match address.to_socket_addrs() {
+                Ok(addresses) => addresses.collect(),
+                Err(err) => error!(Error::own_text(
+                    addr.clone(),
+                    format!("failed to resolve {address}: {err}"),
+                    "check address is valid."
+                )),
+            };
+            addresses.reverse();
+
+            // connecting, address by address, socket
+            // is connected, when it becomes writable
+            let connecting = connect_next(&addr, &address, &mut addresses, None);
+            let registered = utils::register(vm, &addr, connecting, Interest::WRITABLE);
+            let wake = Wake::Io(registered.token);
+            let mut socket = Some(registered);
+            let poll = Box::new(move |vm: &mut VM, addr: &Address, table: *mut Table| {
+                let Some(current) = socket.as_mut() else {
+                    return Ok(false);
+                };
+                let error = match current.get().take_error() {
+                    Ok(None) => match current.get().peer_addr() {
+                        Ok(_) => None,
+                        Err(err)
+                            if err.kind() == ErrorKind::NotConnected
+                                || err.kind() == ErrorKind::WouldBlock =>
+                        {
+                            return Ok(false);
+                        }
+                        Err(err) => Some(err),
+                    },
+                    Ok(Some(err)) | Err(err) => Some(err),
+                };
+                match error {
+                    // retrying with next address
+                    Some(err) => {
+                        let next = connect_next(addr, &address, &mut addresses, Some(err));
+                        utils::expect_io(addr, current.replace(next, Interest::WRITABLE));
+                        Ok(false)
+                    }
+                    None => {
+                        let connected = socket.take().unwrap().into_inner();
+                        push_socket(vm, addr, table, TcpStream::from(connected))?;
+                        Ok(true)
+                    }
+                }
+            });
+            utils::push_task(vm, &addr, should_push, table, TaskKind::Poll(wake, poll))
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "net@socket_read",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let max = utils::expect_int(&addr, vm.pop(&addr));
+            let socket = pop_socket(vm, &addr);
+            if max <= 0 {
+                error!(Error::own_text(
+                    addr.clone(),
+                    format!("couldn't read {max} bytes."),
+                    "bytes amount should be positive."
+                ))
+            }
+
+            // polling socket data, when socket is readable,
+            // empty string is read, when socket is closed
+            let mut socket = utils::register(
+                vm,
+                &addr,
+                mio::net::TcpStream::from_std(socket),
+                Interest::READABLE,
+            );
+            let wake = Wake::Io(socket.token);
+            let mut buffer = vec![0; max as usize];
+            let poll =
+                Box::new(move |vm: &mut VM, addr: &Address, table: *mut Table| {
+                    match socket.get().read(&mut buffer) {
+                        Ok(amount) => {
+                            let text = String::from_utf8_lossy(&buffer[..amount]).into_owned();
+                            vm.op_push(OpcodeValue::String(text), table)?;
+                            Ok(true)
+                        }
+                        Err(err)
+                            if err.kind() == ErrorKind::WouldBlock
+                                || err.kind() == ErrorKind::Interrupted =>
+                        {
+                            Ok(false)
+                        }
+                        Err(err) => error!(Error::own_text(
+                            addr.clone(),
+                            format!("failed to read socket: {err}"),
+                            "check your socket."
+                        )),
+                    }
+                });
+            utils::push_task(vm, &addr, should_push, table, TaskKind::Poll(wake, poll))
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "net@socket_write",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let data = utils::expect_cloned_string(&addr, vm.pop(&addr));
+            let socket = pop_socket(vm, &addr);
+
+            // polling, until all data is written,
+            // when socket is writable
+            let mut socket = utils::register(
+                vm,
+                &addr,
+                mio::net::TcpStream::from_std(socket),
+                Interest::WRITABLE,
+            );
+            let wake = Wake::Io(socket.token);
+            let mut written = 0;
+            let poll = Box::new(move |vm: &mut VM, addr: &Address, table: *mut Table| {
+                while written < data.len() {
+                    match socket.get().write(&data.as_bytes()[written..]) {
+                        Ok(amount) => written += amount,
+                        Err(err)
+                            if err.kind() == ErrorKind::WouldBlock
+                                || err.kind() == ErrorKind::Interrupted =>
+                        {
+                            return Ok(false);
+                        }
+                        Err(err) => error!(Error::own_text(
+                            addr.clone(),
+                            format!("failed to write socket: {err}"),
+                            "check your socket."
+                        )),
+                    }
+                }
+                vm.push(Value::Null);
+                Ok(true)
+            });
+            utils::push_task(vm, &addr, should_push, table, TaskKind::Poll(wake, poll))
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@socket_peer",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let socket = pop_socket(vm, &addr);
+
+            if should_push {
+                match socket.peer_addr() {
+                    Ok(address) => vm.op_push(OpcodeValue::String(address.to_string()), table)?,
+                    Err(err) => error!(Error::own_text(
+                        addr.clone(),
+                        format!("failed to get socket peer: {err}"),
+                        "check your socket."
+                    )),
+                }
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "net@socket_close",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            // closing socket, pending reads are finished
+            if let Some(socket) = pop_socket_handle(vm, &addr).take() {
+                let _ = socket.shutdown(Shutdown::Both);
+            }
+
+            if should_push {
+                vm.push(Value::Null);
+            }
+
+            Ok(())
+        },
+    );
     Ok(())
 }
@@ -263,7 +263,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let string = utils::expect_cloned_string(&addr, vm.pop(&addr));
             if should_push {
-                vm.push(Value::Int(string.len() as i64));
+                vm.op_push(OpcodeValue::Int(string.len() as i64), table)?;
             }
             Ok(())
         },
@@ -334,7 +334,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
 
             if should_push {
                 let ch = raw_ch.chars().next().unwrap();
-                vm.push(Value::Int(ch as i64));
+                vm.op_push(OpcodeValue::Int(ch as i64), table)?;
             }
 
             Ok(())
@@ -4,13 +4,70 @@ use crate::memory::memory::{self};
 use crate::natives::natives;
 use crate::natives::utils;
 use crate::table::Table;
+use crate::task::{Task, TaskKind, Wake};
 use crate::values::Value;
 use crate::vm::VM;
+use std::any::Any;
 use std::process::Command;
+use std::time::Duration;
 use sysinfo::System;
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
+/// Process exit polling interval, used without pidfd
+const EXIT_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Pidfd of process, readable, when it exits
+#[cfg(target_os = "linux")]
+struct PidFd(std::os::fd::OwnedFd);
+/// Source implementation
+#[cfg(target_os = "linux")]
+impl mio::event::Source for PidFd {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> std::io::Result<()> {
+        use std::os::fd::AsRawFd;
+        mio::unix::SourceFd(&self.0.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> std::io::Result<()> {
+        use std::os::fd::AsRawFd;
+        mio::unix::SourceFd(&self.0.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> std::io::Result<()> {
+        use std::os::fd::AsRawFd;
+        mio::unix::SourceFd(&self.0.as_raw_fd()).deregister(registry)
+    }
+}
+
+/// Wake of process exit, pidfd is registered in
+/// reactor on linux, otherwise exit is polled by interval,
+/// returns wake and registered source, kept by task
+fn exit_wake(vm: &mut VM, addr: &Address, pid: Option<u32>) -> (Wake, Option<Box<dyn Any>>) {
+    #[cfg(target_os = "linux")]
+    if let Some(pid) = pid {
+        use std::os::fd::{FromRawFd, OwnedFd};
+        // pidfd_open is unsupported before linux 5.3
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if fd >= 0 {
+            let pidfd = PidFd(unsafe { OwnedFd::from_raw_fd(fd as _) });
+            let pidfd = utils::register(vm, addr, pidfd, mio::Interest::READABLE);
+            return (Wake::Io(pidfd.token), Some(Box::new(pidfd)));
+        }
+    }
+    let _ = (vm, addr, pid);
+    (Wake::Interval(EXIT_INTERVAL), None)
+}
+
 /// Provides
 #[allow(unused_variables)]
 pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Error> {
@@ -20,6 +77,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         1,
         "system@getenv",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            vm.permissions().check_env(&addr);
             if !should_push {
                 return Ok(());
             }
@@ -44,6 +102,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         2,
         "system@setenv",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            vm.permissions().check_env(&addr);
             let env_value = &*utils::expect_string(&addr, vm.pop(&addr));
             let env_key = &*utils::expect_string(&addr, vm.pop(&addr));
 
@@ -80,6 +139,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                 }
             };
 
+            // cwd is revealed only if it could be read
+            vm.permissions().check_read(&addr, &cwd);
+
             vm.op_push(OpcodeValue::String(cwd), table)?;
             Ok(())
         },
@@ -90,6 +152,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         0,
         "system@getargs",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            vm.permissions().check_env(&addr);
             if !should_push {
                 return Ok(());
             }
@@ -134,7 +197,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                 sysinfo::RefreshKind::nothing()
                     .with_memory(sysinfo::MemoryRefreshKind::everything()),
             );
-            vm.push(Value::Int(system_info.total_memory() as _));
+            vm.op_push(OpcodeValue::Int(system_info.total_memory() as _), table)?;
 
             Ok(())
         },
@@ -153,7 +216,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                 sysinfo::RefreshKind::nothing()
                     .with_memory(sysinfo::MemoryRefreshKind::everything()),
             );
-            vm.push(Value::Int(system_info.used_memory() as _));
+            vm.op_push(OpcodeValue::Int(system_info.used_memory() as _), table)?;
 
             Ok(())
         },
@@ -169,11 +232,14 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                 return Ok(());
             }
 
-            vm.push(Value::Int(
-                std::thread::available_parallelism()
-                    .map(|x| x.get())
-                    .unwrap_or(1) as _,
-            ));
+            vm.op_push(
+                OpcodeValue::Int(
+                    std::thread::available_parallelism()
+                        .map(|x| x.get())
+                        .unwrap_or(1) as _,
+                ),
+                table,
+            )?;
 
             Ok(())
         },
@@ -184,7 +250,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         0,
         "system@this_process_id",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
-            vm.push(Value::Int(std::process::id() as _));
+            vm.op_push(OpcodeValue::Int(std::process::id() as _), table)?;
             Ok(())
         },
     );
@@ -214,6 +280,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             }
 
             let command = &*utils::expect_string(&addr, vm.pop(&addr));
+            vm.permissions().check_run(&addr);
             let mut descriptor = if cfg!(target_os = "windows") {
                 let mut shell = Command::new("cmd");
                 shell.args(["/C", command]);
@@ -227,7 +294,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             match descriptor.spawn() {
                 Ok(child) => {
                     vm.op_push(
-                        OpcodeValue::Raw(Value::Any(memory::alloc_value(child))),
+                        OpcodeValue::Raw(Value::Any(memory::alloc_any(Some(child)))),
                         table,
                     )?;
                 }
@@ -246,7 +313,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "system@process_wait",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let child = utils::expect_any(&addr, vm.pop(&addr), None);
-            let child: Option<&mut std::process::Child> = (*child).downcast_mut();
+            let child: Option<&mut std::process::Child> = (*child)
+                .downcast_mut::<Option<std::process::Child>>()
+                .and_then(Option::as_mut);
 
             match child {
                 Some(ch) => {
@@ -254,7 +323,10 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
                     if should_push {
                         match value {
                             Ok(status) => {
-                                vm.push(Value::Int(status.code().unwrap_or(0) as _));
+                                vm.op_push(
+                                    OpcodeValue::Int(status.code().unwrap_or(0) as _),
+                                    table,
+                                )?;
                             }
                             Err(e) => {
                                 vm.push(Value::Null);
@@ -275,6 +347,54 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         },
     );
 
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "system@process_wait_async",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let handle = vm.pop(&addr);
+            let child = utils::expect_any(&addr, handle, None);
+            if !(*child).is::<Option<std::process::Child>>() {
+                error!(Error::new(
+                    addr.clone(),
+                    "the inner raw value is not a `std::process::Child`",
+                    "please file an issue at https://github.com/vyacheslavhere/watt"
+                ))
+            }
+
+            // polling process exit, handle is kept alive
+            // by task, null is pushed, if handle is closed
+            let pid = (*child)
+                .downcast_ref::<Option<std::process::Child>>()
+                .and_then(Option::as_ref)
+                .map(std::process::Child::id);
+            let (wake, mut source) = exit_wake(vm, &addr, pid);
+            let poll = Box::new(move |vm: &mut VM, addr: &Address, table: *mut Table| {
+                let child = (*child)
+                    .downcast_mut::<Option<std::process::Child>>()
+                    .and_then(Option::as_mut);
+                match child.map(|ch| ch.try_wait()) {
+                    Some(Ok(None)) => return Ok(false),
+                    Some(Ok(Some(status))) => {
+                        vm.op_push(OpcodeValue::Int(status.code().unwrap_or(0) as _), table)?
+                    }
+                    Some(Err(_)) | None => vm.push(Value::Null),
+                }
+                source.take();
+                Ok(true)
+            });
+            let mut task = Task::new(addr.clone(), TaskKind::Poll(wake, poll));
+            task.refs.push(handle);
+            let task = vm.spawn_task(task, table, table)?;
+
+            if should_push {
+                vm.push(task);
+            }
+
+            Ok(())
+        },
+    );
     natives::provide(
         vm,
         built_in_address.clone(),
@@ -282,7 +402,9 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "system@process_terminate",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let child = utils::expect_any(&addr, vm.pop(&addr), None);
-            let child: Option<&mut std::process::Child> = (*child).downcast_mut();
+            let child: Option<&mut std::process::Child> = (*child)
+                .downcast_mut::<Option<std::process::Child>>()
+                .and_then(Option::as_mut);
 
             match child {
                 Some(ch) => {
@@ -304,6 +426,37 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             Ok(())
         },
     );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "system@process_close",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let child = utils::expect_any(&addr, vm.pop(&addr), None);
+            let child: Option<&mut Option<std::process::Child>> = (*child).downcast_mut();
+
+            match child {
+                // closing process handle, by dropping child,
+                // process itself keeps running
+                Some(ch) => {
+                    ch.take();
+                }
+                None => {
+                    error!(Error::new(
+                        addr.clone(),
+                        "The inner raw value is not a `std::process::Child`",
+                        "please file an issue at https://github.com/vyacheslavhere/watt"
+                    ))
+                }
+            }
+
+            if should_push {
+                vm.push(Value::Null);
+            }
+
+            Ok(())
+        },
+    );
     natives::provide(
         vm,
         built_in_address.clone(),
@@ -311,12 +464,14 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         "system@process_id",
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let child = utils::expect_any(&addr, vm.pop(&addr), None);
-            let child: Option<&mut std::process::Child> = (*child).downcast_mut();
+            let child: Option<&mut std::process::Child> = (*child)
+                .downcast_mut::<Option<std::process::Child>>()
+                .and_then(Option::as_mut);
 
             match child {
                 Some(ch) => {
                     if should_push {
-                        vm.push(Value::Int(ch.id() as _));
+                        vm.op_push(OpcodeValue::Int(ch.id() as _), table)?;
                     }
                 }
                 None => {
@@ -0,0 +1,46 @@
+// imports
+use crate::natives::natives;
+use crate::natives::utils;
+use crate::table::Table;
+use crate::task::Task;
+use crate::values::Value;
+use crate::vm::VM;
+use watt_common::address::Address;
+use watt_common::{error, errors::Error};
+
+/// Gets task from stack
+unsafe fn pop_task(vm: &mut VM, addr: &Address) -> *mut Task {
+    // getting a raw task
+    let raw_task = utils::expect_any(addr, vm.pop(addr), None);
+
+    match (*raw_task).downcast_mut::<Task>() {
+        Some(task) => task,
+        None => error!(Error::new(
+            addr.clone(),
+            "internal type in Task is not a task!",
+            "please, file an issue at https://github.com/vyacheslavhere/watt"
+        )),
+    }
+}
+
+/// Provides
+#[allow(unused_variables)]
+pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Error> {
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "task@is_done",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let task = pop_task(vm, &addr);
+
+            if should_push {
+                vm.push(Value::Bool((*task).is_done()));
+            }
+
+            Ok(())
+        },
+    );
+
+    Ok(())
+}
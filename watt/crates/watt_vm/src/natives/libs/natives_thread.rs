@@ -0,0 +1,729 @@
+// imports
+use crate::bytecode::{Chunk, Opcode, OpcodeValue};
+use crate::flow::ControlFlow;
+use crate::memory::memory;
+use crate::natives::natives;
+use crate::natives::utils;
+use crate::table::Table;
+use crate::task::{Task, TaskKind, Wake};
+use crate::values::{AnyValue, Unboxed, Value};
+use crate::vm::{Loader, VM, VmSettings};
+use mio::Waker;
+use num_bigint::BigInt;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+use std::time::Instant;
+use watt_common::address::Address;
+use watt_common::errors::ErrorKind;
+use watt_common::{error, errors::Error};
+
+/// Message
+///
+/// deep copy of value, sent between
+/// vm-s of threads, instances are
+/// restored by full name of their type
+///
+#[derive(Clone)]
+enum Message {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    BigInt(BigInt),
+    String(String),
+    List(Vec<Message>),
+    Instance(String, Vec<(String, Message)>),
+    Channel(Arc<Channel>),
+}
+
+/// Channel state
+///
+/// * `queue`: sent messages
+/// * `closed`: channel is closed, nothing
+///   could be sent to it anymore
+/// * `wakers`: wakers of vm-s, receiving asynchronously
+///
+#[derive(Default)]
+struct ChannelState {
+    queue: VecDeque<Message>,
+    closed: bool,
+    wakers: Vec<Arc<Waker>>,
+}
+/// Channel state implementation
+impl ChannelState {
+    /// Adds waker of receiving vm
+    fn listen(&mut self, waker: Arc<Waker>) {
+        if !self.wakers.iter().any(|it| Arc::ptr_eq(it, &waker)) {
+            self.wakers.push(waker);
+        }
+    }
+
+    /// Wakes receiving vm-s
+    fn wake(&self) {
+        for waker in &self.wakers {
+            let _ = waker.wake();
+        }
+    }
+}
+
+/// Channel, shared by threads
+#[derive(Default)]
+struct Channel {
+    state: Mutex<ChannelState>,
+    ready: Condvar,
+}
+/// Channel implementation
+impl Channel {
+    /// Locks channel state, state is consistent
+    /// even if other thread panicked with lock
+    fn state(&self) -> MutexGuard<'_, ChannelState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Thread handle
+///
+/// * `join`: join handle, `None` when thread is joined
+/// * `exit`: exit of thread, shared with it
+///
+struct ThreadHandle {
+    join: Option<JoinHandle<Result<Message, Error>>>,
+    exit: Arc<Mutex<ThreadExit>>,
+}
+
+/// Thread exit
+///
+/// * `done`: thread is done
+/// * `waker`: waker of vm, joining thread asynchronously
+///
+#[derive(Default)]
+struct ThreadExit {
+    done: bool,
+    waker: Option<Arc<Waker>>,
+}
+
+/// Marks thread exit, when dropped,
+/// even if thread is panicked
+struct ExitGuard(Arc<Mutex<ThreadExit>>);
+/// Drop implementation
+impl Drop for ExitGuard {
+    fn drop(&mut self) {
+        let mut exit = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        exit.done = true;
+        if let Some(waker) = &exit.waker {
+            let _ = waker.wake();
+        }
+    }
+}
+
+// args of module, run by this thread
+thread_local! {
+    static ARGS: RefCell<Vec<Message>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Copies value to message
+///
+/// `path` contains containers, value is nested in,
+/// they are used to find cyclic values
+///
+unsafe fn to_message(addr: &Address, value: Value, path: &mut Vec<Value>) -> Message {
+    // cyclic values couldn't be copied
+    if path.contains(&value) {
+        error!(Error::new(
+            addr.clone(),
+            "couldn't send cyclic value to thread.",
+            "break the cycle before sending."
+        ))
+    }
+
+    match value.unbox() {
+        Unboxed::Null => Message::Null,
+        Unboxed::Bool(bool) => Message::Bool(bool),
+        Unboxed::Int(int) => Message::Int(int),
+        Unboxed::Float(float) => Message::Float(float),
+        Unboxed::BigInt(bigint) => Message::BigInt((*bigint).clone()),
+        Unboxed::String(string) => Message::String((*string).clone()),
+        Unboxed::List(list) => {
+            path.push(value);
+            let items = (*list)
+                .iter()
+                .map(|item| to_message(addr, *item, path))
+                .collect();
+            path.pop();
+            Message::List(items)
+        }
+        Unboxed::Instance(instance) => {
+            let t = (*instance).t;
+            let name = (*t)
+                .name
+                .full_name
+                .clone()
+                .unwrap_or((*t).name.name.clone());
+            path.push(value);
+            let fields = (*(*instance).fields)
+                .fields
+                .iter()
+                .map(|(field, value)| (field.clone(), to_message(addr, *value, path)))
+                .collect();
+            path.pop();
+            Message::Instance(name, fields)
+        }
+        Unboxed::Any(any) if (*any).is::<Arc<Channel>>() => {
+            Message::Channel((*any).downcast_ref::<Arc<Channel>>().unwrap().clone())
+        }
+        _ => error!(Error::own_text(
+            addr.clone(),
+            format!("couldn't send {value:?} to thread."),
+            "only null, bools, numbers, strings, lists, channels and plain instances could be sent."
+        )),
+    }
+}
+
+/// Restores message as value of vm, pushes it
+///
+/// safety guaranteed by keeping restored values
+/// on stack, until their container is registered.
+///
+unsafe fn restore(
+    vm: &mut VM,
+    addr: &Address,
+    message: Message,
+    table: *mut Table,
+) -> Result<(), ControlFlow> {
+    match message {
+        Message::Null => vm.push(Value::Null),
+        Message::Bool(bool) => vm.push(Value::Bool(bool)),
+        Message::Int(int) => vm.op_push(OpcodeValue::Int(int), table)?,
+        Message::Float(float) => vm.push(Value::Float(float)),
+        Message::BigInt(bigint) => vm.op_push(
+            OpcodeValue::Raw(Value::BigInt(memory::alloc_value(bigint))),
+            table,
+        )?,
+        Message::String(string) => vm.op_push(OpcodeValue::String(string), table)?,
+        Message::Channel(channel) => vm.op_push(
+            OpcodeValue::Raw(Value::Any(memory::alloc_any(channel))),
+            table,
+        )?,
+        Message::List(items) => {
+            // restoring items
+            let start = vm.stack.len();
+            let len = items.len();
+            for item in items {
+                restore(vm, addr, item, table)?;
+            }
+
+            // pushing list, then unrooting items
+            let list = Value::List(memory::alloc_value(vm.stack[start..].to_vec()));
+            vm.op_push(OpcodeValue::Raw(list), table)?;
+            vm.stack.drain(start..start + len);
+        }
+        Message::Instance(name, fields) => {
+            // looking up type
+            if !(*vm.types).exists(&name) {
+                error!(Error::own_text(
+                    addr.clone(),
+                    format!("couldn't receive instance of {name}."),
+                    "type is not defined in receiving thread, import it's module."
+                ))
+            }
+            let t = utils::expect_type(addr, (*vm.types).lookup(addr, &name));
+
+            // restoring fields
+            let start = vm.stack.len();
+            let mut names = Vec::with_capacity(fields.len());
+            for (field, value) in fields {
+                names.push(field);
+                restore(vm, addr, value, table)?;
+            }
+
+            // pushing instance, then unrooting fields
+            let fields = names.into_iter().zip(vm.stack[start..].to_vec()).collect();
+            let instance = vm.restore_instance(addr, t, fields, table);
+            vm.push(instance);
+            vm.stack.drain(start..vm.stack.len() - 1);
+        }
+    }
+    Ok(())
+}
+
+/// Raises error of thread at joining address,
+/// limit and permission errors keep their kind
+unsafe fn propagate(addr: &Address, err: Error) -> ! {
+    // thread error text, with it's location
+    let location = match err.addr().file.as_ref().and_then(|file| file.file_name()) {
+        Some(file) => format!(" at {}:{}", file.to_string_lossy(), err.addr().line),
+        None => String::new(),
+    };
+    let text = format!("thread panicked{location}: {}", err.text());
+    let hint = err.hint().to_string();
+
+    // raising error
+    match err.kind() {
+        ErrorKind::Panic => error!(Error::own(addr.clone(), text, hint)),
+        ErrorKind::Limit => error!(Error::limit(addr.clone(), text, hint)),
+        ErrorKind::Permission => error!(Error::permission(addr.clone(), text, hint)),
+    }
+}
+
+/// Runs thread vm
+///
+/// when `entry` fn is given, module is loaded with
+/// definitions only, then entry is called with `args`,
+/// otherwise module is run, it gets `args` with `thread.args()`
+///
+unsafe fn run_thread(
+    addr: Address,
+    module: String,
+    entry: Option<String>,
+    args: Vec<Message>,
+    settings: VmSettings,
+    deadline: Option<Instant>,
+    loader: Loader,
+) -> Message {
+    // loading module
+    let chunk = loader(&addr, &module, entry.is_some());
+
+    // creating vm and running
+    let mut vm = VM::for_thread(settings, deadline, Some(loader));
+    let globals = vm.globals;
+    let result = match entry {
+        Some(name) => vm.run(&chunk, globals).and_then(|_| {
+            // restoring args
+            let callable = (*globals).find(&addr, &name);
+            let start = vm.stack.len();
+            for arg in args {
+                restore(&mut vm, &addr, arg, globals)?;
+            }
+            let args = Chunk::new(
+                vm.stack[start..]
+                    .iter()
+                    .map(|arg| Opcode::Push {
+                        addr: addr.clone(),
+                        value: OpcodeValue::Raw(*arg),
+                    })
+                    .collect(),
+            );
+
+            // calling entry
+            vm.call(&addr, &name, callable, None, &args, &[], globals, true)?;
+            let value = vm.pop(&addr);
+            vm.stack.truncate(start);
+            Ok(to_message(&addr, value, &mut Vec::new()))
+        }),
+        None => {
+            ARGS.set(args);
+            vm.run(&chunk, globals).map(|_| Message::Null)
+        }
+    };
+
+    // running scheduled tasks
+    // and finalizers of alive instances
+    match result
+        .and_then(|result| vm.run_tasks(globals).map(|_| result))
+        .and_then(|result| vm.finalize_all(globals).map(|_| result))
+    {
+        Ok(result) => {
+            vm.cleanup();
+            result
+        }
+        Err(e) => error!(Error::own_text(
+            Address::unknown(),
+            format!("control flow leak: {e:?}"),
+            "report this error to the developer."
+        )),
+    }
+}
+
+/// Spawns thread of module, pushes it's handle
+unsafe fn spawn_thread(
+    vm: &mut VM,
+    addr: &Address,
+    table: *mut Table,
+    module: String,
+    entry: Option<String>,
+    args: Value,
+) -> Result<(), ControlFlow> {
+    // module loader
+    let Some(loader) = vm.loader else {
+        error!(Error::new(
+            addr.clone(),
+            "threads are not supported by this vm.",
+            "vm has no module loader."
+        ))
+    };
+
+    // copying args
+    let args = (*utils::expect_list(addr, args))
+        .iter()
+        .map(|arg| to_message(addr, *arg, &mut Vec::new()))
+        .collect();
+
+    // spawning thread, errors of it are captured
+    let (settings, deadline) = vm.thread_settings();
+    let thread_addr = addr.clone();
+    let exit = Arc::new(Mutex::new(ThreadExit::default()));
+    let guard = ExitGuard(exit.clone());
+    let spawned = std::thread::Builder::new()
+        .name("watt-thread".to_string())
+        .spawn(move || {
+            let _guard = guard;
+            Error::capture(|| unsafe {
+                run_thread(thread_addr, module, entry, args, settings, deadline, loader)
+            })
+        });
+
+    match spawned {
+        Ok(handle) => {
+            let handle = ThreadHandle {
+                join: Some(handle),
+                exit,
+            };
+            vm.op_push(
+                OpcodeValue::Raw(Value::Any(memory::alloc_any(handle))),
+                table,
+            )
+        }
+        Err(err) => error!(Error::own_text(
+            addr.clone(),
+            format!("failed to spawn thread: {err}"),
+            "check system resources."
+        )),
+    }
+}
+
+/// Joins thread, raises it's error
+unsafe fn join(addr: &Address, handle: &mut ThreadHandle) -> Message {
+    match handle.join.take() {
+        Some(handle) => match handle.join() {
+            Ok(Ok(message)) => message,
+            Ok(Err(err)) => propagate(addr, err),
+            Err(payload) => std::panic::resume_unwind(payload),
+        },
+        None => error!(Error::new(
+            addr.clone(),
+            "thread is already joined.",
+            "thread could be joined only once."
+        )),
+    }
+}
+
+/// Gets thread handle from any value
+unsafe fn thread_of<'any>(addr: &Address, any: *mut AnyValue) -> &'any mut ThreadHandle {
+    match (*any).downcast_mut() {
+        Some(handle) => handle,
+        None => error!(Error::new(
+            addr.clone(),
+            "internal handle in std.thread.Thread is not a thread!",
+            "please file an issue at https://github.com/vyacheslavhere/watt"
+        )),
+    }
+}
+
+/// Gets channel from stack
+unsafe fn pop_channel(vm: &mut VM, addr: &Address) -> Arc<Channel> {
+    // getting a raw channel
+    let raw_channel = utils::expect_any(addr, vm.pop(addr), None);
+
+    match (*raw_channel).downcast_ref::<Arc<Channel>>() {
+        Some(channel) => channel.clone(),
+        None => error!(Error::new(
+            addr.clone(),
+            "internal handle in std.thread.Channel is not a channel!",
+            "please file an issue at https://github.com/vyacheslavhere/watt"
+        )),
+    }
+}
+
+/// Provides
+#[allow(unused_variables)]
+pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Error> {
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "thread@spawn",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let args = vm.pop(&addr);
+            let function = utils::expect_fn(&addr, vm.pop(&addr));
+
+            // only top-level fn could be loaded by thread
+            let module = match &(*function).module {
+                Some(module) if (*function).closure.is_null() => module.clone(),
+                _ => error!(Error::own_text(
+                    addr.clone(),
+                    format!("couldn't spawn thread of {}.", (*function).name.name),
+                    "only top-level fn could be run by thread."
+                )),
+            };
+            let name = (*function).name.full_name.clone();
+            let name = name.unwrap_or((*function).name.name.clone());
+
+            spawn_thread(
+                vm,
+                &addr,
+                table,
+                module.to_string_lossy().to_string(),
+                Some(name),
+                args,
+            )?;
+
+            if !should_push {
+                vm.pop(&addr);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "thread@run",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let args = vm.pop(&addr);
+            let module = utils::expect_cloned_string(&addr, vm.pop(&addr));
+
+            spawn_thread(vm, &addr, table, module, None, args)?;
+
+            if !should_push {
+                vm.pop(&addr);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        0,
+        "thread@args",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            if should_push {
+                let args = ARGS.with_borrow(|args| args.clone());
+                restore(vm, &addr, Message::List(args), table)?;
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "thread@join",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let handle = thread_of(&addr, utils::expect_any(&addr, vm.pop(&addr), None));
+            let message = join(&addr, handle);
+
+            if should_push {
+                restore(vm, &addr, message, table)?;
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "thread@join_async",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let raw_handle = vm.pop(&addr);
+            let handle = thread_of(&addr, utils::expect_any(&addr, raw_handle, None));
+
+            // polling thread, when it's exit wakes vm,
+            // handle is kept alive by task
+            let waker = vm.reactor(&addr).waker();
+            handle
+                .exit
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .waker = Some(waker);
+            let poll = Box::new(move |vm: &mut VM, addr: &Address, table: *mut Table| {
+                let exit = handle.exit.lock().unwrap_or_else(PoisonError::into_inner);
+                if handle.join.is_some() && !exit.done {
+                    return Ok(false);
+                }
+                drop(exit);
+                let message = join(addr, handle);
+                restore(vm, addr, message, table)?;
+                Ok(true)
+            });
+            let mut task = Task::new(addr.clone(), TaskKind::Poll(Wake::Waker, poll));
+            task.refs.push(raw_handle);
+            let task = vm.spawn_task(task, table, table)?;
+
+            if should_push {
+                vm.push(task);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "thread@is_finished",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let handle = thread_of(&addr, utils::expect_any(&addr, vm.pop(&addr), None));
+
+            if should_push {
+                let finished = handle.join.as_ref().is_none_or(|join| join.is_finished());
+                vm.push(Value::Bool(finished));
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        0,
+        "thread@channel",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            if should_push {
+                let channel = Arc::new(Channel::default());
+                vm.op_push(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(channel))),
+                    table,
+                )?;
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "thread@send",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let value = vm.pop(&addr);
+            let channel = pop_channel(vm, &addr);
+            let message = to_message(&addr, value, &mut Vec::new());
+
+            // sending message
+            let mut state = channel.state();
+            if state.closed {
+                drop(state);
+                error!(Error::new(
+                    addr.clone(),
+                    "couldn't send to closed channel.",
+                    "check channel is not closed."
+                ))
+            }
+            state.queue.push_back(message);
+            channel.ready.notify_one();
+            state.wake();
+            drop(state);
+
+            if should_push {
+                vm.push(Value::Null);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "thread@recv",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let channel = pop_channel(vm, &addr);
+
+            // waiting for message, until timeout deadline,
+            // null is received, when channel is closed
+            let message = loop {
+                let state = channel.state();
+                let waiting = |state: &mut ChannelState| state.queue.is_empty() && !state.closed;
+                let mut state = match vm.time_left() {
+                    Some(left) => {
+                        channel
+                            .ready
+                            .wait_timeout_while(state, left, waiting)
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .0
+                    }
+                    None => channel
+                        .ready
+                        .wait_while(state, waiting)
+                        .unwrap_or_else(PoisonError::into_inner),
+                };
+                if let Some(message) = state.queue.pop_front() {
+                    break message;
+                }
+                if state.closed {
+                    break Message::Null;
+                }
+                drop(state);
+                vm.check_timeout(&addr);
+            };
+
+            if should_push {
+                restore(vm, &addr, message, table)?;
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "thread@recv_async",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let channel = pop_channel(vm, &addr);
+
+            // polling channel, when sent or closed channel wakes
+            // vm, null is received, when channel is closed
+            let waker = vm.reactor(&addr).waker();
+            channel.state().listen(waker);
+            let poll = Box::new(move |vm: &mut VM, addr: &Address, table: *mut Table| {
+                let mut state = channel.state();
+                let message = match state.queue.pop_front() {
+                    Some(message) => message,
+                    None if state.closed => Message::Null,
+                    None => return Ok(false),
+                };
+                drop(state);
+                restore(vm, addr, message, table)?;
+                Ok(true)
+            });
+            utils::push_task(
+                vm,
+                &addr,
+                should_push,
+                table,
+                TaskKind::Poll(Wake::Waker, poll),
+            )
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "thread@close",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let channel = pop_channel(vm, &addr);
+
+            // closing channel, waking receivers
+            let mut state = channel.state();
+            state.closed = true;
+            channel.ready.notify_all();
+            state.wake();
+            drop(state);
+
+            if should_push {
+                vm.push(Value::Null);
+            }
+
+            Ok(())
+        },
+    );
+
+    Ok(())
+}
@@ -5,9 +5,11 @@ use crate::memory::memory;
 use crate::natives::natives;
 use crate::natives::utils;
 use crate::table::Table;
+use crate::task::TaskKind;
 use crate::values::Value;
 use crate::vm::VM;
 use chrono::{DateTime, Datelike, Duration, Local, TimeDelta, Timelike};
+use std::time::Instant;
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
@@ -60,7 +62,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(Local::now()))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(Local::now()))),
                     table,
                 )?;
             }
@@ -76,7 +78,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let milis = timestamp.timestamp_millis();
             if should_push {
-                vm.push(Value::Int(milis));
+                vm.op_push(OpcodeValue::Int(milis), table)?;
             }
             Ok(())
         },
@@ -90,7 +92,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let milis = timestamp.timestamp();
             if should_push {
-                vm.push(Value::Int(milis));
+                vm.op_push(OpcodeValue::Int(milis), table)?;
             }
             Ok(())
         },
@@ -104,7 +106,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let second = timestamp.second().into();
             if should_push {
-                vm.push(Value::Int(second));
+                vm.op_push(OpcodeValue::Int(second), table)?;
             }
             Ok(())
         },
@@ -118,7 +120,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let minute = timestamp.minute().into();
             if should_push {
-                vm.push(Value::Int(minute));
+                vm.op_push(OpcodeValue::Int(minute), table)?;
             }
             Ok(())
         },
@@ -132,7 +134,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let hour = timestamp.hour().into();
             if should_push {
-                vm.push(Value::Int(hour));
+                vm.op_push(OpcodeValue::Int(hour), table)?;
             }
             Ok(())
         },
@@ -146,7 +148,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let day = timestamp.day().into();
             if should_push {
-                vm.push(Value::Int(day));
+                vm.op_push(OpcodeValue::Int(day), table)?;
             }
             Ok(())
         },
@@ -160,7 +162,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let year = timestamp.year().into();
             if should_push {
-                vm.push(Value::Int(year));
+                vm.op_push(OpcodeValue::Int(year), table)?;
             }
             Ok(())
         },
@@ -174,7 +176,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let month = timestamp.month().into();
             if should_push {
-                vm.push(Value::Int(month));
+                vm.op_push(OpcodeValue::Int(month), table)?;
             }
             Ok(())
         },
@@ -188,7 +190,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let weekday = timestamp.weekday().num_days_from_monday() as i64;
             if should_push {
-                vm.push(Value::Int(weekday));
+                vm.op_push(OpcodeValue::Int(weekday), table)?;
             }
             Ok(())
         },
@@ -202,7 +204,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let timestamp: &mut DateTime<Local> = pop_timestamp(vm, &addr)?;
             let week0 = timestamp.iso_week().week0() as i64;
             if should_push {
-                vm.push(Value::Int(week0));
+                vm.op_push(OpcodeValue::Int(week0), table)?;
             }
             Ok(())
         },
@@ -218,7 +220,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let result = first_timestamp - second_timestamp;
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(result))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(result))),
                     table,
                 )?;
             }
@@ -315,7 +317,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             timestamp += Duration::minutes(minutes);
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(timestamp))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(timestamp))),
                     table,
                 )?;
             }
@@ -333,7 +335,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             timestamp += Duration::seconds(seconds);
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(timestamp))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(timestamp))),
                     table,
                 )?;
             }
@@ -351,7 +353,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             timestamp += Duration::hours(hours);
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(timestamp))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(timestamp))),
                     table,
                 )?;
             }
@@ -369,7 +371,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             timestamp += Duration::weeks(weeks);
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(timestamp))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(timestamp))),
                     table,
                 )?;
             }
@@ -387,7 +389,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             timestamp += Duration::milliseconds(millis);
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(timestamp))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(timestamp))),
                     table,
                 )?;
             }
@@ -405,7 +407,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             timestamp += Duration::days(days);
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(timestamp))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(timestamp))),
                     table,
                 )?;
             }
@@ -423,7 +425,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             timestamp += timedelta;
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(timestamp))),
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(timestamp))),
                     table,
                 )?;
             }
@@ -438,10 +440,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
-                vm.op_push(
-                    OpcodeValue::Raw(Value::Int(timedelta.num_milliseconds())),
-                    table,
-                )?;
+                vm.op_push(OpcodeValue::Int(timedelta.num_milliseconds()), table)?;
             }
             Ok(())
         },
@@ -454,7 +453,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
-                vm.op_push(OpcodeValue::Raw(Value::Int(timedelta.num_seconds())), table)?;
+                vm.op_push(OpcodeValue::Int(timedelta.num_seconds()), table)?;
             }
             Ok(())
         },
@@ -467,7 +466,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
-                vm.op_push(OpcodeValue::Raw(Value::Int(timedelta.num_minutes())), table)?;
+                vm.op_push(OpcodeValue::Int(timedelta.num_minutes()), table)?;
             }
             Ok(())
         },
@@ -480,7 +479,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
-                vm.op_push(OpcodeValue::Raw(Value::Int(timedelta.num_hours())), table)?;
+                vm.op_push(OpcodeValue::Int(timedelta.num_hours()), table)?;
             }
             Ok(())
         },
@@ -493,7 +492,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
-                vm.op_push(OpcodeValue::Raw(Value::Int(timedelta.num_days())), table)?;
+                vm.op_push(OpcodeValue::Int(timedelta.num_days()), table)?;
             }
             Ok(())
         },
@@ -506,7 +505,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
         |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
             let timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
-                vm.op_push(OpcodeValue::Raw(Value::Int(timedelta.num_weeks())), table)?;
+                vm.op_push(OpcodeValue::Int(timedelta.num_weeks()), table)?;
             }
             Ok(())
         },
@@ -521,7 +520,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let first_timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(
                         first_timedelta + second_timedelta,
                     ))),
                     table,
@@ -540,7 +539,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let first_timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(
                         first_timedelta - second_timedelta,
                     ))),
                     table,
@@ -559,7 +558,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let first_timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(
                         first_timedelta > second_timedelta,
                     ))),
                     table,
@@ -578,7 +577,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let first_timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(
                         first_timedelta < second_timedelta,
                     ))),
                     table,
@@ -597,7 +596,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let first_timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(
                         first_timedelta >= second_timedelta,
                     ))),
                     table,
@@ -616,7 +615,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let first_timedelta = pop_timedelta(vm, &addr)?.to_owned();
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(
                         first_timedelta <= second_timedelta,
                     ))),
                     table,
@@ -634,7 +633,7 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             let seconds = utils::expect_int(&addr, vm.pop(&addr));
             if should_push {
                 vm.op_push(
-                    OpcodeValue::Raw(Value::Any(memory::alloc_value(
+                    OpcodeValue::Raw(Value::Any(memory::alloc_any(
                         Duration::new(seconds, 0).unwrap(),
                     ))),
                     table,
@@ -643,6 +642,26 @@ pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Err
             Ok(())
         },
     );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "time@sleep",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let millis = utils::expect_int(&addr, vm.pop(&addr));
+            if millis < 0 {
+                error!(Error::own_text(
+                    addr.clone(),
+                    format!("couldn't sleep {millis} millis."),
+                    "sleep duration should be positive."
+                ))
+            }
+
+            // sleep task
+            let deadline = Instant::now() + std::time::Duration::from_millis(millis as u64);
+            utils::push_task(vm, &addr, should_push, table, TaskKind::Sleep(deadline))
+        },
+    );
     // успех
     Ok(())
 }
@@ -0,0 +1,184 @@
+// imports
+use crate::bytecode::OpcodeValue;
+use crate::flow::ControlFlow;
+use crate::memory::memory;
+use crate::memory::weak::{WeakRef, WeakTable};
+use crate::natives::natives;
+use crate::natives::utils;
+use crate::table::Table;
+use crate::values::Value;
+use crate::vm::VM;
+use watt_common::address::Address;
+use watt_common::{error, errors::Error};
+
+/// Gets weak table from stack
+unsafe fn pop_table<'vm>(
+    vm: &'vm mut VM,
+    addr: &Address,
+) -> Result<&'vm mut WeakTable, ControlFlow> {
+    // getting a raw table
+    let raw_table = utils::expect_any(addr, vm.pop(addr), None);
+
+    match (*raw_table).downcast_mut::<WeakTable>() {
+        Some(table) => Ok(table),
+        None => error!(Error::new(
+            addr.clone(),
+            "internal type in std.weak.WeakMap is not a weak table!",
+            "please, file an issue at https://github.com/vyacheslavhere/watt"
+        )),
+    }
+}
+
+/// Pops weak table key from stack,
+/// keys are compared by instance identity
+unsafe fn pop_key(vm: &mut VM, addr: &Address) -> Value {
+    let key = vm.pop(addr);
+    utils::expect_instance(addr, key);
+    key
+}
+
+/// Provides
+#[allow(unused_variables)]
+pub unsafe fn provide(built_in_address: &Address, vm: &mut VM) -> Result<(), Error> {
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "weak@ref",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let target = vm.pop(&addr);
+
+            if should_push {
+                let weak = Value::Any(memory::alloc_any(WeakRef::new(target)));
+                vm.op_push(OpcodeValue::Raw(weak), table)?;
+                (*vm.gc).add_weak(weak);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "weak@get",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            // getting a raw weak reference
+            let raw_weak = utils::expect_any(&addr, vm.pop(&addr), None);
+            let target = match (*raw_weak).downcast_ref::<WeakRef>() {
+                Some(weak) => weak.target,
+                None => error!(Error::new(
+                    addr.clone(),
+                    "internal type in std.weak.Weak is not a weak reference!",
+                    "please, file an issue at https://github.com/vyacheslavhere/watt"
+                )),
+            };
+
+            if should_push {
+                vm.push(target);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        0,
+        "weak@map_make",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            if should_push {
+                let weak = Value::Any(memory::alloc_any(WeakTable::new()));
+                vm.op_push(OpcodeValue::Raw(weak), table)?;
+                (*vm.gc).add_weak(weak);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        3,
+        "weak@map_set",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let value = vm.pop(&addr);
+            let key = pop_key(vm, &addr);
+            pop_table(vm, &addr)?.entries.insert(key, value);
+
+            if should_push {
+                vm.push(Value::Null);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "weak@map_get",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let key = pop_key(vm, &addr);
+            let value = pop_table(vm, &addr)?
+                .entries
+                .get(&key)
+                .copied()
+                .unwrap_or(Value::Null);
+
+            if should_push {
+                vm.push(value);
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "weak@map_has",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let key = pop_key(vm, &addr);
+            let has = pop_table(vm, &addr)?.entries.contains_key(&key);
+
+            if should_push {
+                vm.push(Value::Bool(has));
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        2,
+        "weak@map_delete",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let key = pop_key(vm, &addr);
+            let deleted = pop_table(vm, &addr)?.entries.remove(&key).is_some();
+
+            if should_push {
+                vm.push(Value::Bool(deleted));
+            }
+
+            Ok(())
+        },
+    );
+    natives::provide(
+        vm,
+        built_in_address.clone(),
+        1,
+        "weak@map_length",
+        |vm: &mut VM, addr: Address, should_push: bool, table: *mut Table| {
+            let length = pop_table(vm, &addr)?.entries.len();
+
+            if should_push {
+                vm.op_push(OpcodeValue::Int(length as i64), table)?;
+            }
+
+            Ok(())
+        },
+    );
+    Ok(())
+}
@@ -17,6 +17,9 @@ pub unsafe fn provide_builtins(vm: &mut VM) -> Result<(), Error> {
     natives_base::provide(&built_in_address, vm)?;
     natives_io::provide(&built_in_address, vm)?;
     natives_list::provide(&built_in_address, vm)?;
+    natives_generator::provide(&built_in_address, vm)?;
+    natives_task::provide(&built_in_address, vm)?;
+    natives_thread::provide(&built_in_address, vm)?;
     natives_gc::provide(&built_in_address, vm)?;
     natives_convert::provide(&built_in_address, vm)?;
     natives_time::provide(&built_in_address, vm)?;
@@ -27,6 +30,7 @@ pub unsafe fn provide_builtins(vm: &mut VM) -> Result<(), Error> {
     natives_strings::provide(&built_in_address, vm)?;
     natives_ffi::provide(&built_in_address, vm)?;
     natives_net::provide(&built_in_address, vm)?;
+    natives_weak::provide(&built_in_address, vm)?;
 
     Ok(())
 }
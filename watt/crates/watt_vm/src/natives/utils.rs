@@ -1,13 +1,20 @@
 // imports
-use crate::values::{Function, Instance, Native, Trait, Type, Unit, Value};
-use std::any::Any;
+use crate::flow::ControlFlow;
+use crate::reactor::Registered;
+use crate::table::Table;
+use crate::task::{Task, TaskKind};
+use crate::values::{AnyValue, Function, Instance, Native, Trait, Type, Unboxed, Unit, Value};
+use crate::vm::VM;
+use mio::Interest;
+use mio::event::Source;
+use std::io;
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
 /// Expects value is int, otherwise raises error
 #[allow(unused)]
 pub fn expect_int(addr: &Address, value: Value) -> i64 {
-    if let Value::Int(i) = value {
+    if let Unboxed::Int(i) = value.unbox() {
         i
     } else {
         error!(Error::own_text(
@@ -21,7 +28,7 @@ pub fn expect_int(addr: &Address, value: Value) -> i64 {
 /// Expects value is float, otherwise raises error
 #[allow(unused)]
 pub fn expect_float(addr: &Address, value: Value) -> f64 {
-    if let Value::Float(f) = value {
+    if let Unboxed::Float(f) = value.unbox() {
         f
     } else {
         error!(Error::own_text(
@@ -35,7 +42,7 @@ pub fn expect_float(addr: &Address, value: Value) -> f64 {
 /// Expects value is bool, otherwise raises error
 #[allow(unused)]
 pub fn expect_bool(addr: &Address, value: Value) -> bool {
-    if let Value::Bool(b) = value {
+    if let Unboxed::Bool(b) = value.unbox() {
         b
     } else {
         error!(Error::own_text(
@@ -49,7 +56,7 @@ pub fn expect_bool(addr: &Address, value: Value) -> bool {
 /// Expects value is instance, otherwise raises error
 #[allow(unused)]
 pub fn expect_instance(addr: &Address, value: Value) -> *mut Instance {
-    if let Value::Instance(i) = value {
+    if let Unboxed::Instance(i) = value.unbox() {
         i
     } else {
         error!(Error::own_text(
@@ -63,7 +70,7 @@ pub fn expect_instance(addr: &Address, value: Value) -> *mut Instance {
 /// Expects value is unit, otherwise raises error
 #[allow(unused)]
 pub fn expect_unit(addr: &Address, value: Value) -> *mut Unit {
-    if let Value::Unit(u) = value {
+    if let Unboxed::Unit(u) = value.unbox() {
         u
     } else {
         error!(Error::own_text(
@@ -77,7 +84,7 @@ pub fn expect_unit(addr: &Address, value: Value) -> *mut Unit {
 /// Expects value is trait, otherwise raises error
 #[allow(unused)]
 pub fn expect_trait(addr: &Address, value: Value) -> *mut Trait {
-    if let Value::Trait(t) = value {
+    if let Unboxed::Trait(t) = value.unbox() {
         t
     } else {
         error!(Error::own_text(
@@ -91,7 +98,7 @@ pub fn expect_trait(addr: &Address, value: Value) -> *mut Trait {
 /// Expects value is type, otherwise raises error
 #[allow(unused)]
 pub fn expect_type(addr: &Address, value: Value) -> *mut Type {
-    if let Value::Type(t) = value {
+    if let Unboxed::Type(t) = value.unbox() {
         t
     } else {
         error!(Error::own_text(
@@ -105,7 +112,7 @@ pub fn expect_type(addr: &Address, value: Value) -> *mut Type {
 /// Expects value is fn, otherwise raises error
 #[allow(unused)]
 pub fn expect_fn(addr: &Address, value: Value) -> *mut Function {
-    if let Value::Fn(f) = value {
+    if let Unboxed::Fn(f) = value.unbox() {
         f
     } else {
         error!(Error::own_text(
@@ -119,7 +126,7 @@ pub fn expect_fn(addr: &Address, value: Value) -> *mut Function {
 /// Expects value is native, otherwise raises error
 #[allow(unused)]
 pub fn expect_native(addr: &Address, value: Value) -> *mut Native {
-    if let Value::Native(n) = value {
+    if let Unboxed::Native(n) = value.unbox() {
         n
     } else {
         error!(Error::own_text(
@@ -132,8 +139,8 @@ pub fn expect_native(addr: &Address, value: Value) -> *mut Native {
 
 /// Expects value is any, otherwise raises error
 #[allow(unused)]
-pub fn expect_any(addr: &Address, value: Value, error: Option<Error>) -> *mut dyn Any {
-    if let Value::Any(a) = value {
+pub fn expect_any(addr: &Address, value: Value, error: Option<Error>) -> *mut AnyValue {
+    if let Unboxed::Any(a) = value.unbox() {
         a
     } else {
         error!(error.unwrap_or(Error::own_text(
@@ -147,7 +154,7 @@ pub fn expect_any(addr: &Address, value: Value, error: Option<Error>) -> *mut dy
 /// Expects value is string, otherwise raises error
 #[allow(unused)]
 pub fn expect_string(addr: &Address, value: Value) -> *const String {
-    if let Value::String(s) = value {
+    if let Unboxed::String(s) = value.unbox() {
         s
     } else {
         error!(Error::own_text(
@@ -161,7 +168,7 @@ pub fn expect_string(addr: &Address, value: Value) -> *const String {
 /// Expects value is string, if it's a string returns cloned, otherwise raises error
 #[allow(unused)]
 pub unsafe fn expect_cloned_string(addr: &Address, value: Value) -> String {
-    if let Value::String(s) = value {
+    if let Unboxed::String(s) = value.unbox() {
         (*s).clone()
     } else {
         error!(Error::own_text(
@@ -175,7 +182,7 @@ pub unsafe fn expect_cloned_string(addr: &Address, value: Value) -> String {
 /// Expects value is list, otherwise raises error
 #[allow(unused)]
 pub fn expect_list(addr: &Address, value: Value) -> *mut Vec<Value> {
-    if let Value::List(l) = value {
+    if let Unboxed::List(l) = value.unbox() {
         l
     } else {
         error!(Error::own_text(
@@ -188,12 +195,12 @@ pub fn expect_list(addr: &Address, value: Value) -> *mut Vec<Value> {
 
 /// Expects value is list of strings, otherwise raises error
 pub unsafe fn expect_string_list(addr: &Address, value: Value) -> Vec<String> {
-    if let Value::List(l) = value {
+    if let Unboxed::List(l) = value.unbox() {
         let mut strings = vec![];
         for value in &(*l) {
-            match value {
-                Value::String(string) => {
-                    strings.push((**string).clone());
+            match value.unbox() {
+                Unboxed::String(string) => {
+                    strings.push((*string).clone());
                 }
                 _ => {
                     error!(Error::own_text(
@@ -213,3 +220,41 @@ pub unsafe fn expect_string_list(addr: &Address, value: Value) -> Vec<String> {
         ));
     }
 }
+
+/// Spawns task of native, pushes it's instance
+pub unsafe fn push_task(
+    vm: &mut VM,
+    addr: &Address,
+    should_push: bool,
+    table: *mut Table,
+    kind: TaskKind,
+) -> Result<(), ControlFlow> {
+    let task = vm.spawn_task(Task::new(addr.clone(), kind), table, table)?;
+    if should_push {
+        vm.push(task);
+    }
+    Ok(())
+}
+
+/// Expects io is succeeded, otherwise raises error
+pub fn expect_io<T>(addr: &Address, result: io::Result<T>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => error!(Error::own_text(
+            addr.clone(),
+            format!("io failure: {err}"),
+            "check system resources."
+        )),
+    }
+}
+
+/// Registers io source in vm reactor, otherwise raises error
+pub fn register<S: Source>(
+    vm: &mut VM,
+    addr: &Address,
+    source: S,
+    interest: Interest,
+) -> Registered<S> {
+    let result = vm.reactor(addr).register(source, interest);
+    expect_io(addr, result)
+}
@@ -0,0 +1,203 @@
+// imports
+use std::path::{Component, Path, PathBuf};
+use watt_common::address::Address;
+use watt_common::{error, errors::Error};
+
+/// Permission grant
+///
+/// `Denied` grants nothing, `Only` grants
+/// listed paths or hosts, `All` grants everything
+///
+#[derive(Debug, Default, Clone)]
+pub enum Grant<T> {
+    #[default]
+    Denied,
+    Only(Vec<T>),
+    All,
+}
+/// Grant implementation
+impl<T> Grant<T> {
+    /// Checks grant allows something,
+    /// matching listed items with `matches`
+    fn allows(&self, matches: impl Fn(&T) -> bool) -> bool {
+        match self {
+            Grant::Denied => false,
+            Grant::Only(items) => items.iter().any(matches),
+            Grant::All => true,
+        }
+    }
+}
+
+/// Vm permissions
+///
+/// capabilities of script, checked
+/// by fs, net, system and ffi natives,
+/// everything is denied by default
+///
+#[derive(Debug, Default, Clone)]
+pub struct Permissions {
+    pub read: Grant<PathBuf>,
+    pub write: Grant<PathBuf>,
+    pub net: Grant<String>,
+    pub run: bool,
+    pub env: bool,
+    pub ffi: bool,
+}
+/// Permissions implementation
+impl Permissions {
+    /// Permissions granting everything
+    pub fn all() -> Permissions {
+        Permissions {
+            read: Grant::All,
+            write: Grant::All,
+            net: Grant::All,
+            run: true,
+            env: true,
+            ffi: true,
+        }
+    }
+
+    /// Is path allowed to be read
+    pub fn can_read(&self, path: &str) -> bool {
+        allows_path(&self.read, path)
+    }
+
+    /// Is path allowed to be written
+    pub fn can_write(&self, path: &str) -> bool {
+        allows_path(&self.write, path)
+    }
+
+    /// Checks path can be read
+    pub fn check_read(&self, addr: &Address, path: &str) {
+        if !self.can_read(path) {
+            denied(addr, "read", Some(path));
+        }
+    }
+
+    /// Checks path can be written
+    pub fn check_write(&self, addr: &Address, path: &str) {
+        if !self.can_write(path) {
+            denied(addr, "write", Some(path));
+        }
+    }
+
+    /// Checks url host can be accessed
+    pub fn check_net(&self, addr: &Address, url: &str) {
+        let authority = authority_of(url);
+        let host = strip_port(authority);
+        let allowed = self.net.allows(|granted| {
+            granted.eq_ignore_ascii_case(authority) || granted.eq_ignore_ascii_case(host)
+        });
+        if !allowed {
+            denied(addr, "net", Some(authority));
+        }
+    }
+
+    /// Checks processes can be spawned
+    pub fn check_run(&self, addr: &Address) {
+        if !self.run {
+            denied(addr, "run", None);
+        }
+    }
+
+    /// Checks environment variables can be accessed
+    pub fn check_env(&self, addr: &Address) {
+        if !self.env {
+            denied(addr, "env", None);
+        }
+    }
+
+    /// Checks foreign libraries can be loaded
+    pub fn check_ffi(&self, addr: &Address) {
+        if !self.ffi {
+            denied(addr, "ffi", None);
+        }
+    }
+}
+
+/// Raises permission error
+fn denied(addr: &Address, permission: &str, target: Option<&str>) -> ! {
+    match target {
+        Some(target) => error!(Error::permission(
+            addr.clone(),
+            format!("missing {permission} permission for '{target}'."),
+            format!("rerun with --allow-{permission}={target} or --allow-all to grant it.")
+        )),
+        None => error!(Error::permission(
+            addr.clone(),
+            format!("missing {permission} permission."),
+            format!("rerun with --allow-{permission} or --allow-all to grant it.")
+        )),
+    }
+}
+
+/// Checks path grant allows path,
+/// granted directories allow paths inside
+fn allows_path(grant: &Grant<PathBuf>, path: &str) -> bool {
+    let path = resolve(Path::new(path));
+    grant.allows(|granted| path.starts_with(resolve(granted)))
+}
+
+/// Max symlinks, followed while resolving path
+const MAX_LINKS: usize = 40;
+
+/// Resolves path to absolute one, following
+/// symlinks before applying `..`, like os does
+fn resolve(path: &Path) -> PathBuf {
+    // absolute path
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    // following components
+    let mut resolved = PathBuf::new();
+    let mut links = 0;
+    follow(&mut resolved, &absolute, &mut links);
+    resolved
+}
+
+/// Appends components of path to resolved one,
+/// symlink is replaced with it's target, resolved
+/// from symlink directory, so `..` after symlink
+/// is applied to it's target directory
+fn follow(resolved: &mut PathBuf, path: &Path, links: &mut usize) {
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => *resolved = PathBuf::from(prefix.as_os_str()),
+            Component::RootDir => resolved.push(component),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                // symlinks loop is left as is, os refuses it
+                if let Ok(target) = std::fs::read_link(&resolved)
+                    && *links < MAX_LINKS
+                {
+                    *links += 1;
+                    resolved.pop();
+                    follow(resolved, &target, links);
+                }
+            }
+        }
+    }
+}
+
+/// Url authority, `host` or `host:port`
+fn authority_of(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
+}
+
+/// Strips port from authority
+fn strip_port(authority: &str) -> &str {
+    match authority.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => authority,
+    }
+}
@@ -0,0 +1,166 @@
+// imports
+use rustc_hash::FxHashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Root frame name, used for
+/// the top level code time
+pub const ROOT_FRAME: &str = "<main>";
+
+/// Profiler frame
+///
+/// active call with it's start time
+/// and time, spent in callees
+///
+#[derive(Debug)]
+struct Frame {
+    name: String,
+    start: Instant,
+    children: Duration,
+}
+
+/// Fn stats
+///
+/// `self_time` excludes callees time,
+/// `total_time` includes it, recursive
+/// calls are counted in total once
+///
+#[derive(Debug, Default, Clone)]
+pub struct FnStats {
+    pub calls: usize,
+    pub self_time: Duration,
+    pub total_time: Duration,
+}
+
+/// Profiler
+///
+/// Instrumenting profiler, records time
+/// and calls amount of every fn and native,
+/// and self time of every collapsed stack
+/// like `<main>;a;b`
+///
+#[derive(Debug)]
+pub struct Profiler {
+    frames: Vec<Frame>,
+    active: FxHashMap<String, usize>,
+    stats: FxHashMap<String, FnStats>,
+    stacks: FxHashMap<String, Duration>,
+}
+/// Profiler implementation
+impl Profiler {
+    /// New profiler, starts root frame
+    pub fn new() -> Profiler {
+        let mut profiler = Profiler {
+            frames: Vec::new(),
+            active: FxHashMap::default(),
+            stats: FxHashMap::default(),
+            stacks: FxHashMap::default(),
+        };
+        profiler.enter(ROOT_FRAME.to_string());
+        profiler
+    }
+
+    /// Enters frame
+    pub fn enter(&mut self, name: String) {
+        *self.active.entry(name.clone()).or_insert(0) += 1;
+        self.frames.push(Frame {
+            name,
+            start: Instant::now(),
+            children: Duration::ZERO,
+        });
+    }
+
+    /// Leaves current frame
+    pub fn leave(&mut self) {
+        // current frame
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        let total = frame.start.elapsed();
+        let self_time = total.saturating_sub(frame.children);
+
+        // adding time to the caller
+        if let Some(caller) = self.frames.last_mut() {
+            caller.children += total;
+        }
+
+        // collapsed stack
+        let mut stack = String::new();
+        for caller in &self.frames {
+            stack.push_str(&caller.name);
+            stack.push(';');
+        }
+        stack.push_str(&frame.name);
+        *self.stacks.entry(stack).or_default() += self_time;
+
+        // fn stats
+        let active = self.active.get_mut(&frame.name).unwrap();
+        *active -= 1;
+        let stats = self.stats.entry(frame.name).or_default();
+        stats.calls += 1;
+        stats.self_time += self_time;
+        if *active == 0 {
+            stats.total_time += total;
+        }
+    }
+
+    /// Leaves all frames, including root
+    pub fn finish(&mut self) {
+        while !self.frames.is_empty() {
+            self.leave();
+        }
+    }
+
+    /// Fn stats by name
+    pub fn stats(&self) -> &FxHashMap<String, FnStats> {
+        &self.stats
+    }
+
+    /// Writes collapsed stacks,
+    /// compatible with flamegraph tools,
+    /// values are in microseconds
+    ///
+    pub fn write_folded(&self, path: &Path) -> std::io::Result<()> {
+        // sorting stacks
+        let mut stacks: Vec<(&String, &Duration)> = self.stacks.iter().collect();
+        stacks.sort_by(|a, b| a.0.cmp(b.0));
+
+        // writing
+        let mut writer = BufWriter::new(File::create(path)?);
+        for (stack, time) in stacks {
+            let micros = time.as_micros();
+            if micros > 0 {
+                writeln!(writer, "{stack} {micros}")?;
+            }
+        }
+        writer.flush()
+    }
+
+    /// Prints top `top` fn-s by self time
+    pub fn print_summary(&self, top: usize) {
+        // sorting stats
+        let mut stats: Vec<(&String, &FnStats)> = self.stats.iter().collect();
+        stats.sort_by(|a, b| b.1.self_time.cmp(&a.1.self_time).then(a.0.cmp(b.0)));
+
+        // printing
+        println!("profile, top {top} by self time:");
+        println!("{:>12} {:>12} {:>10}  name", "self ms", "total ms", "calls");
+        for (name, stats) in stats.iter().take(top) {
+            println!(
+                "{:>12.3} {:>12.3} {:>10}  {}",
+                stats.self_time.as_secs_f64() * 1000f64,
+                stats.total_time.as_secs_f64() * 1000f64,
+                stats.calls,
+                name
+            );
+        }
+    }
+}
+/// Profiler default implementation
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,129 @@
+// imports
+use mio::event::Source;
+use mio::{Events, Interest, Poll, Registry, Token, Waker};
+use std::fmt::{self, Debug, Formatter};
+use std::io::{self, ErrorKind};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Token of reactor waker
+pub const WAKER: Token = Token(0);
+
+/// Events buffer capacity
+const EVENTS_CAPACITY: usize = 256;
+
+/// Reactor
+///
+/// waits for readiness of registered io sources
+/// and for wakes from other threads, so event
+/// loop sleeps, while no task could progress
+///
+/// * `poll`: os poller
+/// * `events`: buffer of ready events
+/// * `registry`: registry of poller, shared with io sources
+/// * `waker`: wakes poller from other threads
+/// * `next`: next io source token
+///
+pub struct Reactor {
+    poll: Poll,
+    events: Events,
+    registry: Rc<Registry>,
+    waker: Arc<Waker>,
+    next: usize,
+}
+/// Reactor implementation
+impl Reactor {
+    /// New reactor
+    pub fn new() -> io::Result<Reactor> {
+        let poll = Poll::new()?;
+        let registry = Rc::new(poll.registry().try_clone()?);
+        let waker = Arc::new(Waker::new(poll.registry(), WAKER)?);
+        Ok(Reactor {
+            poll,
+            events: Events::with_capacity(EVENTS_CAPACITY),
+            registry,
+            waker,
+            next: WAKER.0 + 1,
+        })
+    }
+
+    /// Waker of reactor
+    pub fn waker(&self) -> Arc<Waker> {
+        self.waker.clone()
+    }
+
+    /// Registers io source with new token
+    pub fn register<S: Source>(
+        &mut self,
+        mut source: S,
+        interest: Interest,
+    ) -> io::Result<Registered<S>> {
+        let token = Token(self.next);
+        self.next += 1;
+        self.registry.register(&mut source, token, interest)?;
+        Ok(Registered {
+            source: Some(source),
+            token,
+            registry: self.registry.clone(),
+        })
+    }
+
+    /// Waits for events, at most `timeout`,
+    /// forever, if it's `None`, returns tokens
+    /// of ready sources and `WAKER`, if woken
+    pub fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Vec<Token>> {
+        match self.poll.poll(&mut self.events, timeout) {
+            Ok(()) => Ok(self.events.iter().map(|event| event.token()).collect()),
+            Err(err) if err.kind() == ErrorKind::Interrupted => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+}
+/// Debug implementation
+impl Debug for Reactor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reactor").field("next", &self.next).finish()
+    }
+}
+
+/// Io source, registered in reactor,
+/// it's deregistered, when dropped
+pub struct Registered<S: Source> {
+    source: Option<S>,
+    pub token: Token,
+    registry: Rc<Registry>,
+}
+/// Registered source implementation
+impl<S: Source> Registered<S> {
+    /// Registered source
+    pub fn get(&mut self) -> &mut S {
+        self.source.as_mut().unwrap()
+    }
+
+    /// Replaces source, registering
+    /// new one with same token
+    pub fn replace(&mut self, mut source: S, interest: Interest) -> io::Result<()> {
+        if let Some(mut old) = self.source.take() {
+            let _ = self.registry.deregister(&mut old);
+        }
+        self.registry.register(&mut source, self.token, interest)?;
+        self.source = Some(source);
+        Ok(())
+    }
+
+    /// Deregisters source, returns it
+    pub fn into_inner(mut self) -> S {
+        let mut source = self.source.take().unwrap();
+        let _ = self.registry.deregister(&mut source);
+        source
+    }
+}
+/// Drop implementation
+impl<S: Source> Drop for Registered<S> {
+    fn drop(&mut self) {
+        if let Some(source) = self.source.as_mut() {
+            let _ = self.registry.deregister(source);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// imports
+use corosensei::stack::{DefaultStack, Stack};
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Host stack segment size, segments are small, since
+/// every started and unfinished generator or task body
+/// holds one, memory is reserved, but committed lazily.
+///
+/// segment is mapped with guard page, that's two memory
+/// mappings, so amount of suspended bodies is bounded by
+/// `vm.max_map_count` of os, about 32k by default
+///
+pub const SEGMENT_SIZE: usize = 1024 * 1024;
+
+/// Host stack, that is kept free for single op,
+/// when less is left, run continues on next segment
+const RED_ZONE: usize = 256 * 1024;
+
+/// Max amount of free segments in pool
+const POOL_SIZE: usize = 16;
+
+/// Host stack
+///
+/// vm runs chunks on stack segments, allocated by itself,
+/// so depth of calls doesn't depend on host thread stack.
+/// when current segment is nearly exhausted, run continues
+/// on next segment, taken from pool
+///
+/// * `limit`: lowest address, current segment could be used
+///   to, `usize::MAX` when vm is not running on segment yet
+/// * `pool`: free segments
+///
+pub struct HostStack {
+    pub limit: usize,
+    pool: Vec<DefaultStack>,
+}
+/// Host stack implementation
+impl HostStack {
+    /// New host stack, vm is not on segment yet
+    pub fn new() -> HostStack {
+        HostStack {
+            limit: usize::MAX,
+            pool: Vec::new(),
+        }
+    }
+
+    /// Is current segment nearly exhausted
+    #[inline]
+    pub fn exhausted(&self) -> bool {
+        (psm::stack_pointer() as usize) < self.limit
+    }
+
+    /// Takes segment from pool, or allocates new
+    pub fn take(&mut self) -> std::io::Result<DefaultStack> {
+        match self.pool.pop() {
+            Some(segment) => Ok(segment),
+            None => DefaultStack::new(SEGMENT_SIZE),
+        }
+    }
+
+    /// Returns segment to pool
+    pub fn put(&mut self, segment: DefaultStack) {
+        if self.pool.len() < POOL_SIZE {
+            self.pool.push(segment);
+        }
+    }
+
+    /// Limit of segment, it could be used to
+    pub fn limit_of(segment: &DefaultStack) -> usize {
+        segment.limit().get() + RED_ZONE
+    }
+
+    /// Enters segment, returns previous limit
+    pub fn enter(&mut self, segment: &DefaultStack) -> usize {
+        std::mem::replace(&mut self.limit, Self::limit_of(segment))
+    }
+
+    /// Leaves segment, restoring previous limit,
+    /// segment is returned to pool
+    pub fn leave(&mut self, segment: DefaultStack, previous: usize) {
+        self.limit = previous;
+        self.put(segment);
+    }
+}
+/// Debug implementation
+impl Debug for HostStack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostStack")
+            .field("limit", &self.limit)
+            .field("pool", &self.pool.len())
+            .finish()
+    }
+}
+/// Default implementation
+impl Default for HostStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs `f` on segment, panic of `f` is caught,
+/// since it couldn't unwind through segment switch
+pub unsafe fn on_segment<R>(
+    segment: &DefaultStack,
+    f: impl FnOnce() -> R,
+) -> Result<R, Box<dyn Any + Send>> {
+    let base = segment.limit().get() as *mut u8;
+    let size = segment.base().get() - segment.limit().get();
+    psm::on_stack(base, size, || panic::catch_unwind(AssertUnwindSafe(f)))
+}
@@ -1,8 +1,6 @@
 // imports
-use crate::memory::memory;
 use crate::values::Value;
 use rustc_hash::FxHashMap;
-use std::collections::HashSet;
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
@@ -19,7 +17,7 @@ pub struct Table {
     ///
     /// root table, previous lexical table
     /// for example:
-    /// ```
+    /// ```text
     /// if a { // table one
     ///   if b { // table two, root: table one
     ///   }
@@ -33,6 +31,17 @@ pub struct Table {
     pub closure: *mut Table,
     /// captures amount
     pub captures: usize,
+    /// table is owned by old generation object,
+    /// writes to it are remembered by gc
+    pub old: bool,
+    /// table is in gc remembered set
+    pub remembered: bool,
+    /// table is shared by instances, like methods
+    /// of type, its variables, assigned from instance,
+    /// are shadowed by the instance fields
+    pub shared: bool,
+    /// table shadows variables of shared root table
+    pub shadows: bool,
 }
 /// Table implementation
 impl Table {
@@ -44,6 +53,10 @@ impl Table {
             parent: std::ptr::null_mut(),
             closure: std::ptr::null_mut(),
             captures: 0,
+            old: false,
+            remembered: false,
+            shared: false,
+            shadows: false,
         }
     }
 
@@ -95,26 +108,45 @@ impl Table {
         }
     }
 
-    /// Sets variable in fields or roots
+    /// Sets variable in fields or roots,
+    /// returns table, variable was set in
     ///
     /// raises error if not defined
     ///
-    pub unsafe fn set(&mut self, address: Address, name: &str, value: Value) {
-        if self.fields.contains_key(name) {
-            self.fields.insert(name.to_string(), value);
-        } else if !self.root.is_null() && (*self.root).has(name) {
-            (*self.root).set(address, name, value);
-        } else if !self.closure.is_null() && (*self.closure).exists(name) {
-            (*self.closure).set(address, name, value);
-        } else {
-            error!(Error::own_text(
-                address.clone(),
-                format!("{name} is not defined."),
-                "check variable existence.",
-            ));
+    pub unsafe fn set(&mut self, address: Address, name: &str, value: Value) -> *mut Table {
+        // roots chain is walked by loop, since
+        // it's as long as calls chain
+        let mut table: *mut Table = self;
+        loop {
+            if (*table).fields.contains_key(name) {
+                (*table).fields.insert(name.to_string(), value);
+                return table;
+            } else if !(*table).root.is_null() && (*(*table).root).has(name) {
+                // variables of shared root are shadowed
+                if (*(*table).root).shared && (*(*table).root).fields.contains_key(name) {
+                    (*table).shadow(name, value);
+                    return table;
+                }
+                table = (*table).root;
+            } else if !(*table).closure.is_null() && (*(*table).closure).exists(name) {
+                table = (*table).closure;
+            } else {
+                error!(Error::own_text(
+                    address.clone(),
+                    format!("{name} is not defined."),
+                    "check variable existence.",
+                ));
+            }
         }
     }
 
+    /// Shadows variable of shared root table,
+    /// like method of type, by variable in fields
+    pub fn shadow(&mut self, name: &str, value: Value) {
+        self.fields.insert(name.to_string(), value);
+        self.shadows = true;
+    }
+
     /// Sets variable in fields
     ///
     /// raises error if not defined
@@ -132,13 +164,14 @@ impl Table {
 
     /// Checks variable exists in fields, closures or roots
     pub unsafe fn has(&mut self, name: &str) -> bool {
-        if self.exists(name) {
-            true
-        } else if !self.root.is_null() {
-            (*self.root).has(name)
-        } else {
-            false
+        let mut table: *const Table = self;
+        while !table.is_null() {
+            if (*table).exists(name) {
+                return true;
+            }
+            table = (*table).root;
         }
+        false
     }
 
     /// Finds variable in fields
@@ -147,32 +180,59 @@ impl Table {
     /// raises error if not exists
     ///
     pub unsafe fn lookup(&mut self, address: &Address, name: &str) -> Value {
-        if self.fields.contains_key(name) {
-            self.fields[name]
-        } else if !self.root.is_null() && (*self.root).has(name) {
-            (*self.root).lookup(address, name)
-        } else if !self.closure.is_null() && (*self.closure).exists(name) {
-            (*self.closure).find(address, name)
-        } else {
-            error!(Error::own_text(
-                address.clone(),
-                format!("{name} is not defined."),
-                "check variable existence.",
-            ))
+        match self.try_lookup(address, name) {
+            Some(value) => value,
+            None => {
+                error!(Error::own_text(
+                    address.clone(),
+                    format!("{name} is not defined."),
+                    "check variable existence.",
+                ))
+            }
         }
     }
 
+    /// Finds variable in fields
+    /// roots, and closures in a single
+    /// pass over the roots chain
+    ///
+    /// returns none if not exists
+    ///
+    unsafe fn try_lookup(&self, address: &Address, name: &str) -> Option<Value> {
+        // fields of roots chain, closures are kept,
+        // since they are checked from the last root
+        let mut closures: Vec<*mut Table> = Vec::new();
+        let mut table: *const Table = self;
+        while !table.is_null() {
+            if let Some(value) = (*table).fields.get(name) {
+                return Some(*value);
+            }
+            if !(*table).closure.is_null() {
+                closures.push((*table).closure);
+            }
+            table = (*table).root;
+        }
+
+        // closures of roots chain
+        for closure in closures.into_iter().rev() {
+            if (*closure).exists(name) {
+                return Some((*closure).find(address, name));
+            }
+        }
+        None
+    }
+
     /// Sets root
     ///
     /// if root already exists, set root to root, and if root's root is already
     /// exists, set root to root's root, ...
     ///
     pub unsafe fn set_root(&mut self, root: *mut Table) {
-        if self.root.is_null() {
-            self.root = root
-        } else {
-            (*self.root).set_root(root);
+        let mut table: *mut Table = self;
+        while !(*table).root.is_null() {
+            table = (*table).root;
         }
+        (*table).root = root;
     }
 
     /// Deletes last root from roots chain
@@ -187,59 +247,6 @@ impl Table {
         }
     }
 
-    /// Frees all field values
-    pub unsafe fn free_fields(&self) {
-        // to free list
-        let to_free: HashSet<&Value> = self.fields.values().collect();
-
-        // freeing
-        for val in to_free {
-            match *val {
-                Value::Fn(f) => {
-                    if !f.is_null() {
-                        memory::free_value(f);
-                    }
-                }
-                Value::Instance(i) => {
-                    if !i.is_null() {
-                        memory::free_value(i);
-                    }
-                }
-                Value::String(s) => {
-                    if !s.is_null() {
-                        memory::free_const_value(s);
-                    }
-                }
-                Value::Native(n) => {
-                    if !n.is_null() {
-                        memory::free_value(n);
-                    }
-                }
-                Value::Unit(u) => {
-                    if !u.is_null() {
-                        memory::free_value(u);
-                    }
-                }
-                Value::List(l) => {
-                    if !l.is_null() {
-                        memory::free_value(l);
-                    }
-                }
-                Value::Type(t) => {
-                    if !t.is_null() {
-                        memory::free_value(t);
-                    }
-                }
-                Value::Trait(t) => {
-                    if !t.is_null() {
-                        memory::free_value(t);
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-
     /// Prints table tree
     #[allow(unused)]
     pub unsafe fn print(&self, indent: usize) {
@@ -0,0 +1,244 @@
+// imports
+use crate::flow::ControlFlow;
+use crate::generator::Generator;
+use crate::table::Table;
+use crate::values::{Unboxed, Value};
+use crate::vm::VM;
+use mio::{Token, Waker};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+use watt_common::address::Address;
+
+/// Task poll, called by scheduler, when task is woken,
+/// until it pushes task result and returns `true`
+pub type TaskPoll = Box<dyn FnMut(&mut VM, &Address, *mut Table) -> Result<bool, ControlFlow>>;
+
+/// Wake of poll task
+///
+/// `Io` task is woken, when it's io source is ready,
+/// `Waker` task is woken by other thread, with reactor
+/// waker, `Interval` task is polled on every tick, and
+/// event loop waits at most interval for it, it's for
+/// sources without readiness, like regular files
+///
+#[derive(Debug, Clone, Copy)]
+pub enum Wake {
+    Io(Token),
+    Waker,
+    Interval(Duration),
+}
+
+/// Task kind
+///
+/// `Body` runs `async fn` body on generator frame,
+/// `Sleep` is done after deadline, `Poll` is done,
+/// when it's poll is ready
+///
+pub enum TaskKind {
+    Body(Generator),
+    Sleep(Instant),
+    Poll(Wake, TaskPoll),
+}
+
+/// Task
+///
+/// * `addr`: address, task was spawned at
+/// * `kind`: what task is waiting for
+/// * `result`: task result, when it's done
+/// * `waiters`: tasks, suspended by awaiting this task
+/// * `refs`: values, used by task poll, kept alive by task
+/// * `woken`: poll should be called on next tick
+///
+pub struct Task {
+    pub addr: Address,
+    pub kind: TaskKind,
+    pub result: Option<Value>,
+    pub waiters: Vec<Value>,
+    pub refs: Vec<Value>,
+    pub woken: bool,
+}
+/// Task implementation
+impl Task {
+    /// New task
+    pub fn new(addr: Address, kind: TaskKind) -> Task {
+        Task {
+            addr,
+            kind,
+            result: None,
+            waiters: Vec::new(),
+            refs: Vec::new(),
+            woken: true,
+        }
+    }
+
+    /// Is task done
+    pub fn is_done(&self) -> bool {
+        self.result.is_some()
+    }
+}
+
+/// Task scheduler
+///
+/// * `ready`: tasks with body, ready to be resumed
+/// * `sleeping`: sleep tasks, waiting for deadline
+/// * `polling`: poll tasks, polled when woken
+/// * `running`: tasks, which bodies are running now
+/// * `io`: poll tasks by tokens of their io sources
+///
+/// all of them are gc roots, suspended tasks
+/// are kept alive by tasks they are awaiting
+///
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    pub ready: VecDeque<Value>,
+    pub sleeping: Vec<Value>,
+    pub polling: Vec<Value>,
+    pub running: Vec<Value>,
+    pub io: HashMap<Token, Value>,
+}
+/// Scheduler implementation
+impl Scheduler {
+    /// Nearest sleep deadline
+    pub unsafe fn nearest_deadline(&self) -> Option<Instant> {
+        self.sleeping
+            .iter()
+            .filter_map(|value| match &(*task_of(*value)).kind {
+                TaskKind::Sleep(deadline) => Some(*deadline),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// Shortest interval of polling tasks
+    pub unsafe fn shortest_interval(&self) -> Option<Duration> {
+        self.polling
+            .iter()
+            .filter_map(|value| match &(*task_of(*value)).kind {
+                TaskKind::Poll(Wake::Interval(interval), _) => Some(*interval),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// Wakes tasks by ready tokens
+    pub unsafe fn wake(&mut self, tokens: &[Token]) {
+        for token in tokens {
+            if *token == crate::reactor::WAKER {
+                for value in &self.polling {
+                    let task = task_of(*value);
+                    if let TaskKind::Poll(Wake::Waker, _) = (*task).kind {
+                        (*task).woken = true;
+                    }
+                }
+            } else if let Some(value) = self.io.get(token) {
+                (*task_of(*value)).woken = true;
+            }
+        }
+    }
+
+    /// Is any task pending
+    pub fn has_pending(&self) -> bool {
+        !self.ready.is_empty() || !self.sleeping.is_empty() || !self.polling.is_empty()
+    }
+
+    /// All scheduled tasks
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.ready
+            .iter()
+            .chain(&self.sleeping)
+            .chain(&self.polling)
+            .chain(&self.running)
+    }
+}
+
+/// Blocking pool threads amount
+const BLOCKING_THREADS: usize = 4;
+
+/// Job of blocking pool
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Blocking pool, runs work, that has no non-blocking
+/// api, like http requests, on fixed amount of threads,
+/// started with first job
+static BLOCKING: OnceLock<Result<Mutex<Sender<Job>>, String>> = OnceLock::new();
+
+/// Starts blocking pool
+fn start_blocking() -> Result<Mutex<Sender<Job>>, String> {
+    let (sender, receiver) = mpsc::channel::<Job>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..BLOCKING_THREADS {
+        let receiver: Arc<Mutex<Receiver<Job>>> = receiver.clone();
+        std::thread::Builder::new()
+            .name("watt-blocking".to_string())
+            .spawn(move || {
+                loop {
+                    // lock is released before running job
+                    let job = receiver
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .recv();
+                    match job {
+                        Ok(job) => {
+                            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                        }
+                        Err(_) => break,
+                    }
+                }
+            })
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(Mutex::new(sender))
+}
+
+/// Poll of blocking `work`, run by blocking pool,
+/// it's output is pushed by `push`, when it's done,
+/// reactor is woken by `waker` then
+pub fn blocking_poll<T: Send + 'static>(
+    waker: Arc<Waker>,
+    work: impl FnOnce() -> T + Send + 'static,
+    mut push: impl FnMut(&mut VM, &Address, *mut Table, T) -> Result<(), ControlFlow> + 'static,
+) -> io::Result<TaskPoll> {
+    // sending job
+    let (sender, receiver) = mpsc::channel();
+    let job: Job = Box::new(move || {
+        let _ = sender.send(work());
+        let _ = waker.wake();
+    });
+    let pool = BLOCKING.get_or_init(start_blocking).as_ref();
+    let sent = pool
+        .map_err(|err| io::Error::other(err.clone()))
+        .and_then(|pool| {
+            pool.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .send(job)
+                .map_err(|_| io::Error::other("blocking pool is stopped"))
+        });
+    sent?;
+
+    // polling output
+    Ok(Box::new(move |vm, addr, table| match receiver.try_recv() {
+        Ok(output) => {
+            push(vm, addr, table, output)?;
+            Ok(true)
+        }
+        Err(TryRecvError::Empty) => Ok(false),
+        Err(TryRecvError::Disconnected) => {
+            panic!("blocking job is disconnected. report this error to the developer.")
+        }
+    }))
+}
+
+/// Gets task of task handle value
+pub unsafe fn task_of(value: Value) -> *mut Task {
+    match value.unbox() {
+        Unboxed::Any(any) => match (*any).downcast_mut::<Task>() {
+            Some(task) => task,
+            None => panic!("task handle is not a task. report this error to the developer."),
+        },
+        _ => panic!("task handle is not an any value. report this error to the developer."),
+    }
+}
@@ -1,11 +1,14 @@
 // imports
-use crate::bytecode::Chunk;
+use crate::bytecode::{Chunk, FnKind};
 use crate::flow::ControlFlow;
-use crate::memory::memory;
+use crate::memory::memory::{self, WideArena};
 use crate::table::Table;
 use crate::vm::{VM, try_free_table};
+use num_bigint::BigInt;
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use watt_common::address::Address;
 
 /// Symbol structure
@@ -49,37 +52,79 @@ impl Symbol {
 /// Type is a `instruction` to build
 /// an instance
 ///
+/// * `addr`: address of declaration
+/// * `methods`: methods table, shared by all
+///   instances of type
+/// * `shape`: unique type id, used by inline caches
+/// * `traits`: traits of `impls`, resolved
+///   when first instance is created
+///
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Type {
     pub name: Symbol,
+    pub addr: Address,
     pub constructor: Vec<String>,
     pub body: *const Chunk,
     pub impls: Vec<String>,
+    pub methods: *mut Table,
+    pub shape: usize,
+    pub traits: Vec<*mut Trait>,
 }
 /// Type implementation
 impl Type {
     /// New type
     pub fn new(
         name: Symbol,
+        addr: Address,
         constructor: Vec<String>,
         body: *const Chunk,
         impls: Vec<String>,
     ) -> Type {
+        // methods table, shared by instances
+        let mut methods = Table::new();
+        methods.shared = true;
         Type {
             name,
+            addr,
             constructor,
             body,
             impls,
+            methods: memory::alloc_value(methods),
+            shape: NEXT_SHAPE.fetch_add(1, Ordering::Relaxed),
+            traits: Vec::new(),
         }
     }
+
+    /// Checks type impls trait by `name`, directly
+    /// or through parents of resolved traits
+    ///
+    /// # Safety
+    ///
+    /// traits of type should be alive
+    ///
+    pub unsafe fn impls_named(&self, name: &str) -> bool {
+        self.impls.iter().any(|_impl| _impl == name)
+            || self.traits.iter().any(|_trait| {
+                Trait::with_parents(*_trait)
+                    .iter()
+                    .any(|parent| (**parent).is_named(name))
+            })
+    }
 }
 /// Type drop implementation
 impl Drop for Type {
     fn drop(&mut self) {
         memory::free_const_value(self.body);
+        unsafe {
+            try_free_table(self.methods);
+        }
     }
 }
 
+/// Next type shape id, shapes are
+/// never reused, even if type is freed
+static NEXT_SHAPE: AtomicUsize = AtomicUsize::new(1);
+
 /// Instance structure
 ///
 /// Object created from `Type`
@@ -134,6 +179,92 @@ impl Drop for Unit {
     }
 }
 
+/// Enum variant declaration
+///
+/// * `fields`: names of variant payload
+///   values, empty for constant variant
+///
+#[derive(Clone, Debug)]
+pub struct EnumVariant {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+/// Enum variant implementation
+impl EnumVariant {
+    /// New enum variant
+    pub fn new(name: String, fields: Vec<String>) -> EnumVariant {
+        EnumVariant { name, fields }
+    }
+}
+
+/// Enum
+///
+/// Set of variants, every variant
+/// can carry payload values
+///
+/// * `addr`: address of declaration
+/// * `constants`: values of variants without
+///   payload, created once per enum
+///
+#[derive(Clone, Debug)]
+#[allow(unused)]
+pub struct Enum {
+    pub name: Symbol,
+    pub addr: Address,
+    pub variants: Vec<EnumVariant>,
+    pub constants: Vec<Value>,
+}
+/// Enum implementation
+impl Enum {
+    /// New enum
+    pub fn new(name: Symbol, addr: Address, variants: Vec<EnumVariant>) -> Enum {
+        Enum {
+            name,
+            addr,
+            variants,
+            constants: Vec::new(),
+        }
+    }
+
+    /// Finds variant index by name
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.variants
+            .iter()
+            .position(|variant| variant.name == name)
+    }
+}
+
+/// Variant
+///
+/// Value of enum variant
+///
+/// * `index`: index of variant in enum
+/// * `values`: payload values
+///
+#[derive(Clone, Debug)]
+pub struct Variant {
+    pub e: *mut Enum,
+    pub index: usize,
+    pub values: Vec<Value>,
+}
+/// Variant implementation
+impl Variant {
+    /// New variant
+    pub fn new(e: *mut Enum, index: usize, values: Vec<Value>) -> Variant {
+        Variant { e, index, values }
+    }
+
+    /// Variant declaration
+    ///
+    /// # Safety
+    ///
+    /// enum of variant should be alive
+    ///
+    pub unsafe fn declaration(&self) -> &EnumVariant {
+        &(*self.e).variants[self.index]
+    }
+}
+
 /// Default trait fn realisation
 #[derive(Clone, Debug)]
 pub struct DefaultTraitFn {
@@ -186,12 +317,50 @@ impl TraitFn {
 #[allow(unused)]
 pub struct Trait {
     pub name: Symbol,
+    pub addr: Address,
+    pub parents: Vec<*mut Trait>,
     pub functions: Vec<TraitFn>,
 }
 /// Trait implementation
 impl Trait {
-    pub fn new(name: Symbol, functions: Vec<TraitFn>) -> Trait {
-        Trait { name, functions }
+    pub fn new(
+        name: Symbol,
+        addr: Address,
+        parents: Vec<*mut Trait>,
+        functions: Vec<TraitFn>,
+    ) -> Trait {
+        Trait {
+            name,
+            addr,
+            parents,
+            functions,
+        }
+    }
+
+    /// Trait with all of it's parents,
+    /// trait itself goes first, each trait once
+    ///
+    /// # Safety
+    ///
+    /// `this` and it's parents should be alive traits
+    ///
+    pub unsafe fn with_parents(this: *mut Trait) -> Vec<*mut Trait> {
+        let mut family: Vec<*mut Trait> = Vec::new();
+        let mut pending: Vec<*mut Trait> = vec![this];
+        while let Some(_trait) = pending.pop() {
+            if family.contains(&_trait) {
+                continue;
+            }
+            family.push(_trait);
+            // parents in declaration order
+            pending.extend((*_trait).parents.iter().rev());
+        }
+        family
+    }
+
+    /// Checks trait is named by `name`
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.name == name || self.name.full_name.as_deref() == Some(name)
     }
 }
 /// Trait drop implementation
@@ -208,39 +377,92 @@ impl Drop for Trait {
 /// Owner of a function, be it
 /// a unit or instance.
 ///
+/// methods, shared by instances, are owned by
+/// type, their receiver is taken at call site.
+///
 #[derive(Clone, Debug)]
 pub enum FnOwner {
     Unit(*mut Unit),
     Instance(*mut Instance),
+    Type(*mut Type),
+}
+
+/// Fn parameter
+///
+/// * `name`: name of param
+/// * `default`: optional default value chunk,
+///   evaluated in call table
+/// * `variadic`: collects rest args to list
+///
+#[derive(Clone, Debug)]
+pub struct Param {
+    pub name: String,
+    pub default: Option<Chunk>,
+    pub variadic: bool,
+}
+/// Fn parameter implementation
+impl Param {
+    /// New param
+    pub fn new(name: String, default: Option<Chunk>, variadic: bool) -> Param {
+        Param {
+            name,
+            default,
+            variadic,
+        }
+    }
+}
+/// Fn parameter display implementation
+impl Display for Param {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.variadic {
+            write!(f, "..{}", self.name)
+        } else if self.default.is_some() {
+            write!(f, "{} = ...", self.name)
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
 }
 
 /// Function
 ///
 /// Just a function that have name,
 /// params, body, `closure`, `owner`
-/// (something, that owns function, be it unit or instance)
+/// (something, that owns function, be it unit or instance),
+/// `kind` tells, what fn returns, when called
 ///
 #[derive(Clone, Debug)]
 #[allow(unused)]
 pub struct Function {
     pub name: Symbol,
     pub body: *const Chunk,
-    pub params: Vec<String>,
+    pub params: Vec<Param>,
     pub owner: Option<FnOwner>,
     pub closure: *mut Table,
+    pub kind: FnKind,
+    pub module: Option<PathBuf>,
 }
 /// Function implementation
 impl Function {
     /// New function
-    pub fn new(name: Symbol, body: *const Chunk, params: Vec<String>) -> Function {
+    pub fn new(name: Symbol, body: *const Chunk, params: Vec<Param>) -> Function {
         Function {
             name,
             body,
             params,
             owner: None,
             closure: std::ptr::null_mut(),
+            kind: FnKind::Plain,
+            module: None,
         }
     }
+
+    /// Fn signature with given name,
+    /// like `connect(host, port = ...)`
+    pub fn signature(&self, name: &str) -> String {
+        let params: Vec<String> = self.params.iter().map(|p| p.to_string()).collect();
+        format!("{name}({})", params.join(", "))
+    }
 }
 /// Function drop implementation
 impl Drop for Function {
@@ -283,11 +505,82 @@ impl Native {
     }
 }
 
+/// Any value
+///
+/// rust value of any type, boxed so
+/// `Value::Any` holds a thin pointer
+///
+pub type AnyValue = Box<dyn std::any::Any>;
+
+/// Boxed int
+///
+/// int, that doesn't fit value payload. cells are
+/// kept by arena of vm gc, see `memory::WideArena`,
+/// and swept by full gc
+///
+/// * `value`: int value
+/// * `marked`: cell is reached by current full gc
+///
+#[derive(Debug)]
+pub struct WideInt {
+    pub value: i64,
+    pub marked: bool,
+}
+
+/// Quiet nan bits, that are set in every tagged value,
+/// float nan-s are made canonical, so they never match it
+const TAGGED: u64 = 0xFFF8_0000_0000_0000;
+
+/// Tag shift, tag is stored in 4 bits above payload
+const TAG_SHIFT: u32 = 47;
+
+/// Payload mask, 47 low bits
+const PAYLOAD: u64 = (1 << TAG_SHIFT) - 1;
+
+/// Pointer shift, pointers are aligned
+/// by 8 bytes, so 3 low bits are dropped
+const PTR_SHIFT: u32 = 3;
+
+/// Canonical nan
+const CANONICAL_NAN: u64 = 0x7FF8_0000_0000_0000;
+
+/// Value tags
+const TAG_INT: u64 = 0;
+const TAG_WIDE: u64 = 1;
+const TAG_BIGINT: u64 = 2;
+const TAG_STRING: u64 = 3;
+const TAG_BOOL: u64 = 4;
+const TAG_TYPE: u64 = 5;
+const TAG_FN: u64 = 6;
+const TAG_NATIVE: u64 = 7;
+const TAG_INSTANCE: u64 = 8;
+const TAG_UNIT: u64 = 9;
+const TAG_TRAIT: u64 = 10;
+const TAG_LIST: u64 = 11;
+const TAG_ANY: u64 = 12;
+const TAG_ENUM: u64 = 13;
+const TAG_VARIANT: u64 = 14;
+const TAG_NULL: u64 = 15;
+
 /// Value
+///
+/// nan-boxed 8 byte word. float is stored as is, other
+/// values are tagged quiet nan-s with 47 bits payload:
+/// pointers, shifted by alignment, bools and ints, that
+/// fit payload, wider ints are boxed into `WideInt`.
+///
+/// value is matched by it's unboxed form, see `unbox`,
+/// constructors are named after `Unboxed` variants
+///
+#[derive(Clone, Copy)]
+pub struct Value(u64);
+
+/// Unboxed value
 #[derive(Clone, Copy)]
-pub enum Value {
+pub enum Unboxed {
     Float(f64),
     Int(i64),
+    BigInt(*mut BigInt),
     String(*const String),
     Bool(bool),
     Type(*mut Type),
@@ -297,52 +590,258 @@ pub enum Value {
     Unit(*mut Unit),
     Trait(*mut Trait),
     List(*mut Vec<Value>),
-    Any(*mut dyn std::any::Any),
+    Any(*mut AnyValue),
+    Enum(*mut Enum),
+    Variant(*mut Variant),
     Null,
 }
+
+/// Value implementation
+#[allow(non_snake_case, non_upper_case_globals)]
+impl Value {
+    /// Null value
+    pub const Null: Value = Value::tagged(TAG_NULL, 0);
+
+    /// Tagged value
+    #[inline]
+    const fn tagged(tag: u64, payload: u64) -> Value {
+        Value(TAGGED | (tag << TAG_SHIFT) | payload)
+    }
+
+    /// Tagged pointer value
+    #[inline]
+    fn pointer<T>(tag: u64, ptr: *const T) -> Value {
+        let address = ptr as u64;
+        debug_assert!(address & ((1 << PTR_SHIFT) - 1) == 0 && address >> PTR_SHIFT <= PAYLOAD);
+        Value::tagged(tag, address >> PTR_SHIFT)
+    }
+
+    /// Float value, nan is made canonical
+    #[inline]
+    pub fn Float(value: f64) -> Value {
+        if value.is_nan() {
+            Value(CANONICAL_NAN)
+        } else {
+            Value(value.to_bits())
+        }
+    }
+
+    /// Int value, it's boxed into `arena`,
+    /// if it doesn't fit payload
+    #[inline]
+    pub fn Int(value: i64, arena: &mut WideArena) -> Value {
+        let payload = (value as u64) & PAYLOAD;
+        if Value::sign_extend(payload) == value {
+            Value::tagged(TAG_INT, payload)
+        } else {
+            Value::pointer(TAG_WIDE, arena.alloc(value))
+        }
+    }
+
+    /// Big int value
+    #[inline]
+    pub fn BigInt(ptr: *mut BigInt) -> Value {
+        Value::pointer(TAG_BIGINT, ptr)
+    }
+
+    /// String value
+    #[inline]
+    pub fn String(ptr: *const String) -> Value {
+        Value::pointer(TAG_STRING, ptr)
+    }
+
+    /// Bool value
+    #[inline]
+    pub fn Bool(value: bool) -> Value {
+        Value::tagged(TAG_BOOL, value as u64)
+    }
+
+    /// Type value
+    #[inline]
+    pub fn Type(ptr: *mut Type) -> Value {
+        Value::pointer(TAG_TYPE, ptr)
+    }
+
+    /// Fn value
+    #[inline]
+    pub fn Fn(ptr: *mut Function) -> Value {
+        Value::pointer(TAG_FN, ptr)
+    }
+
+    /// Native value
+    #[inline]
+    pub fn Native(ptr: *mut Native) -> Value {
+        Value::pointer(TAG_NATIVE, ptr)
+    }
+
+    /// Instance value
+    #[inline]
+    pub fn Instance(ptr: *mut Instance) -> Value {
+        Value::pointer(TAG_INSTANCE, ptr)
+    }
+
+    /// Unit value
+    #[inline]
+    pub fn Unit(ptr: *mut Unit) -> Value {
+        Value::pointer(TAG_UNIT, ptr)
+    }
+
+    /// Trait value
+    #[inline]
+    pub fn Trait(ptr: *mut Trait) -> Value {
+        Value::pointer(TAG_TRAIT, ptr)
+    }
+
+    /// List value
+    #[inline]
+    pub fn List(ptr: *mut Vec<Value>) -> Value {
+        Value::pointer(TAG_LIST, ptr)
+    }
+
+    /// Any value
+    #[inline]
+    pub fn Any(ptr: *mut AnyValue) -> Value {
+        Value::pointer(TAG_ANY, ptr)
+    }
+
+    /// Enum value
+    #[inline]
+    pub fn Enum(ptr: *mut Enum) -> Value {
+        Value::pointer(TAG_ENUM, ptr)
+    }
+
+    /// Variant value
+    #[inline]
+    pub fn Variant(ptr: *mut Variant) -> Value {
+        Value::pointer(TAG_VARIANT, ptr)
+    }
+
+    /// Sign extends int payload
+    #[inline]
+    fn sign_extend(payload: u64) -> i64 {
+        ((payload << (64 - TAG_SHIFT)) as i64) >> (64 - TAG_SHIFT)
+    }
+
+    /// Tag of value, `None` for float
+    #[inline]
+    fn tag(self) -> Option<u64> {
+        if self.0 & TAGGED == TAGGED {
+            Some((self.0 >> TAG_SHIFT) & 0xF)
+        } else {
+            None
+        }
+    }
+
+    /// Pointer payload of value
+    #[inline]
+    fn ptr<T>(self) -> *mut T {
+        ((self.0 & PAYLOAD) << PTR_SHIFT) as *mut T
+    }
+
+    /// Boxed int cell, if value is wide int
+    #[inline]
+    pub fn wide(self) -> Option<*mut WideInt> {
+        match self.tag() {
+            Some(TAG_WIDE) => Some(self.ptr()),
+            _ => None,
+        }
+    }
+
+    /// Unboxes value, to match it
+    #[inline]
+    pub fn unbox(self) -> Unboxed {
+        let Some(tag) = self.tag() else {
+            return Unboxed::Float(f64::from_bits(self.0));
+        };
+        match tag {
+            TAG_INT => Unboxed::Int(Value::sign_extend(self.0 & PAYLOAD)),
+            TAG_WIDE => Unboxed::Int(unsafe { (*self.ptr::<WideInt>()).value }),
+            TAG_BIGINT => Unboxed::BigInt(self.ptr()),
+            TAG_STRING => Unboxed::String(self.ptr()),
+            TAG_BOOL => Unboxed::Bool(self.0 & PAYLOAD != 0),
+            TAG_TYPE => Unboxed::Type(self.ptr()),
+            TAG_FN => Unboxed::Fn(self.ptr()),
+            TAG_NATIVE => Unboxed::Native(self.ptr()),
+            TAG_INSTANCE => Unboxed::Instance(self.ptr()),
+            TAG_UNIT => Unboxed::Unit(self.ptr()),
+            TAG_TRAIT => Unboxed::Trait(self.ptr()),
+            TAG_LIST => Unboxed::List(self.ptr()),
+            TAG_ANY => Unboxed::Any(self.ptr()),
+            TAG_ENUM => Unboxed::Enum(self.ptr()),
+            TAG_VARIANT => Unboxed::Variant(self.ptr()),
+            _ => Unboxed::Null,
+        }
+    }
+
+    /// Is value null
+    #[inline]
+    pub fn is_null(self) -> bool {
+        self.0 == Value::Null.0
+    }
+}
 /// Debug implementation for value
 impl Debug for Value {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
         unsafe {
-            match self {
-                Value::String(s) => {
-                    write!(fmt, "{}", **s)
+            match self.unbox() {
+                Unboxed::String(s) => {
+                    write!(fmt, "{}", *s)
                 }
-                Value::Instance(i) => {
-                    write!(fmt, "Instance{:?}", *i)
+                Unboxed::Instance(i) => {
+                    write!(fmt, "Instance{i:?}")
                 }
-                Value::Trait(t) => {
-                    write!(fmt, "Trait{:?}", *t)
+                Unboxed::Trait(t) => {
+                    write!(fmt, "Trait{t:?}")
                 }
-                Value::Fn(f) => {
-                    write!(fmt, "Fn{:?}", (**f).name)
+                Unboxed::Fn(f) => {
+                    write!(fmt, "Fn{:?}", (*f).name)
                 }
-                Value::Native(n) => {
-                    write!(fmt, "Native{:?}", *n)
+                Unboxed::Native(n) => {
+                    write!(fmt, "Native{n:?}")
                 }
-                Value::Unit(n) => {
-                    write!(fmt, "Unit{:?}", *n)
+                Unboxed::Unit(n) => {
+                    write!(fmt, "Unit{n:?}")
                 }
-                Value::Null => {
+                Unboxed::Null => {
                     write!(fmt, "Null")
                 }
-                Value::Bool(b) => {
-                    write!(fmt, "{}", *b)
+                Unboxed::Bool(b) => {
+                    write!(fmt, "{b}")
+                }
+                Unboxed::Type(t) => {
+                    write!(fmt, "Type{t:?}")
                 }
-                Value::Type(t) => {
-                    write!(fmt, "Type{:?}", *t)
+                Unboxed::Int(i) => {
+                    write!(fmt, "{i}")
                 }
-                Value::Int(i) => {
+                Unboxed::BigInt(i) => {
                     write!(fmt, "{}", *i)
                 }
-                Value::Float(fl) => {
-                    write!(fmt, "{}", *fl)
+                Unboxed::Float(fl) => {
+                    write!(fmt, "{fl}")
+                }
+                Unboxed::List(l) => {
+                    write!(fmt, "List{l:?}")
+                }
+                Unboxed::Any(a) => {
+                    write!(fmt, "Any{a:?}")
                 }
-                Value::List(l) => {
-                    write!(fmt, "List{:?}", *l)
+                Unboxed::Enum(e) => {
+                    write!(fmt, "Enum{e:?}")
                 }
-                Value::Any(a) => {
-                    write!(fmt, "Any{:?}", *a)
+                Unboxed::Variant(v) => {
+                    write!(fmt, "{}", (*v).declaration().name)?;
+                    if !(*v).values.is_empty() {
+                        write!(fmt, "(")?;
+                        for (i, value) in (*v).values.iter().enumerate() {
+                            if i > 0 {
+                                write!(fmt, ", ")?;
+                            }
+                            write!(fmt, "{value:?}")?;
+                        }
+                        write!(fmt, ")")?;
+                    }
+                    Ok(())
                 }
             }
         }
@@ -351,9 +850,9 @@ impl Debug for Value {
 /// Display implementation for value
 impl Display for Value {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Value::Instance(i) => unsafe {
-                write!(fmt, "Instance{:?} of {:?}", *i, (*(**i).t).name.name)
+        match self.unbox() {
+            Unboxed::Instance(i) => unsafe {
+                write!(fmt, "Instance{:?} of {:?}", i, (*(*i).t).name.name)
             },
             _ => write!(fmt, "{self:?}"),
         }
@@ -365,26 +864,17 @@ impl Display for Value {
 ///  compared by value,
 /// Reference types
 ///  (`instance`, `type`, `fn`, `native`, `list`
-///   `native`, `trait`, `any`, `unit`)
-///  compared by pointer address
+///   `native`, `trait`, `any`, `unit`, `bigint`,
+///   `enum`, `variant`)
+///  compared by pointer address, so by value word
 ///
-#[allow(unused_unsafe)]
 impl PartialEq for Value {
     fn eq(&self, other: &Value) -> bool {
-        match (*self, *other) {
-            (Value::Instance(a), Value::Instance(b)) => unsafe { a == b },
-            (Value::Fn(a), Value::Fn(b)) => unsafe { a == b },
-            (Value::Native(a), Value::Native(b)) => unsafe { a == b },
-            (Value::Bool(a), Value::Bool(b)) => unsafe { a == b },
-            (Value::Type(a), Value::Type(b)) => unsafe { a == b },
-            (Value::String(a), Value::String(b)) => unsafe { a == b },
-            (Value::Int(a), Value::Int(b)) => a == b,
-            (Value::Float(a), Value::Float(b)) => a == b,
-            (Value::Unit(a), Value::Unit(b)) => unsafe { a == b },
-            (Value::Trait(a), Value::Trait(b)) => unsafe { a == b },
-            (Value::List(a), Value::List(b)) => unsafe { a == b },
-            (Value::Any(a), Value::Any(b)) => unsafe { std::ptr::addr_eq(a, b) },
-            _ => false,
+        match (self.unbox(), other.unbox()) {
+            (Unboxed::Int(a), Unboxed::Int(b)) => a == b,
+            (Unboxed::Float(a), Unboxed::Float(b)) => a == b,
+            (Unboxed::Null, _) | (_, Unboxed::Null) => false,
+            _ => self.0 == other.0,
         }
     }
 }
@@ -393,47 +883,12 @@ impl Eq for Value {}
 /// Hash implementation for value
 impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        match *self {
-            Value::Instance(a) => {
-                (a as usize).hash(state);
-            }
-            Value::Fn(a) => {
-                (a as usize).hash(state);
-            }
-            Value::Native(a) => {
-                (a as usize).hash(state);
-            }
-            Value::Bool(a) => {
-                a.hash(state);
-            }
-            Value::Type(a) => {
-                (a as usize).hash(state);
-            }
-            Value::String(a) => {
-                (a as usize).hash(state);
-            }
-            Value::Int(a) => {
-                a.hash(state);
-            }
-            Value::Float(a) => {
-                a.to_bits().hash(state);
-            }
-            Value::Unit(a) => {
-                (a as usize).hash(state);
-            }
-            Value::Trait(a) => {
-                (a as usize).hash(state);
-            }
-            Value::List(a) => {
-                (a as usize).hash(state);
-            }
-            Value::Any(a) => {
-                let any_ptr = a as *const () as usize;
-                any_ptr.hash(state);
-            }
-            Value::Null => {
-                0.hash(state);
-            }
+        match self.wide() {
+            Some(wide) => unsafe { (*wide).value.hash(state) },
+            None => self.0.hash(state),
         }
     }
 }
+
+/// Value size check
+const _: () = assert!(size_of::<Value>() == 8);
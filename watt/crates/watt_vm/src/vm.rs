@@ -1,35 +1,177 @@
 // imports
-use crate::bytecode::{Chunk, Opcode, OpcodeValue};
+use crate::bytecode::{
+    Chunk, DestructureKind, FnKind, InlineCache, MatchArm, Opcode, OpcodeValue, Pattern,
+};
+use crate::coverage::Coverage;
+use crate::debugger::Debugger;
 use crate::flow::ControlFlow;
 use crate::gc_guard;
+use crate::generator::{Generator, GeneratorCoroutine, GeneratorYielder};
 use crate::memory::gc::{GC, GcGuard};
 use crate::memory::memory;
+use crate::memory::snapshot;
 use crate::natives::natives;
+use crate::permissions::Permissions;
+use crate::profiler::Profiler;
+use crate::reactor::Reactor;
+use crate::stack::{self, HostStack};
 use crate::table::Table;
+use crate::task::{Scheduler, Task, TaskKind, Wake, task_of};
 use crate::values::*;
+use corosensei::CoroutineResult;
+use corosensei::stack::DefaultStack;
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
 use scopeguard::defer;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::path::Path;
+use std::time::{Duration, Instant};
 use watt_common::address::Address;
 use watt_common::{error, errors::Error};
 
+/// Vm limits
+///
+/// execution budget for untrusted
+/// scripts, `None` is unlimited
+///
+#[derive(Debug, Default, Clone)]
+pub struct VmLimits {
+    pub max_instructions: Option<u64>,
+    pub timeout: Option<Duration>,
+    pub max_stack: Option<usize>,
+    pub max_objects: Option<usize>,
+    pub max_bytes: Option<usize>,
+}
+
+/// Gc mode
+///
+/// `MarkSweep` collects whole heap every time,
+/// `Generational` collects young objects, when
+/// `nursery` of them are allocated, and whole
+/// heap only, when threshold is reached
+///
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum GcMode {
+    #[default]
+    MarkSweep,
+    Generational {
+        nursery: usize,
+    },
+}
+
+/// Gc statistics
+///
+/// snapshot of garbage collector state,
+/// objects are counted by kind, bytes
+/// are approximate
+///
+#[derive(Debug, Default, Clone)]
+pub struct GcStats {
+    pub collections: u64,
+    pub minor_collections: u64,
+    pub objects: usize,
+    pub instances: usize,
+    pub lists: usize,
+    pub strings: usize,
+    pub fns: usize,
+    pub natives: usize,
+    pub units: usize,
+    pub any: usize,
+    pub types: usize,
+    pub traits: usize,
+    pub bigints: usize,
+    pub bytes: usize,
+    pub last_pause: Duration,
+    pub total_pause: Duration,
+    pub max_pause: Duration,
+    pub threshold: usize,
+}
+/// Gc statistics implementation
+impl GcStats {
+    /// Prints statistics summary
+    pub fn print_summary(&self) {
+        println!("gc stats:");
+        println!("{:>12}  collections", self.collections);
+        println!("{:>12}  > minor", self.minor_collections);
+        println!(
+            "{:>12.3}  last pause ms",
+            self.last_pause.as_secs_f64() * 1000f64
+        );
+        println!(
+            "{:>12.3}  max pause ms",
+            self.max_pause.as_secs_f64() * 1000f64
+        );
+        println!(
+            "{:>12.3}  total pause ms",
+            self.total_pause.as_secs_f64() * 1000f64
+        );
+        println!("{:>12}  threshold", self.threshold);
+        println!("{:>12}  bytes", self.bytes);
+        println!("{:>12}  objects", self.objects);
+        for (kind, amount) in [
+            ("instance", self.instances),
+            ("list", self.lists),
+            ("string", self.strings),
+            ("fn", self.fns),
+            ("native", self.natives),
+            ("unit", self.units),
+            ("any", self.any),
+            ("type", self.types),
+            ("trait", self.traits),
+            ("bigint", self.bigints),
+        ] {
+            println!("{amount:>12}  > {kind}");
+        }
+    }
+}
+
 /// Vm settings,
-/// contains gc_threshold, gc_debug
-#[derive(Debug)]
+/// contains gc_threshold, gc_mode, gc_debug, max_call_depth, limits, permissions
+#[derive(Debug, Clone)]
 pub struct VmSettings {
     gc_threshold: usize,
     gc_threshold_grow_factor: usize,
+    gc_mode: GcMode,
     gc_debug: bool,
+    max_call_depth: usize,
+    limits: VmLimits,
+    permissions: Permissions,
 }
 /// Vm settings implementation
 impl VmSettings {
-    pub fn new(gc_threshold: usize, gc_threshold_grow_factor: usize, gc_debug: bool) -> Self {
+    pub fn new(
+        gc_threshold: usize,
+        gc_threshold_grow_factor: usize,
+        gc_mode: GcMode,
+        gc_debug: bool,
+        max_call_depth: usize,
+        limits: VmLimits,
+        permissions: Permissions,
+    ) -> Self {
         Self {
             gc_threshold,
             gc_threshold_grow_factor,
+            gc_mode,
             gc_debug,
+            max_call_depth,
+            limits,
+            permissions,
         }
     }
 }
 
+/// Module loader
+///
+/// compiles module by path, resolved relative to
+/// address, when `definitions` is true, leaves
+/// only definitions of module, like import does
+///
+pub type Loader = unsafe fn(&Address, &str, bool) -> Chunk;
+
+/// Ops amount between timeout checks
+const TIMEOUT_CHECK_INTERVAL: u64 = 1024;
+
 /// Virtual machine
 ///
 /// Vm that runs opcodes 🤔
@@ -37,19 +179,38 @@ impl VmSettings {
 #[derive(Debug)]
 pub struct VM {
     pub globals: *mut Table,
-    types: *mut Table,
+    pub types: *mut Table,
     pub units: *mut Table,
-    traits: *mut Table,
+    pub traits: *mut Table,
     pub natives: *mut Table,
     pub gc: *mut GC,
     settings: VmSettings,
     pub stack: Vec<Value>,
+    pub profiler: Option<Profiler>,
+    pub coverage: Option<Coverage>,
+    pub debugger: Option<Debugger>,
+    pub loader: Option<Loader>,
+    generators: Vec<*mut Generator>,
+    pub(crate) scheduler: Scheduler,
+    reactor: Option<Reactor>,
+    host_stack: HostStack,
+    op_addr: *const Address,
+    call_depth: usize,
+    instructions: u64,
+    deadline: Option<Instant>,
+    finalizing: bool,
 }
 /// Vm implementation
 #[allow(non_upper_case_globals)]
 #[allow(unused_qualifications)]
 impl VM {
     /// New vm
+    ///
+    /// # Safety
+    ///
+    /// vm tables and values are raw allocated,
+    /// they are freed once by `cleanup`
+    ///
     pub unsafe fn new(settings: VmSettings) -> VM {
         // vm
         let mut vm = VM {
@@ -58,8 +219,29 @@ impl VM {
             units: memory::alloc_value(Table::new()),
             traits: memory::alloc_value(Table::new()),
             natives: memory::alloc_value(Table::new()),
-            gc: memory::alloc_value(GC::new(settings.gc_debug)),
+            gc: memory::alloc_value(GC::new(
+                settings.gc_debug,
+                settings.gc_mode,
+                settings.limits.max_objects,
+                settings.limits.max_bytes,
+            )),
             stack: Vec::new(),
+            profiler: None,
+            coverage: None,
+            debugger: None,
+            loader: None,
+            generators: Vec::new(),
+            scheduler: Scheduler::default(),
+            reactor: None,
+            host_stack: HostStack::new(),
+            op_addr: std::ptr::null(),
+            call_depth: 0,
+            instructions: 0,
+            deadline: settings
+                .limits
+                .timeout
+                .map(|timeout| Instant::now() + timeout),
+            finalizing: false,
             settings,
         };
         // natives
@@ -71,10 +253,125 @@ impl VM {
     }
 
     /// Push value to vm stack
+    ///
+    /// # Safety
+    ///
+    /// `value` should be an alive value of this vm
+    ///
     pub unsafe fn push(&mut self, value: Value) {
+        if let Some(max) = self.settings.limits.max_stack
+            && self.stack.len() >= max
+        {
+            Self::limit_exceeded(&self.op_address(), "stack size", max, "max-stack");
+        }
         self.stack.push(value);
     }
 
+    /// Gc statistics snapshot
+    ///
+    /// # Safety
+    ///
+    /// vm shouldn't be cleaned up
+    ///
+    pub unsafe fn gc_stats(&self) -> GcStats {
+        (*self.gc).stats(self.settings.gc_threshold)
+    }
+
+    /// Writes heap snapshot json, walking
+    /// gc roots with current `table`
+    ///
+    /// # Safety
+    ///
+    /// vm shouldn't be cleaned up
+    ///
+    pub unsafe fn write_heap_snapshot(
+        &self,
+        table: *mut Table,
+        path: &Path,
+    ) -> std::io::Result<()> {
+        snapshot::write_snapshot(self, table, path)
+    }
+
+    /// Vm permissions
+    pub fn permissions(&self) -> &Permissions {
+        &self.settings.permissions
+    }
+
+    /// Settings and timeout deadline of vm,
+    /// vm of spawned thread is created with them
+    pub(crate) fn thread_settings(&self) -> (VmSettings, Option<Instant>) {
+        (self.settings.clone(), self.deadline)
+    }
+
+    /// New vm of spawned thread, it shares
+    /// timeout deadline with spawning vm
+    pub(crate) unsafe fn for_thread(
+        settings: VmSettings,
+        deadline: Option<Instant>,
+        loader: Option<Loader>,
+    ) -> VM {
+        let mut vm = VM::new(settings);
+        vm.deadline = deadline;
+        vm.loader = loader;
+        vm
+    }
+
+    /// Reactor of vm, it's created with first io
+    pub(crate) fn reactor(&mut self, addr: &Address) -> &mut Reactor {
+        if self.reactor.is_none() {
+            match Reactor::new() {
+                Ok(reactor) => self.reactor = Some(reactor),
+                Err(err) => error!(Error::own_text(
+                    addr.clone(),
+                    format!("failed to create io reactor: {err}"),
+                    "check system resources."
+                )),
+            }
+        }
+        self.reactor.as_mut().unwrap()
+    }
+
+    /// Time left until timeout deadline
+    pub(crate) fn time_left(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Raises limit error, if
+    /// timeout deadline is passed
+    pub(crate) fn check_timeout(&self, addr: &Address) {
+        if let Some(deadline) = self.deadline
+            && Instant::now() > deadline
+        {
+            let timeout = self.settings.limits.timeout.unwrap_or_default();
+            Self::limit_exceeded(
+                addr,
+                "timeout",
+                format!("{}ms", timeout.as_millis()),
+                "timeout",
+            );
+        }
+    }
+
+    /// Raises limit error
+    pub(crate) fn limit_exceeded(addr: &Address, limit: &str, max: impl Display, flag: &str) -> ! {
+        error!(Error::limit(
+            addr.clone(),
+            format!("{limit} exceeded {max}."),
+            format!("script exceeded execution limit, raise it with --{flag}.")
+        ));
+    }
+
+    /// Address of running opcode, used by
+    /// errors of ops, that don't take address
+    fn op_address(&self) -> Address {
+        if self.op_addr.is_null() {
+            Address::unknown()
+        } else {
+            unsafe { (*self.op_addr).clone() }
+        }
+    }
+
     /// Pop value from vm stack
     pub fn pop(&mut self, address: &Address) -> Value {
         if self.stack.is_empty() {
@@ -87,20 +384,43 @@ impl VM {
         self.stack.pop().unwrap()
     }
 
+    /// Int value, wide int is boxed into gc arena,
+    /// and should be rooted before next allocation
+    ///
+    /// # Safety
+    ///
+    /// vm shouldn't be cleaned up
+    ///
+    pub unsafe fn int(&mut self, value: i64) -> Value {
+        Value::Int(value, (*self.gc).wide())
+    }
+
+    /// Pushes int to vm stack, full gc is invoked,
+    /// when wide int cells amount exceeds threshold
+    unsafe fn push_int(&mut self, int: i64, table: *mut Table) {
+        let value = self.int(int);
+        self.push(value);
+        if value.wide().is_some() && (*self.gc).exceeds_wide() {
+            self.gc_invoke(table);
+        }
+    }
+
     /// Cleanup vm
     ///
     /// Frees all values and
     /// all tables themselves
     ///
+    /// # Safety
+    ///
+    /// should be called once, values and
+    /// tables of vm are not used after
+    ///
     pub unsafe fn cleanup(&mut self) {
-        // cleanup gc values
+        // cleanup gc values,
+        // including types and traits
         (*self.gc).cleanup();
         // freeing gc
         memory::free_value(self.gc);
-        // freeing types table fields
-        (*self.types).free_fields();
-        // freeing traits table fields
-        (*self.traits).free_fields();
         // freeing tables themselves
         memory::free_value(self.traits);
         memory::free_value(self.types);
@@ -110,18 +430,72 @@ impl VM {
     }
 
     /// Invoke garbage collector
+    ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm, values
+    /// unreachable from roots and `table` are freed
+    ///
     pub unsafe fn gc_invoke(&mut self, table: *mut Table) {
         (*self.gc).collect_garbage(self, table);
     }
 
+    /// Write barrier of table, should be called
+    /// after value is written in table
+    ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
+    pub unsafe fn gc_barrier(&mut self, table: *mut Table, value: Value) {
+        (*self.gc).barrier_table(table, value);
+    }
+
+    /// Write barrier of list, fn or type, should be
+    /// called after value is written in container
+    ///
+    /// # Safety
+    ///
+    /// `container` should be an alive value of vm
+    ///
+    pub unsafe fn gc_barrier_value(&mut self, container: Value, value: Value) {
+        (*self.gc).barrier_value(container, value);
+    }
+
+    /// Write barrier of generator, should be
+    /// called before its body is started
+    ///
+    /// # Safety
+    ///
+    /// `generator` should be an alive value of vm
+    ///
+    pub unsafe fn gc_barrier_coroutine(&mut self, generator: Value) {
+        (*self.gc).barrier_coroutine(generator);
+    }
+
     /// Registers object in gc
     ///
     /// if gc objects amount > gc_threshold
     /// | gc invokes
     /// | gc_threshold multiplies by 2
+    /// if wide int cells amount > wide threshold
+    /// | gc invokes
+    /// if gc is generational and young objects
+    /// amount > nursery
+    /// | minor gc invokes
+    ///
+    /// # Safety
+    ///
+    /// `value` should be rooted before registering,
+    /// `table` should be an alive table of vm
+    ///
     pub unsafe fn gc_register(&mut self, value: Value, table: *mut Table) {
-        // adding object
-        (*self.gc).add_object(value);
+        // adding object, garbage is collected
+        // before raising memory limits error
+        if (*self.gc).add_object(value) {
+            self.gc_invoke(table);
+            (*self.gc).check_limits(&self.op_address());
+        }
         // checking gc threshold
         if (*self.gc).objects_amount() > self.settings.gc_threshold {
             // calling gc
@@ -130,19 +504,189 @@ impl VM {
             self.settings.gc_threshold =
                 (*self.gc).objects_amount() * self.settings.gc_threshold_grow_factor;
         }
+        // checking wide ints
+        else if (*self.gc).exceeds_wide() {
+            self.gc_invoke(table);
+        }
+        // checking nursery
+        else if let GcMode::Generational { nursery } = self.settings.gc_mode
+            && (*self.gc).young_amount() > nursery
+        {
+            (*self.gc).collect_young(self, table);
+        }
     }
 
     /// Guard values from being freed by gc,
     /// by pushing to guard stack
+    ///
+    /// # Safety
+    ///
+    /// `value` should be an alive value of vm,
+    /// guards are popped in reverse order
+    ///
     pub unsafe fn gc_guard(&mut self, value: Value) {
         (*self.gc).push_guard(value);
     }
 
     /// Unguarding value from being freed by gc
+    ///
+    /// # Safety
+    ///
+    /// should be paired with `gc_guard`
+    ///
     pub unsafe fn gc_unguard(&mut self) {
         (*self.gc).pop_guard();
     }
 
+    /// Runs pending finalizers
+    ///
+    /// calls `drop` of instances, queued by gc
+    /// before freeing them. finalizers are not
+    /// nested: instances, queued during `drop`,
+    /// are dropped by the same loop
+    ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
+    pub unsafe fn run_finalizers(
+        &mut self,
+        addr: &Address,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        if self.finalizing {
+            return Ok(());
+        }
+        self.finalizing = true;
+        let mut result = Ok(());
+        while let Some(value) = (*self.gc).pop_finalizer() {
+            if let Unboxed::Instance(instance) = value.unbox() {
+                gc_guard!(self.gc, value);
+                result = self.call_drop(addr, instance, table);
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+        self.finalizing = false;
+        result
+    }
+
+    /// Runs finalizers of all alive
+    /// instances, before vm is cleaned up
+    ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
+    pub unsafe fn finalize_all(&mut self, table: *mut Table) -> Result<(), ControlFlow> {
+        (*self.gc).queue_all_finalizers();
+        self.run_finalizers(&Address::unknown(), table)
+    }
+
+    /// Calls `drop` of instance
+    unsafe fn call_drop(
+        &mut self,
+        addr: &Address,
+        instance: *mut Instance,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // finding callable
+        let callable = Self::member(addr, instance, "drop");
+        match callable.unbox() {
+            Unboxed::Fn(function) => {
+                if !(*function).params.is_empty() {
+                    error!(Error::own_text(
+                        addr.clone(),
+                        format!("drop takes {} params", (*function).params.len()),
+                        "drop should take 0 params."
+                    ));
+                }
+            }
+            _ => {
+                error!(Error::new(
+                    addr.clone(),
+                    "drop is not a fn.",
+                    "drop should be fn."
+                ));
+            }
+        }
+
+        // calling
+        let receiver = Some(FnOwner::Instance(instance));
+        self.call(
+            addr,
+            "drop",
+            callable,
+            receiver,
+            &Chunk::new(vec![]),
+            &[],
+            table,
+            false,
+        )
+    }
+
+    /// Gets instance of value, if it's
+    /// type impls trait by `trait_name`
+    unsafe fn impl_of(value: Value, trait_name: &str) -> Option<*mut Instance> {
+        match value.unbox() {
+            Unboxed::Instance(instance) if (*(*instance).t).impls_named(trait_name) => {
+                Some(instance)
+            }
+            _ => None,
+        }
+    }
+
+    /// Calls operator fn of instance with given
+    /// args, returns result of the call
+    ///
+    /// safety guaranteed by guarding instance
+    /// and args, while fn is called
+    ///
+    unsafe fn call_operator(
+        &mut self,
+        addr: &Address,
+        instance: *mut Instance,
+        name: &str,
+        args: &[Value],
+        table: *mut Table,
+    ) -> Result<Value, ControlFlow> {
+        // guarding operands
+        gc_guard!(self.gc, Value::Instance(instance));
+        let _guards: Vec<GcGuard> = args.iter().map(|arg| GcGuard::new(self.gc, *arg)).collect();
+
+        // args chunk
+        let args = Chunk::new(
+            args.iter()
+                .map(|arg| Opcode::Push {
+                    addr: addr.clone(),
+                    value: OpcodeValue::Raw(*arg),
+                })
+                .collect(),
+        );
+
+        // calling
+        let callable = Self::member(addr, instance, name);
+        let receiver = Some(FnOwner::Instance(instance));
+        self.call(addr, name, callable, receiver, &args, &[], table, true)?;
+        Ok(self.pop(addr))
+    }
+
+    /// Displays instance, that impls Display,
+    /// with it's `to_string`, other values
+    /// are returned as they are
+    unsafe fn display(
+        &mut self,
+        addr: &Address,
+        value: Value,
+        table: *mut Table,
+    ) -> Result<Value, ControlFlow> {
+        match Self::impl_of(value, "Display") {
+            Some(instance) => self.call_operator(addr, instance, "to_string", &[], table),
+            None => Ok(value),
+        }
+    }
+
     /// Opcode: Push value to vm stack
     ///
     /// if value is a reference type except
@@ -153,6 +697,10 @@ impl VM {
     /// safety guaranteed by pushing value to stack
     /// before registering in gc.
     ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
     pub unsafe fn op_push(
         &mut self,
         value: OpcodeValue,
@@ -162,7 +710,7 @@ impl VM {
         match value {
             // primitives
             OpcodeValue::Int(int) => {
-                self.push(Value::Int(int));
+                self.push_int(int, table);
             }
             OpcodeValue::Float(float) => {
                 self.push(Value::Float(float));
@@ -181,14 +729,17 @@ impl VM {
             }
             // raw
             OpcodeValue::Raw(raw) => {
-                match raw {
-                    Value::Instance(_)
-                    | Value::Fn(_)
-                    | Value::Native(_)
-                    | Value::String(_)
-                    | Value::Unit(_)
-                    | Value::List(_)
-                    | Value::Any(_) => {
+                match raw.unbox() {
+                    Unboxed::Instance(_)
+                    | Unboxed::Fn(_)
+                    | Unboxed::Native(_)
+                    | Unboxed::String(_)
+                    | Unboxed::Unit(_)
+                    | Unboxed::List(_)
+                    | Unboxed::Any(_)
+                    | Unboxed::BigInt(_)
+                    | Unboxed::Enum(_)
+                    | Unboxed::Variant(_) => {
                         // push
                         self.push(raw);
                         // then register
@@ -215,6 +766,35 @@ impl VM {
         let operand_a = self.pop(address);
         let operand_b = self.pop(address);
 
+        // operator traits, dispatched by left operand
+        let operator = match op {
+            "+" => Some(("Add", "add")),
+            "-" => Some(("Sub", "sub")),
+            "*" => Some(("Mul", "mul")),
+            "/" => Some(("Div", "div")),
+            _ => None,
+        };
+        if let Some((trait_name, name)) = operator
+            && let Some(instance) = Self::impl_of(operand_a, trait_name)
+        {
+            let result = self.call_operator(address, instance, name, &[operand_b], table)?;
+            self.push(result);
+            return Ok(());
+        }
+
+        // strings are concatenated with displayed instances
+        let (operand_a, operand_b) = match (operand_a.unbox(), operand_b.unbox()) {
+            (Unboxed::String(_), Unboxed::Instance(_)) if op == "+" => {
+                gc_guard!(self.gc, operand_a);
+                (operand_a, self.display(address, operand_b, table)?)
+            }
+            (Unboxed::Instance(_), Unboxed::String(_)) if op == "+" => {
+                gc_guard!(self.gc, operand_b);
+                (self.display(address, operand_a, table)?, operand_b)
+            }
+            _ => (operand_a, operand_b),
+        };
+
         // error generators
         let invalid_op_error = || {
             error!(Error::own_text(
@@ -238,17 +818,36 @@ impl VM {
             Value::String(memory::alloc_value(string))
         };
 
+        // checked int result
+        let checked = |result: Option<i64>| match result {
+            Some(value) => value,
+            None => error!(Error::own_text(
+                address.clone(),
+                format!("integer overflow in {operand_a:?} {op} {operand_b:?}"),
+                "you can use convert.to_bigint for arbitrary precision."
+            )),
+        };
+
+        // big integers, string concat is handled below
+        if (matches!(operand_a.unbox(), Unboxed::BigInt(_))
+            || matches!(operand_b.unbox(), Unboxed::BigInt(_)))
+            && !matches!(operand_a.unbox(), Unboxed::String(_))
+            && !matches!(operand_b.unbox(), Unboxed::String(_))
+        {
+            return self.op_big_binary(address, op, operand_a, operand_b, table);
+        }
+
         // binary operators
         match op {
-            "+" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            "+" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Float(a + b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Float(a + (b as f64)));
                     }
-                    Value::String(b) => {
+                    Unboxed::String(b) => {
                         let string = concat(String::with_capacity((*b).len()), &a.to_string(), &*b);
                         self.push(string);
                         self.gc_register(string, table);
@@ -257,14 +856,14 @@ impl VM {
                         invalid_op_error();
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Float((a as f64) + b));
                     }
-                    Value::Int(b) => {
-                        self.push(Value::Int(a + b));
+                    Unboxed::Int(b) => {
+                        self.push_int(checked(a.checked_add(b)), table);
                     }
-                    Value::String(b) => {
+                    Unboxed::String(b) => {
                         let string = concat(String::with_capacity((*b).len()), &a.to_string(), &*b);
                         self.push(string);
                         self.gc_register(string, table);
@@ -273,8 +872,8 @@ impl VM {
                         invalid_op_error();
                     }
                 },
-                Value::String(a) => match operand_b {
-                    Value::String(b) => {
+                Unboxed::String(a) => match operand_b.unbox() {
+                    Unboxed::String(b) => {
                         let string =
                             concat(String::with_capacity((*a).len() + (*b).len()), &*a, &*b);
                         self.push(string);
@@ -291,7 +890,7 @@ impl VM {
                     }
                 },
                 _ => {
-                    if let Value::String(b) = operand_b {
+                    if let Unboxed::String(b) = operand_b.unbox() {
                         let string = concat(
                             String::with_capacity((*b).len()),
                             &operand_a.to_string(),
@@ -305,24 +904,24 @@ impl VM {
                     }
                 }
             },
-            "-" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            "-" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Float(a - b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Float(a - (b as f64)));
                     }
                     _ => {
                         invalid_op_error();
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Float((a as f64) - b));
                     }
-                    Value::Int(b) => {
-                        self.push(Value::Int(a - b));
+                    Unboxed::Int(b) => {
+                        self.push_int(checked(a.checked_sub(b)), table);
                     }
                     _ => {
                         invalid_op_error();
@@ -332,24 +931,24 @@ impl VM {
                     invalid_op_error();
                 }
             },
-            "*" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            "*" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Float(a * b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Float(a * (b as f64)));
                     }
                     _ => {
                         invalid_op_error();
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Float((a as f64) * b));
                     }
-                    Value::Int(b) => {
-                        self.push(Value::Int(a * b));
+                    Unboxed::Int(b) => {
+                        self.push_int(checked(a.checked_mul(b)), table);
                     }
                     _ => {
                         invalid_op_error();
@@ -360,10 +959,10 @@ impl VM {
                 }
             },
             "/" => {
-                match operand_a {
-                    Value::Float(a) => {
-                        match operand_b {
-                            Value::Float(b) => {
+                match operand_a.unbox() {
+                    Unboxed::Float(a) => {
+                        match operand_b.unbox() {
+                            Unboxed::Float(b) => {
                                 // проверка на деление на 0
                                 if b == 0f64 {
                                     division_error();
@@ -371,7 +970,7 @@ impl VM {
                                 // деление
                                 self.push(Value::Float(a / b));
                             }
-                            Value::Int(b) => {
+                            Unboxed::Int(b) => {
                                 // проверка на деление на 0
                                 if b == 0 {
                                     division_error();
@@ -384,9 +983,9 @@ impl VM {
                             }
                         }
                     }
-                    Value::Int(a) => {
-                        match operand_b {
-                            Value::Float(b) => {
+                    Unboxed::Int(a) => {
+                        match operand_b.unbox() {
+                            Unboxed::Float(b) => {
                                 // checking division by zero
                                 if b == 0f64 {
                                     division_error();
@@ -394,16 +993,19 @@ impl VM {
                                 // dividing
                                 self.push(Value::Float((a as f64) / b));
                             }
-                            Value::Int(b) => {
+                            Unboxed::Int(b) => {
                                 // checking division by zero
                                 if b == 0 {
                                     division_error();
                                 }
-                                // dividing
-                                if a % b == 0 {
-                                    self.push(Value::Int(a / b));
-                                } else {
-                                    self.push(Value::Float(a as f64 / b as f64))
+                                // dividing, `i64::MIN / -1` overflows
+                                // in both remainder and quotient
+                                match a.checked_rem(b) {
+                                    Some(0) => self.push_int(checked(a.checked_div(b)), table),
+                                    Some(_) => self.push(Value::Float(a as f64 / b as f64)),
+                                    None => {
+                                        checked(None);
+                                    }
                                 }
                             }
                             _ => {
@@ -416,24 +1018,61 @@ impl VM {
                     }
                 }
             }
-            "%" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            "%" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Float(a % b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Float(a % (b as f64)));
                     }
                     _ => {
                         invalid_op_error();
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Float((a as f64) % b));
                     }
-                    Value::Int(b) => {
-                        self.push(Value::Int(a % b));
+                    Unboxed::Int(b) => {
+                        // checking division by zero
+                        if b == 0 {
+                            division_error();
+                        }
+                        self.push_int(checked(a.checked_rem(b)), table);
+                    }
+                    _ => {
+                        invalid_op_error();
+                    }
+                },
+                _ => {
+                    invalid_op_error();
+                }
+            },
+            "**" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
+                        self.push(Value::Float(a.powf(b)));
+                    }
+                    Unboxed::Int(b) => {
+                        self.push(Value::Float(a.powf(b as f64)));
+                    }
+                    _ => {
+                        invalid_op_error();
+                    }
+                },
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
+                        self.push(Value::Float((a as f64).powf(b)));
+                    }
+                    Unboxed::Int(b) => {
+                        // negative exponent gives float
+                        if b < 0 {
+                            self.push(Value::Float((a as f64).powf(b as f64)));
+                        } else {
+                            let exp = u32::try_from(b).ok();
+                            self.push_int(checked(exp.and_then(|exp| a.checked_pow(exp))), table);
+                        }
                     }
                     _ => {
                         invalid_op_error();
@@ -443,10 +1082,10 @@ impl VM {
                     invalid_op_error();
                 }
             },
-            "&" => match operand_a {
-                Value::Int(a) => match operand_b {
-                    Value::Int(b) => {
-                        self.push(Value::Int(a & b));
+            "&" => match operand_a.unbox() {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Int(b) => {
+                        self.push_int(a & b, table);
                     }
                     _ => {
                         invalid_op_error();
@@ -456,10 +1095,10 @@ impl VM {
                     invalid_op_error();
                 }
             },
-            "|" => match operand_a {
-                Value::Int(a) => match operand_b {
-                    Value::Int(b) => {
-                        self.push(Value::Int(a | b));
+            "|" => match operand_a.unbox() {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Int(b) => {
+                        self.push_int(a | b, table);
                     }
                     _ => {
                         invalid_op_error();
@@ -469,10 +1108,10 @@ impl VM {
                     invalid_op_error();
                 }
             },
-            "^" => match operand_a {
-                Value::Int(a) => match operand_b {
-                    Value::Int(b) => {
-                        self.push(Value::Int(a ^ b));
+            "^" => match operand_a.unbox() {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Int(b) => {
+                        self.push_int(a ^ b, table);
                     }
                     _ => {
                         invalid_op_error();
@@ -489,26 +1128,157 @@ impl VM {
         Ok(())
     }
 
-    /// Opcode: Negate operation
-    unsafe fn op_negate(&mut self, address: &Address) -> Result<(), ControlFlow> {
-        // operand
-        let operand = self.pop(address);
-        // negate
-        match operand {
-            Value::Float(a) => {
-                self.push(Value::Float(-a));
+    /// Binary operation with big integer operand
+    ///
+    /// int operand is promoted to big integer,
+    /// float operand makes operation a float one
+    ///
+    unsafe fn op_big_binary(
+        &mut self,
+        address: &Address,
+        op: &str,
+        operand_a: Value,
+        operand_b: Value,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // error generators
+        let invalid_op_error = || {
+            error!(Error::own_text(
+                address.clone(),
+                format!("could not use '{op}' with {operand_a:?} and {operand_b:?}"),
+                "check your code."
+            ));
+        };
+        let division_error = || {
+            error!(Error::new(
+                address.clone(),
+                "division by zero.",
+                "undefined operation."
+            ));
+        };
+
+        // float operation
+        let (Some(a), Some(b)) = (bigint_of(operand_a), bigint_of(operand_b)) else {
+            let (Some(a), Some(b)) = (float_of(operand_a), float_of(operand_b)) else {
+                invalid_op_error();
+                return Ok(());
+            };
+            let result = match op {
+                "+" => a + b,
+                "-" => a - b,
+                "*" => a * b,
+                "/" => {
+                    // checking division by zero
+                    if b == 0f64 {
+                        division_error();
+                    }
+                    a / b
+                }
+                "%" => a % b,
+                "**" => a.powf(b),
+                _ => {
+                    invalid_op_error();
+                    return Ok(());
+                }
+            };
+            self.push(Value::Float(result));
+            return Ok(());
+        };
+
+        // big integer operation
+        let result = match op {
+            "+" => a + b,
+            "-" => a - b,
+            "*" => a * b,
+            "/" => {
+                // checking division by zero
+                if b.is_zero() {
+                    division_error();
+                }
+                // dividing, inexact result is an error,
+                // since float would lose precision
+                if !(&a % &b).is_zero() {
+                    error!(Error::own_text(
+                        address.clone(),
+                        format!("inexact big integer division {a} / {b}."),
+                        "subtract remainder first, or use convert.to_float."
+                    ));
+                }
+                a / b
             }
-            Value::Int(a) => {
-                self.push(Value::Int(-a));
+            "%" => {
+                // checking division by zero
+                if b.is_zero() {
+                    division_error();
+                }
+                a % b
             }
-            _ => {
-                error!(Error::own_text(
+            "**" => match b.to_u32() {
+                Some(exp) => a.pow(exp),
+                // negative exponent gives float
+                None if b.is_negative() => {
+                    self.push(Value::Float(
+                        float_of(operand_a)
+                            .unwrap()
+                            .powf(float_of(operand_b).unwrap()),
+                    ));
+                    return Ok(());
+                }
+                None => error!(Error::own_text(
                     address.clone(),
-                    format!("could not use 'negate' for {operand:?}"),
-                    "check your code."
-                ));
+                    format!("exponent {b} is too large."),
+                    "exponent should fit in u32."
+                )),
+            },
+            "&" => a & b,
+            "|" => a | b,
+            "^" => a ^ b,
+            _ => {
+                panic!("operator = {op} is not found.")
             }
-        }
+        };
+
+        // pushing and registering result
+        let value = Value::BigInt(memory::alloc_value(result));
+        self.push(value);
+        self.gc_register(value, table);
+        Ok(())
+    }
+
+    /// Opcode: Negate operation
+    unsafe fn op_negate(
+        &mut self,
+        address: &Address,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // operand
+        let operand = self.pop(address);
+        // negate
+        match operand.unbox() {
+            Unboxed::Float(a) => {
+                self.push(Value::Float(-a));
+            }
+            Unboxed::Int(a) => match a.checked_neg() {
+                Some(a) => self.push_int(a, table),
+                None => error!(Error::own_text(
+                    address.clone(),
+                    format!("integer overflow in negation of {a}"),
+                    "you can use convert.to_bigint for arbitrary precision."
+                )),
+            },
+            Unboxed::BigInt(a) => {
+                let value = Value::BigInt(memory::alloc_value(-(*a).clone()));
+                self.push(value);
+                self.gc_register(value, table);
+            }
+            _ => {
+                error!(Error::own_text(
+                    address.clone(),
+                    format!("could not use 'negate' for {operand:?}"),
+                    "check your code."
+                ));
+            }
+        }
         Ok(())
     }
 
@@ -517,8 +1287,8 @@ impl VM {
         // operand
         let operand = self.pop(address);
         // bang
-        match operand {
-            Value::Bool(b) => {
+        match operand.unbox() {
+            Unboxed::Bool(b) => {
                 self.push(Value::Bool(!b));
             }
             _ => {
@@ -533,10 +1303,57 @@ impl VM {
     }
 
     /// Opcode: Conditional operation
-    unsafe fn op_conditional(&mut self, address: &Address, op: &str) -> Result<(), ControlFlow> {
+    unsafe fn op_conditional(
+        &mut self,
+        address: &Address,
+        op: &str,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
         // operands
         let operand_a = self.pop(address);
         let operand_b = self.pop(address);
+
+        // operator traits, dispatched by left operand,
+        // instance is never equal to null
+        match op {
+            "==" if !matches!(operand_b.unbox(), Unboxed::Null) => {
+                if let Some(instance) = Self::impl_of(operand_a, "Eq") {
+                    let result =
+                        self.call_operator(address, instance, "eq", &[operand_b], table)?;
+                    if !matches!(result.unbox(), Unboxed::Bool(_)) {
+                        error!(Error::own_text(
+                            address.clone(),
+                            format!("eq returned {result:?}, instead of bool."),
+                            "eq should return bool."
+                        ));
+                    }
+                    self.push(result);
+                    return Ok(());
+                }
+            }
+            ">" | "<" | ">=" | "<=" => {
+                if let Some(instance) = Self::impl_of(operand_a, "Ord") {
+                    let result =
+                        self.call_operator(address, instance, "cmp", &[operand_b], table)?;
+                    let ordering = match result.unbox() {
+                        Unboxed::Int(ordering) => ordering.cmp(&0),
+                        _ => error!(Error::own_text(
+                            address.clone(),
+                            format!("cmp returned {result:?}, instead of int."),
+                            "cmp should return int, less, equal or greater than zero."
+                        )),
+                    };
+                    self.push(Value::Bool(match op {
+                        ">" => ordering == Ordering::Greater,
+                        "<" => ordering == Ordering::Less,
+                        ">=" => ordering != Ordering::Less,
+                        _ => ordering != Ordering::Greater,
+                    }));
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
         // error
         let invalid_op_error = || {
             Error::own_text(
@@ -545,33 +1362,57 @@ impl VM {
                 "check your code.",
             )
         };
+        // big integers
+        if op != "!="
+            && (matches!(operand_a.unbox(), Unboxed::BigInt(_))
+                || matches!(operand_b.unbox(), Unboxed::BigInt(_)))
+        {
+            let ordering = match (bigint_of(operand_a), bigint_of(operand_b)) {
+                (Some(a), Some(b)) => Some(a.cmp(&b)),
+                _ => match (float_of(operand_a), float_of(operand_b)) {
+                    (Some(a), Some(b)) => a.partial_cmp(&b),
+                    _ if op == "==" => None,
+                    _ => error!(invalid_op_error()),
+                },
+            };
+            let result = match op {
+                ">" => ordering == Some(Ordering::Greater),
+                "<" => ordering == Some(Ordering::Less),
+                ">=" => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                "<=" => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                "==" => ordering == Some(Ordering::Equal),
+                _ => panic!("operator {op} is not found. report this error to the developer."),
+            };
+            self.push(Value::Bool(result));
+            return Ok(());
+        }
         // conditional op
         match op {
-            ">" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            ">" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool(a > b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a > (b as f64)));
                     }
                     _ => {
                         error!(invalid_op_error());
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool((a as f64) > b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a > b));
                     }
                     _ => {
                         error!(invalid_op_error());
                     }
                 },
-                Value::String(a) => match operand_b {
-                    Value::String(b) => {
+                Unboxed::String(a) => match operand_b.unbox() {
+                    Unboxed::String(b) => {
                         self.push(Value::Bool(*a > *b));
                     }
                     _ => {
@@ -582,31 +1423,31 @@ impl VM {
                     error!(invalid_op_error());
                 }
             },
-            "<" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            "<" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool(a < b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a < (b as f64)));
                     }
                     _ => {
                         error!(invalid_op_error());
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool((a as f64) < b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a < b));
                     }
                     _ => {
                         error!(invalid_op_error());
                     }
                 },
-                Value::String(a) => match operand_b {
-                    Value::String(b) => {
+                Unboxed::String(a) => match operand_b.unbox() {
+                    Unboxed::String(b) => {
                         self.push(Value::Bool(*a < *b));
                     }
                     _ => {
@@ -617,31 +1458,31 @@ impl VM {
                     error!(invalid_op_error());
                 }
             },
-            ">=" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            ">=" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool(a >= b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a >= (b as f64)));
                     }
                     _ => {
                         error!(invalid_op_error());
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool((a as f64) >= b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a >= b));
                     }
                     _ => {
                         error!(invalid_op_error());
                     }
                 },
-                Value::String(a) => match operand_b {
-                    Value::String(b) => {
+                Unboxed::String(a) => match operand_b.unbox() {
+                    Unboxed::String(b) => {
                         self.push(Value::Bool(*a >= *b));
                     }
                     _ => {
@@ -652,31 +1493,31 @@ impl VM {
                     error!(invalid_op_error());
                 }
             },
-            "<=" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            "<=" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool(a <= b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a <= (b as f64)));
                     }
                     _ => {
                         error!(invalid_op_error());
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool((a as f64) <= b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a <= b));
                     }
                     _ => {
                         error!(invalid_op_error());
                     }
                 },
-                Value::String(a) => match operand_b {
-                    Value::String(b) => {
+                Unboxed::String(a) => match operand_b.unbox() {
+                    Unboxed::String(b) => {
                         self.push(Value::Bool(*a <= *b));
                     }
                     _ => {
@@ -687,81 +1528,96 @@ impl VM {
                     error!(invalid_op_error());
                 }
             },
-            "==" => match operand_a {
-                Value::Float(a) => match operand_b {
-                    Value::Float(b) => {
+            "==" => match operand_a.unbox() {
+                Unboxed::Float(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool(a == b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a == (b as f64)));
                     }
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::Int(a) => match operand_b {
-                    Value::Float(b) => {
+                Unboxed::Int(a) => match operand_b.unbox() {
+                    Unboxed::Float(b) => {
                         self.push(Value::Bool((a as f64) == b));
                     }
-                    Value::Int(b) => {
+                    Unboxed::Int(b) => {
                         self.push(Value::Bool(a == b));
                     }
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::Null => match operand_b {
-                    Value::Null => {
+                Unboxed::Null => match operand_b.unbox() {
+                    Unboxed::Null => {
                         self.push(Value::Bool(true));
                     }
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::Fn(f1) => match operand_b {
-                    Value::Fn(f2) => {
+                Unboxed::Fn(f1) => match operand_b.unbox() {
+                    Unboxed::Fn(f2) => {
                         self.push(Value::Bool(f1 == f2));
                     }
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::Bool(a) => match operand_b {
-                    Value::Bool(b) => {
+                Unboxed::Bool(a) => match operand_b.unbox() {
+                    Unboxed::Bool(b) => {
                         self.push(Value::Bool(a == b));
                     }
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::Instance(a) => match operand_b {
-                    Value::Instance(b) => {
+                Unboxed::Instance(a) => match operand_b.unbox() {
+                    Unboxed::Instance(b) => {
                         self.push(Value::Bool(a == b));
                     }
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::Type(a) => match operand_b {
-                    Value::Type(b) => self.push(Value::Bool(a == b)),
+                Unboxed::Type(a) => match operand_b.unbox() {
+                    Unboxed::Type(b) => self.push(Value::Bool(a == b)),
+                    _ => {
+                        self.push(Value::Bool(false));
+                    }
+                },
+                Unboxed::String(a) => match operand_b.unbox() {
+                    Unboxed::String(b) => self.push(Value::Bool(*a == *b)),
+                    _ => {
+                        self.push(Value::Bool(false));
+                    }
+                },
+                Unboxed::Native(a) => match operand_b.unbox() {
+                    Unboxed::Native(b) => self.push(Value::Bool(a == b)),
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::String(a) => match operand_b {
-                    Value::String(b) => self.push(Value::Bool(*a == *b)),
+                Unboxed::Trait(a) => match operand_b.unbox() {
+                    Unboxed::Trait(b) => self.push(Value::Bool(a == b)),
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::Native(a) => match operand_b {
-                    Value::Native(b) => self.push(Value::Bool(a == b)),
+                Unboxed::Enum(a) => match operand_b.unbox() {
+                    Unboxed::Enum(b) => self.push(Value::Bool(a == b)),
                     _ => {
                         self.push(Value::Bool(false));
                     }
                 },
-                Value::Trait(a) => match operand_b {
-                    Value::Trait(b) => self.push(Value::Bool(a == b)),
+                Unboxed::Variant(a) => match operand_b.unbox() {
+                    Unboxed::Variant(b) => {
+                        let equal = self.variants_equal(address, a, b, table)?;
+                        self.push(Value::Bool(equal));
+                    }
                     _ => {
                         self.push(Value::Bool(false));
                     }
@@ -775,7 +1631,7 @@ impl VM {
                 self.push(operand_b);
                 self.push(operand_a);
                 // running equals cond op
-                self.op_conditional(address, "==")?;
+                self.op_conditional(address, "==", table)?;
                 // running bang
                 self.op_bang(address)?;
             }
@@ -797,8 +1653,8 @@ impl VM {
     ) -> Result<(), ControlFlow> {
         // expect bool
         fn expect_bool(value: Value, error: Error) -> bool {
-            match value {
-                Value::Bool(b) => b,
+            match value.unbox() {
+                Unboxed::Bool(b) => b,
                 _ => {
                     error!(error)
                 }
@@ -898,7 +1754,7 @@ impl VM {
         let bool = self.pop(addr);
 
         // checking condition returned true
-        if let Value::Bool(b) = bool {
+        if let Unboxed::Bool(b) = bool.unbox() {
             if b {
                 self.run(body, table)?
             } else if let Option::Some(else_if) = elif {
@@ -915,6 +1771,263 @@ impl VM {
         Ok(())
     }
 
+    /// Opcode: Match
+    ///
+    /// evaluates matchable once, then checks
+    /// arms in order. every arm gets its own table,
+    /// where pattern bindings are defined
+    ///
+    unsafe fn op_match(
+        &mut self,
+        addr: &Address,
+        matchable: &Chunk,
+        arms: &Vec<MatchArm>,
+        default: &Chunk,
+        root: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // matchable value
+        self.run(matchable, root)?;
+        let value = self.pop(addr);
+        gc_guard!(self.gc, value);
+
+        // checking arms
+        for arm in arms {
+            // arm table
+            let table = memory::alloc_value(Table::new());
+            (*table).set_root(root);
+
+            // defer arm table free
+            defer! {
+                try_free_table(table);
+            }
+
+            // matching pattern
+            if !self.match_pattern(addr, &arm.pattern, value, table)? {
+                continue;
+            }
+
+            // checking guard
+            if let Some(guard) = &arm.guard {
+                self.run(guard, table)?;
+                let provided = self.pop(addr);
+                match provided.unbox() {
+                    Unboxed::Bool(true) => {}
+                    Unboxed::Bool(false) => continue,
+                    _ => error!(Error::own_text(
+                        addr.clone(),
+                        format!("guard provided not a bool: {provided:?}"),
+                        "guard should provide a bool."
+                    )),
+                }
+            }
+
+            // running body
+            return self.run(&arm.body, table);
+        }
+
+        // default table
+        let table = memory::alloc_value(Table::new());
+        (*table).set_root(root);
+
+        // defer default table free
+        defer! {
+            try_free_table(table);
+        }
+
+        // running default
+        self.run(default, table)
+    }
+
+    /// Checks value matches pattern,
+    /// defining bindings in table
+    unsafe fn match_pattern(
+        &mut self,
+        addr: &Address,
+        pattern: &Pattern,
+        value: Value,
+        table: *mut Table,
+    ) -> Result<bool, ControlFlow> {
+        match pattern {
+            Pattern::Wildcard => Ok(true),
+            Pattern::Bind { name } => {
+                (*table).fields.insert(name.clone(), value);
+                self.gc_barrier(table, value);
+                Ok(true)
+            }
+            Pattern::Value { value: expected } => {
+                self.run(expected, table)?;
+                self.push(value);
+                self.op_conditional(addr, "==", table)?;
+                Ok(matches!(self.pop(addr).unbox(), Unboxed::Bool(true)))
+            }
+            Pattern::Range { from, to } => {
+                // only numbers are in range
+                if !matches!(
+                    value.unbox(),
+                    Unboxed::Int(_) | Unboxed::Float(_) | Unboxed::BigInt(_)
+                ) {
+                    return Ok(false);
+                }
+                // value >= from
+                self.run(from, table)?;
+                self.push(value);
+                self.op_conditional(addr, ">=", table)?;
+                if let Unboxed::Bool(false) = self.pop(addr).unbox() {
+                    return Ok(false);
+                }
+                // value < to
+                self.run(to, table)?;
+                self.push(value);
+                self.op_conditional(addr, "<", table)?;
+                Ok(matches!(self.pop(addr).unbox(), Unboxed::Bool(true)))
+            }
+            Pattern::List { items, rest } => {
+                // raw list
+                let list = match Self::list_of(value) {
+                    Some(list) => list,
+                    None => return Ok(false),
+                };
+                // checking length
+                let length = (*list).len();
+                if length < items.len() || (rest.is_none() && length != items.len()) {
+                    return Ok(false);
+                }
+                // matching items
+                for (i, item) in items.iter().enumerate() {
+                    if !self.match_pattern(addr, item, (*list)[i], table)? {
+                        return Ok(false);
+                    }
+                }
+                // matching rest
+                match rest {
+                    Some(rest) => {
+                        let rest_list = self.make_list(addr, &(*list)[items.len()..], table)?;
+                        gc_guard!(self.gc, rest_list);
+                        self.match_pattern(addr, rest, rest_list, table)
+                    }
+                    None => Ok(true),
+                }
+            }
+            Pattern::Constructor {
+                name,
+                qualifier,
+                fields,
+                rest,
+            } => {
+                // values to match with fields
+                let values = match value.unbox() {
+                    Unboxed::Variant(variant) => {
+                        let declaration = (*variant).declaration();
+                        if declaration.name != *name {
+                            return Ok(false);
+                        }
+                        // checking enum, unqualified variant
+                        // name is unique, that's checked statically
+                        if let Some(qualifier) = qualifier {
+                            self.run(qualifier, table)?;
+                            let qualified = self.pop(addr);
+                            match qualified.unbox() {
+                                Unboxed::Enum(e) if e == (*variant).e => {}
+                                Unboxed::Enum(_) => return Ok(false),
+                                _ => error!(Error::own_text(
+                                    addr.clone(),
+                                    format!("{qualified:?} is not an enum."),
+                                    "check your pattern."
+                                )),
+                            }
+                        }
+                        (*variant).values.clone()
+                    }
+                    Unboxed::Instance(instance) if qualifier.is_none() => {
+                        let t = (*instance).t;
+                        if (*t).name.name != *name
+                            && (*t).name.full_name.as_deref() != Some(name.as_str())
+                        {
+                            return Ok(false);
+                        }
+                        (*t).constructor
+                            .iter()
+                            .map(|field| {
+                                (*(*instance).fields)
+                                    .fields
+                                    .get(field)
+                                    .copied()
+                                    .unwrap_or(Value::Null)
+                            })
+                            .collect()
+                    }
+                    _ => return Ok(false),
+                };
+                // checking arity
+                if fields.len() > values.len() || (!rest && fields.len() != values.len()) {
+                    error!(Error::own(
+                        addr.clone(),
+                        format!(
+                            "invalid fields amount: {} in pattern of {name}.",
+                            fields.len()
+                        ),
+                        format!("expected {} fields, or use `..`.", values.len())
+                    ));
+                }
+                // matching fields
+                for (field, field_value) in fields.iter().zip(values) {
+                    if !self.match_pattern(addr, field, field_value, table)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Pattern::Or { patterns } => {
+                for pattern in patterns {
+                    if self.match_pattern(addr, pattern, value, table)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    /// Gets raw list of list instance or raw list
+    unsafe fn list_of(value: Value) -> Option<*mut Vec<Value>> {
+        match value.unbox() {
+            Unboxed::List(list) => Some(list),
+            Unboxed::Instance(instance) if (*(*instance).t).name.name == "List" => {
+                match (*(*instance).fields)
+                    .fields
+                    .get("internal")
+                    .copied()
+                    .map(Value::unbox)
+                {
+                    Some(Unboxed::List(list)) => Some(list),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Creates list instance from values
+    unsafe fn make_list(
+        &mut self,
+        addr: &Address,
+        values: &[Value],
+        table: *mut Table,
+    ) -> Result<Value, ControlFlow> {
+        // creating list instance
+        self.op_instance(addr, "List", &Chunk::new(vec![]), true, table)?;
+        let instance = self.pop(addr);
+        // filling raw list
+        if let Some(list) = Self::list_of(instance) {
+            let container = Value::List(list);
+            for value in values {
+                (*list).push(*value);
+                self.gc_barrier_value(container, *value);
+            }
+        }
+        Ok(instance)
+    }
+
     /// Opcode: Loop
     #[allow(unused_variables)]
     unsafe fn op_loop(
@@ -966,8 +2079,9 @@ impl VM {
         addr: &Address,
         symbol: Symbol,
         body: &Chunk,
-        params: &[String],
+        params: &[Param],
         make_closure: bool,
+        kind: FnKind,
         table: *mut Table,
     ) -> Result<(), ControlFlow> {
         // allocating function
@@ -976,6 +2090,7 @@ impl VM {
             memory::alloc_value(body.clone()),
             params.to_owned(),
         ));
+        (*function).kind = kind;
 
         // if it's need to make_closure
         if make_closure && table != self.globals {
@@ -984,6 +2099,12 @@ impl VM {
             (*function).closure = table;
         }
 
+        // top-level fn remembers it's module,
+        // so it could be loaded by thread
+        if table == self.globals {
+            (*function).module = addr.file.clone();
+        }
+
         // function value
         let function_value = Value::Fn(function);
 
@@ -993,9 +2114,10 @@ impl VM {
 
         // defining fn by name and full name
         (*table).define(addr, &symbol.name, function_value);
-        if symbol.full_name.is_some() {
-            (*table).define(addr, symbol.full_name.as_ref().unwrap(), function_value);
+        if let Some(full_name) = &symbol.full_name {
+            (*table).define(addr, full_name, function_value);
         }
+        self.gc_barrier(table, function_value);
 
         Ok(())
     }
@@ -1010,8 +2132,9 @@ impl VM {
     unsafe fn op_anonymous_fn(
         &mut self,
         body: &Chunk,
-        params: &[String],
+        params: &[Param],
         make_closure: bool,
+        kind: FnKind,
         table: *mut Table,
     ) -> Result<(), ControlFlow> {
         // allocating function
@@ -1020,6 +2143,7 @@ impl VM {
             memory::alloc_value(body.clone()),
             params.to_owned(),
         ));
+        (*function).kind = kind;
 
         // if it's need to make_closure
         if make_closure {
@@ -1050,10 +2174,137 @@ impl VM {
     /// search functions and then binds owner
     /// to them.
     unsafe fn bind_functions(&mut self, table: *mut Table, owner: FnOwner) {
+        // owner value, for gc write barrier
+        let owner_value = match owner {
+            FnOwner::Unit(unit) => Value::Unit(unit),
+            FnOwner::Instance(instance) => Value::Instance(instance),
+            FnOwner::Type(t) => Value::Type(t),
+        };
         for val in (*table).fields.values() {
-            if let Value::Fn(function) = *val {
+            if let Unboxed::Fn(function) = (*val).unbox() {
                 (*function).owner = Some(owner.clone());
+                self.gc_barrier_value(*val, owner_value);
+            }
+        }
+    }
+
+    /// Finds instance member
+    ///
+    /// fields are looked up first, then
+    /// methods, shared by instance type
+    ///
+    unsafe fn find_member(instance: *mut Instance, name: &str) -> Option<Value> {
+        match (*(*instance).fields).fields.get(name) {
+            Some(value) => Some(*value),
+            None => (*(*(*instance).t).methods).fields.get(name).copied(),
+        }
+    }
+
+    /// Finds instance member, like `find_member` does
+    ///
+    /// raises error if not exists
+    ///
+    unsafe fn member(addr: &Address, instance: *mut Instance, name: &str) -> Value {
+        match Self::find_member(instance, name) {
+            Some(value) => value,
+            None => error!(Error::own_text(
+                addr.clone(),
+                format!("{name} is not defined."),
+                "check variable existence.",
+            )),
+        }
+    }
+
+    /// Resolves instance member, like `member`
+    /// does, resolved method is cached at call site
+    /// by type shape, cache is skipped for instances,
+    /// which fields shadow methods
+    ///
+    /// returns member and whether it's a method
+    ///
+    unsafe fn resolve_member(
+        addr: &Address,
+        instance: *mut Instance,
+        name: &str,
+        cache: &InlineCache,
+    ) -> (Value, bool) {
+        let shape = (*(*instance).t).shape;
+        if !(*(*instance).fields).shadows
+            && let Some(method) = cache.get(shape)
+        {
+            return (method, true);
+        }
+        if let Some(value) = (*(*instance).fields).fields.get(name) {
+            return (*value, false);
+        }
+        match (*(*(*instance).t).methods).fields.get(name) {
+            Some(method) => {
+                cache.set(shape, *method);
+                (*method, true)
             }
+            None => error!(Error::own_text(
+                addr.clone(),
+                format!("{name} is not defined."),
+                "check variable existence.",
+            )),
+        }
+    }
+
+    /// Binds method to instance
+    ///
+    /// pushes fn with method body, owned by
+    /// instance, so method could be used as value
+    ///
+    unsafe fn bind_method(&mut self, instance: *mut Instance, method: Value, table: *mut Table) {
+        let Unboxed::Fn(method) = method.unbox() else {
+            panic!("found a non-fn value in methods table. report this error to the developer.")
+        };
+        // allocating bound fn
+        let function = memory::alloc_value(Function::new(
+            (*method).name.clone(),
+            memory::alloc_value((*(*method).body).clone()),
+            (*method).params.clone(),
+        ));
+        (*function).owner = Some(FnOwner::Instance(instance));
+        (*function).kind = (*method).kind;
+
+        // push bound fn to stack
+        let function_value = Value::Fn(function);
+        self.push(function_value);
+
+        // register value in gc
+        self.gc_register(function_value, table);
+    }
+
+    /// Receiver of fn, called without previous
+    ///
+    /// methods, shared by type, called by name
+    /// from other method, receive it's `self`
+    ///
+    unsafe fn self_receiver(addr: &Address, callable: Value, table: *mut Table) -> Option<FnOwner> {
+        match callable.unbox() {
+            Unboxed::Fn(function)
+                if matches!((*function).owner, Some(FnOwner::Type(_))) && (*table).has("self") =>
+            {
+                match (*table).lookup(addr, "self").unbox() {
+                    Unboxed::Instance(instance) => Some(FnOwner::Instance(instance)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks instance fields don't
+    /// shadow methods of instance type
+    ///
+    unsafe fn check_shadowing(addr: &Address, instance: *mut Instance, name: &str) {
+        if (*(*(*instance).t).methods).fields.contains_key(name) {
+            error!(Error::own_text(
+                addr.clone(),
+                format!("{name} is already defined."),
+                "you can rename variable.",
+            ))
         }
     }
 
@@ -1062,35 +2313,70 @@ impl VM {
     /// defines type in `self.types` table
     /// with `safely` allocating it
     ///
-    /// safety guaranteed because gc bears
-    /// no responsibility to control types,
-    /// they will be freed in `self.cleanup()`
+    /// safety guaranteed by registering type
+    /// in gc after it's defined in types table
     ///
     unsafe fn op_define_type(
         &mut self,
         addr: &Address,
         symbol: &Symbol,
         body: &Chunk,
+        methods: &Chunk,
         constructor: &[String],
         impls: &[String],
+        table: *mut Table,
     ) -> Result<(), ControlFlow> {
         // allocating type
         let t = memory::alloc_value(Type::new(
             symbol.clone(),
+            addr.clone(),
             constructor.to_owned(),
             memory::alloc_value(body.clone()),
             impls.to_owned(),
         ));
 
         // defining type by name && full name
-        (*self.types).define(addr, &symbol.name, Value::Type(t));
-        if symbol.full_name.is_some() {
-            (*self.types).define(addr, symbol.full_name.as_ref().unwrap(), Value::Type(t));
+        Self::bind_declaration(self.types, addr, &symbol.name, Value::Type(t));
+        if let Some(full_name) = &symbol.full_name {
+            Self::bind_declaration(self.types, addr, full_name, Value::Type(t));
         }
 
+        // registering in gc, after type
+        // is reachable from types table
+        self.gc_register(Value::Type(t), table);
+
+        // defining methods, shared by all instances,
+        // instance fields are rooted to methods table
+        let methods_table = (*t).methods;
+        (*methods_table).set_root(self.globals);
+        (*methods_table).parent = table;
+        self.run(methods, methods_table)?;
+        (*methods_table).parent = std::ptr::null_mut();
+        self.bind_functions(methods_table, FnOwner::Type(t));
+
         Ok(())
     }
 
+    /// Binds type or trait declaration in table
+    ///
+    /// declaration, executed again (e.g. inside
+    /// fn or loop) rebinds the name, previous one
+    /// is collected by gc, when it's unused
+    ///
+    unsafe fn bind_declaration(table: *mut Table, addr: &Address, name: &str, value: Value) {
+        let redeclared = match (*table).fields.get(name).copied().map(Value::unbox) {
+            Some(Unboxed::Type(t)) => (*t).addr == *addr,
+            Some(Unboxed::Trait(t)) => (*t).addr == *addr,
+            Some(Unboxed::Enum(e)) => (*e).addr == *addr,
+            _ => false,
+        };
+        if redeclared {
+            (*table).fields.insert(name.to_string(), value);
+        } else {
+            (*table).define(addr, name, value);
+        }
+    }
+
     /// Opcode: DefineUnit
     ///
     /// defines type in `self.types` table
@@ -1142,13 +2428,23 @@ impl VM {
         let init_fn = "init";
         if (*(*unit).fields).exists(init_fn) {
             self.push(unit_value);
-            self.op_call(addr, init_fn, true, false, &Chunk::new(vec![]), table)?
+            let args = Chunk::new(vec![]);
+            self.op_call(
+                addr,
+                init_fn,
+                true,
+                false,
+                &args,
+                &[],
+                &InlineCache::new(),
+                table,
+            )?
         }
 
         // defining unit by name and full name
         (*self.units).define(addr, &symbol.name, unit_value);
-        if symbol.full_name.is_some() {
-            (*self.units).define(addr, symbol.full_name.as_ref().unwrap(), unit_value);
+        if let Some(full_name) = &symbol.full_name {
+            (*self.units).define(addr, full_name, unit_value);
         }
 
         // deleting temp parent
@@ -1162,122 +2458,295 @@ impl VM {
     /// defines trait in `self.traits` table
     /// with `safely` allocating it
     ///
-    /// safety guaranteed because gc bears
-    /// no responsibility to control types,
-    /// they will be freed in `self.cleanup()`
+    /// safety guaranteed by registering trait
+    /// in gc after it's defined in traits table
     ///
     unsafe fn op_define_trait(
         &mut self,
         addr: &Address,
         symbol: &Symbol,
+        parents: &[String],
         functions: &[TraitFn],
+        table: *mut Table,
     ) -> Result<(), ControlFlow> {
+        // resolving parent traits, they're
+        // already defined, so kept by traits table
+        let parents: Vec<*mut Trait> = parents
+            .iter()
+            .map(|parent| match (*self.traits).lookup(addr, parent).unbox() {
+                Unboxed::Trait(_trait) => _trait,
+                _ => panic!("not a trait in traits table. report to developer."),
+            })
+            .collect();
+
         // allocating trait
-        let _trait = memory::alloc_value(Trait::new(symbol.clone(), functions.to_owned()));
+        let _trait = memory::alloc_value(Trait::new(
+            symbol.clone(),
+            addr.clone(),
+            parents,
+            functions.to_owned(),
+        ));
 
         // define trait by name and full name
-        (*self.traits).define(addr, &symbol.name, Value::Trait(_trait));
-        if symbol.full_name.is_some() {
-            (*self.traits).define(
-                addr,
-                symbol.full_name.as_ref().unwrap(),
-                Value::Trait(_trait),
-            );
+        Self::bind_declaration(self.traits, addr, &symbol.name, Value::Trait(_trait));
+        if let Some(full_name) = &symbol.full_name {
+            Self::bind_declaration(self.traits, addr, full_name, Value::Trait(_trait));
         }
 
+        // registering in gc, after trait
+        // is reachable from traits table
+        self.gc_register(Value::Trait(_trait), table);
+
         Ok(())
     }
 
-    /// Opcode: Define
+    /// Opcode: DefineEnum
     ///
-    /// defines value in local table
-    /// or, if `has_previous` pops
-    /// value (instance/unit, otherwise raises error)
-    /// from stack and then defines given
-    /// value in it by name
+    /// defines enum in `self.types` table,
+    /// constant variants are created once
+    /// and kept by enum
     ///
-    unsafe fn op_define(
+    /// safety guaranteed by registering enum
+    /// in gc after it's defined in types table,
+    /// and constants after they're kept by enum
+    ///
+    unsafe fn op_define_enum(
         &mut self,
         addr: &Address,
-        name: &str,
-        has_previous: bool,
-        value: &Chunk,
+        symbol: &Symbol,
+        variants: &[EnumVariant],
         table: *mut Table,
     ) -> Result<(), ControlFlow> {
-        // non-previous
-        if !has_previous {
-            self.run(value, table)?;
-            let operand = self.pop(addr);
-            (*table).define(addr, name, operand);
+        // allocating enum
+        let e = memory::alloc_value(Enum::new(symbol.clone(), addr.clone(), variants.to_owned()));
+
+        // define enum by name and full name
+        Self::bind_declaration(self.types, addr, &symbol.name, Value::Enum(e));
+        if let Some(full_name) = &symbol.full_name {
+            Self::bind_declaration(self.types, addr, full_name, Value::Enum(e));
         }
-        // previous
-        else {
-            let previous = self.pop(addr);
 
-            match previous {
-                // define in instance
-                Value::Instance(instance) => {
-                    self.run(value, table)?;
-                    let operand = self.pop(addr);
-                    (*(*instance).fields).define(addr, name, operand);
-                }
-                // define in unit
-                Value::Unit(unit) => {
-                    self.run(value, table)?;
-                    let operand = self.pop(addr);
-                    (*(*unit).fields).define(addr, name, operand);
-                }
-                _ => {
-                    error!(Error::own_text(
-                        addr.clone(),
-                        format!("{previous:?} is not a container."),
-                        "you can define variable for unit or instance."
-                    ))
-                }
+        // registering in gc, after enum
+        // is reachable from types table
+        self.gc_register(Value::Enum(e), table);
+
+        // creating constant variants
+        for (index, variant) in variants.iter().enumerate() {
+            if variant.fields.is_empty() {
+                let constant = Value::Variant(memory::alloc_value(Variant::new(e, index, vec![])));
+                (*e).constants.push(constant);
+                self.gc_barrier_value(Value::Enum(e), constant);
+                self.gc_register(constant, table);
+            } else {
+                (*e).constants.push(Value::Null);
             }
         }
 
         Ok(())
     }
 
-    /// Opcode: Set
+    /// Makes enum variant by name
     ///
-    /// sets value in local table
-    /// or, if `has_previous` pops
-    /// value (instance/unit, otherwise raises error)
-    /// from stack and then sets given
-    /// value in it by name
+    /// variant with payload is created from
+    /// `args`, constant variant is taken
+    /// from enum constants
     ///
-    unsafe fn op_set(
+    unsafe fn make_variant(
         &mut self,
         addr: &Address,
+        e: *mut Enum,
         name: &str,
-        has_previous: bool,
-        value: &Chunk,
+        args: Option<&Chunk>,
+        table: *mut Table,
+        should_push: bool,
+    ) -> Result<(), ControlFlow> {
+        // finding variant
+        let Some(index) = (*e).find(name) else {
+            error!(Error::own_text(
+                addr.clone(),
+                format!("variant {name} is not defined in {}.", (*e).name.name),
+                "check variant existence."
+            ))
+        };
+        let fields_amount = (*e).variants[index].fields.len();
+
+        // loading payload
+        let prev_size = self.stack.len();
+        if let Some(args) = args {
+            self.run(args, table)?;
+        }
+        let passed_amount = self.stack.len() - prev_size;
+
+        // ensuring payload and fields amount are equal
+        if passed_amount != fields_amount {
+            error!(Error::own(
+                addr.clone(),
+                format!("invalid values amount: {passed_amount} to create variant: {name}."),
+                format!(
+                    "expected {fields_amount} values: {name}({}).",
+                    (*e).variants[index].fields.join(", ")
+                )
+            ));
+        }
+
+        // constant variant
+        if fields_amount == 0 {
+            if should_push {
+                self.push((*e).constants[index]);
+            }
+        }
+        // variant with payload, registered in gc
+        // after it's pushed instead of payload
+        else {
+            let values = self.stack.split_off(prev_size);
+            let variant = Value::Variant(memory::alloc_value(Variant::new(e, index, values)));
+            self.push(variant);
+            self.gc_register(variant, table);
+            if !should_push {
+                self.pop(addr);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks variants are equal, variants of same
+    /// enum kind are compared by payload values
+    unsafe fn variants_equal(
+        &mut self,
+        addr: &Address,
+        a: *mut Variant,
+        b: *mut Variant,
+        table: *mut Table,
+    ) -> Result<bool, ControlFlow> {
+        // checking kind
+        if a == b {
+            return Ok(true);
+        }
+        // guarding variants, since payload
+        // could be compared by eq fn-s
+        gc_guard!(self.gc, Value::Variant(a));
+        gc_guard!(self.gc, Value::Variant(b));
+        if (*a).e != (*b).e || (*a).index != (*b).index {
+            return Ok(false);
+        }
+        // checking payload
+        for i in 0..(*a).values.len() {
+            self.push((*b).values[i]);
+            self.push((*a).values[i]);
+            self.op_conditional(addr, "==", table)?;
+            if let Unboxed::Bool(false) = self.pop(addr).unbox() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Opcode: Define
+    ///
+    /// defines value in local table
+    /// or, if `has_previous` pops
+    /// value (instance/unit, otherwise raises error)
+    /// from stack and then defines given
+    /// value in it by name
+    ///
+    unsafe fn op_define(
+        &mut self,
+        addr: &Address,
+        name: &str,
+        has_previous: bool,
+        value: &Chunk,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // non-previous
+        if !has_previous {
+            self.run(value, table)?;
+            let operand = self.pop(addr);
+            (*table).define(addr, name, operand);
+            self.gc_barrier(table, operand);
+        }
+        // previous
+        else {
+            let previous = self.pop(addr);
+
+            match previous.unbox() {
+                // define in instance
+                Unboxed::Instance(instance) => {
+                    self.run(value, table)?;
+                    let operand = self.pop(addr);
+                    Self::check_shadowing(addr, instance, name);
+                    (*(*instance).fields).define(addr, name, operand);
+                    self.gc_barrier((*instance).fields, operand);
+                }
+                // define in unit
+                Unboxed::Unit(unit) => {
+                    self.run(value, table)?;
+                    let operand = self.pop(addr);
+                    (*(*unit).fields).define(addr, name, operand);
+                    self.gc_barrier((*unit).fields, operand);
+                }
+                _ => {
+                    error!(Error::own_text(
+                        addr.clone(),
+                        format!("{previous:?} is not a container."),
+                        "you can define variable for unit or instance."
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Opcode: Set
+    ///
+    /// sets value in local table
+    /// or, if `has_previous` pops
+    /// value (instance/unit, otherwise raises error)
+    /// from stack and then sets given
+    /// value in it by name
+    ///
+    unsafe fn op_set(
+        &mut self,
+        addr: &Address,
+        name: &str,
+        has_previous: bool,
+        value: &Chunk,
         table: *mut Table,
     ) -> Result<(), ControlFlow> {
         // non-previous
         if !has_previous {
             self.run(value, table)?;
             let operand = self.pop(addr);
-            (*table).set(addr.clone(), name, operand);
+            let written = (*table).set(addr.clone(), name, operand);
+            self.gc_barrier(written, operand);
         }
         // previous
         else {
             let previous = self.pop(addr);
 
-            match previous {
+            match previous.unbox() {
                 // define in instance
-                Value::Instance(instance) => {
+                Unboxed::Instance(instance) => {
                     self.run(value, table)?;
                     let operand = self.pop(addr);
-                    (*(*instance).fields).set_local(addr, name, operand);
+                    let fields = (*instance).fields;
+                    // method is overridden for this instance only
+                    if !(*fields).fields.contains_key(name)
+                        && (*(*(*instance).t).methods).fields.contains_key(name)
+                    {
+                        (*fields).shadow(name, operand);
+                    } else {
+                        (*fields).set_local(addr, name, operand);
+                    }
+                    self.gc_barrier(fields, operand);
                 }
                 // define in unit
-                Value::Unit(unit) => {
+                Unboxed::Unit(unit) => {
                     self.run(value, table)?;
                     let operand = self.pop(addr);
                     (*(*unit).fields).set_local(addr, name, operand);
+                    self.gc_barrier((*unit).fields, operand);
                 }
                 _ => {
                     error!(Error::own_text(
@@ -1306,6 +2775,7 @@ impl VM {
         name: &str,
         has_previous: bool,
         should_push: bool,
+        cache: &InlineCache,
         table: *mut Table,
     ) -> Result<(), ControlFlow> {
         // non-previous
@@ -1332,26 +2802,54 @@ impl VM {
         // previous
         else {
             let previous = self.pop(addr);
-            match previous {
-                // from instance
-                Value::Instance(instance) => {
-                    let value = (*(*instance).fields).find(addr, name);
+            match previous.unbox() {
+                // from instance, methods are loaded
+                // as fns, bound to the instance
+                Unboxed::Instance(instance) => {
+                    let (value, is_method) = Self::resolve_member(addr, instance, name, cache);
                     if should_push {
-                        self.push(value);
+                        if is_method {
+                            gc_guard!(self.gc, previous);
+                            self.bind_method(instance, value, table);
+                        } else {
+                            self.push(value);
+                        }
                     }
                 }
                 // from unit
-                Value::Unit(unit) => {
+                Unboxed::Unit(unit) => {
                     let value = (*(*unit).fields).find(addr, name);
                     if should_push {
                         self.push(value);
                     }
                 }
+                // constant variant from enum
+                Unboxed::Enum(e) => {
+                    self.make_variant(addr, e, name, None, table, should_push)?;
+                }
+                // payload value from variant
+                Unboxed::Variant(variant) => {
+                    let declaration = (*variant).declaration();
+                    match declaration.fields.iter().position(|field| field == name) {
+                        Some(index) => {
+                            if should_push {
+                                self.push((*variant).values[index]);
+                            }
+                        }
+                        None => {
+                            error!(Error::own_text(
+                                addr.clone(),
+                                format!("{name} is not a field of variant {}.", declaration.name),
+                                "check field existence."
+                            ))
+                        }
+                    }
+                }
                 _ => {
                     error!(Error::own_text(
                         addr.clone(),
                         format!("{previous:?} is not a container."),
-                        "you can load variable from unit or instance."
+                        "you can load variable from unit, instance or variant."
                     ))
                 }
             }
@@ -1368,35 +2866,41 @@ impl VM {
     /// from stack and then calls fn
     /// by name from it
     ///
+    /// * `receiver`: instance, fn is called from,
+    ///   used as `self` of methods, shared by type
+    ///
+    /// # Safety
+    ///
+    /// `callable` should be rooted and
+    /// `table` should be an alive table of vm
+    ///
     #[allow(unused_parens)]
+    #[allow(clippy::too_many_arguments)]
     pub unsafe fn call(
         &mut self,
         addr: &Address,
         name: &str,
         callable: Value,
+        receiver: Option<FnOwner>,
         args: &Chunk,
+        named: &[String],
         table: *mut Table,
         should_push: bool,
     ) -> Result<(), ControlFlow> {
-        /// Pass arguments
+        /// Just loads arguments to stack
         ///
         /// * `params_amount`: expected params amount
         /// * `args`: args chunk
-        /// * `params`: params vector, used
-        ///   to set variables with params names
-        ///   to args
         /// * `table`: table, where fn called
         /// * `call_table`: call table
         ///
-        unsafe fn pass_arguments(
+        unsafe fn load_arguments(
             vm: &mut VM,
             addr: &Address,
             name: &str,
             params_amount: usize,
             args: &Chunk,
-            params: Vec<String>,
             table: *mut Table,
-            call_table: *mut Table,
         ) -> Result<(), ControlFlow> {
             // passing args
             let prev_size = vm.stack.len();
@@ -1406,13 +2910,6 @@ impl VM {
 
             // ensuring args && params amount are equal
             if passed_amount == params_amount {
-                // defining params variables with
-                // args values
-                for param in params.iter().rev() {
-                    let operand = vm.pop(addr);
-                    (*call_table).define(addr, param, operand);
-                }
-
                 Ok(())
             } else {
                 error!(Error::own(
@@ -1426,44 +2923,238 @@ impl VM {
             }
         }
 
-        /// Just loads arguments to stack
+        // checking value is fn
+        if let Unboxed::Fn(function) = callable.unbox() {
+            // loading arguments to stack
+            let prev_size = self.stack.len();
+            self.run(args, table)?;
+            let passed_amount = self.stack.len() - prev_size;
+
+            // calling fn
+            self.call_fn(
+                addr,
+                name,
+                function,
+                receiver,
+                passed_amount,
+                named,
+                table,
+                should_push,
+            )
+        }
+        // checking value is native
+        else if let Unboxed::Native(function) = callable.unbox() {
+            Self::expect_positional(addr, name, named);
+
+            // call table
+            let call_table = memory::alloc_value(Table::new());
+
+            // parent and closure tables, to chain call_table
+            // with current
+            (*call_table).parent = table;
+
+            // freeing
+            defer! {
+                try_free_table(call_table);
+            }
+
+            // root to globals
+            (*call_table).set_root(self.globals);
+
+            // loading arguments to stack
+            load_arguments(self, addr, name, (*function).params_amount, args, table)?;
+
+            // calling native fn
+            let native = (*function).function;
+            if let Some(profiler) = &mut self.profiler {
+                profiler.enter((*function).name.name.clone());
+            }
+            let result = native(self, addr.clone(), should_push, call_table);
+            if let Some(profiler) = &mut self.profiler {
+                profiler.leave();
+            }
+
+            result
+        } else {
+            error!(Error::own_text(
+                addr.clone(),
+                format!("{name} is not a fn."),
+                "you can call only fn-s."
+            ));
+        }
+    }
+
+    /// Calls fn with arguments,
+    /// already loaded to stack
+    ///
+    /// tail calls, propagated from the fn body,
+    /// are executed in the same loop, so the
+    /// host stack doesn't grow with them.
+    ///
+    /// * `receiver`: instance, fn is called from
+    /// * `passed_amount`: amount of loaded args
+    /// * `table`: table, where fn called
+    ///
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn call_fn(
+        &mut self,
+        addr: &Address,
+        name: &str,
+        function: *mut Function,
+        receiver: Option<FnOwner>,
+        passed_amount: usize,
+        named: &[String],
+        table: *mut Table,
+        should_push: bool,
+    ) -> Result<(), ControlFlow> {
+        /// Pass arguments
         ///
-        /// * `params_amount`: expected params amount
-        /// * `args`: args chunk
-        /// * `table`: table, where fn called
+        /// positional args are passed by order,
+        /// named args by name, extra positional args
+        /// are collected to variadic param, missing
+        /// params are set to their defaults
+        ///
+        /// * `passed_amount`: amount of loaded args
+        /// * `named`: names of last loaded args
         /// * `call_table`: call table
         ///
-        unsafe fn load_arguments(
+        unsafe fn pass_arguments(
             vm: &mut VM,
             addr: &Address,
             name: &str,
-            params_amount: usize,
-            args: &Chunk,
-            table: *mut Table,
+            function: *mut Function,
+            passed_amount: usize,
+            named: &[String],
+            call_table: *mut Table,
         ) -> Result<(), ControlFlow> {
-            // passing args
-            let prev_size = vm.stack.len();
-            vm.run(args, table)?;
-            let new_size = vm.stack.len();
-            let passed_amount = new_size - prev_size;
+            let params = &(*function).params;
+            // args are kept in stack, until
+            // they are defined in call table
+            let base = vm.stack.len() - passed_amount;
+            let positional = passed_amount - named.len();
+            let fixed = params.iter().filter(|p| !p.variadic).count();
+            let variadic = params.iter().find(|p| p.variadic);
+            let mut passed = vec![false; params.len()];
+
+            // expected amount hint
+            let expected = || {
+                let required = params
+                    .iter()
+                    .filter(|p| !p.variadic && p.default.is_none())
+                    .count();
+                if variadic.is_some() {
+                    format!("expected at least {required} arguments.")
+                } else if required == fixed {
+                    format!("expected {fixed} arguments.")
+                } else {
+                    format!("expected from {required} to {fixed} arguments.")
+                }
+            };
 
-            // ensuring args && params amount are equal
-            if passed_amount == params_amount {
-                Ok(())
-            } else {
+            // positional args
+            if positional > fixed && variadic.is_none() {
                 error!(Error::own(
                     addr.clone(),
                     format!(
-                        "invalid args amount: {} to call: {}. stack: {:?}",
-                        passed_amount, name, vm.stack
+                        "invalid args amount: {passed_amount} to call: {}.",
+                        (*function).signature(name)
                     ),
-                    format!("expected {params_amount} arguments.")
+                    expected()
                 ));
             }
+            for (i, param) in params.iter().take(positional.min(fixed)).enumerate() {
+                (*call_table).define(addr, &param.name, vm.stack[base + i]);
+                passed[i] = true;
+            }
+
+            // named args
+            for (i, arg) in named.iter().enumerate() {
+                let Some(index) = params.iter().position(|p| !p.variadic && &p.name == arg) else {
+                    error!(Error::own_text(
+                        addr.clone(),
+                        format!(
+                            "unknown argument: {arg} to call: {}.",
+                            (*function).signature(name)
+                        ),
+                        "check fn params."
+                    ))
+                };
+                if passed[index] {
+                    error!(Error::own_text(
+                        addr.clone(),
+                        format!(
+                            "argument {arg} is passed twice to call: {}.",
+                            (*function).signature(name)
+                        ),
+                        "pass argument by position or by name."
+                    ));
+                }
+                (*call_table).define(addr, arg, vm.stack[base + positional + i]);
+                passed[index] = true;
+            }
+
+            // variadic args
+            if let Some(variadic) = variadic {
+                let rest = vm.stack[base + positional.min(fixed)..base + positional].to_vec();
+                let list = vm.make_list(addr, &rest, call_table)?;
+                (*call_table).define(addr, &variadic.name, list);
+            }
+            vm.stack.truncate(base);
+
+            // default values
+            for (i, param) in params.iter().enumerate() {
+                if passed[i] || param.variadic {
+                    continue;
+                }
+                match &param.default {
+                    Some(default) => {
+                        vm.run(default, call_table)?;
+                        let value = vm.pop(addr);
+                        (*call_table).define(addr, &param.name, value);
+                    }
+                    None => error!(Error::own(
+                        addr.clone(),
+                        format!(
+                            "missing argument: {} to call: {}.",
+                            param.name,
+                            (*function).signature(name)
+                        ),
+                        expected()
+                    )),
+                }
+            }
+            Ok(())
         }
 
-        // checking value is fn
-        if let Value::Fn(function) = callable {
+        // checking call depth
+        self.check_call_depth(addr);
+        self.call_depth += 1;
+
+        // current fn, receiver and call site
+        let mut function = function;
+        let mut receiver = receiver;
+        let mut passed_amount = passed_amount;
+        let mut named = named.to_vec();
+        let mut tail_site: Option<(Address, String)> = None;
+
+        let result = loop {
+            let (addr, name) = match &tail_site {
+                Some((addr, name)) => (addr, name.as_str()),
+                None => (addr, name),
+            };
+
+            // bound owner, or receiver for
+            // methods, shared by type
+            let owner = match (*function).owner.clone() {
+                Some(FnOwner::Type(_)) => receiver.clone(),
+                owner => owner,
+            };
+
+            // entering profiler frame
+            if let Some(profiler) = &mut self.profiler {
+                profiler.enter(Self::profile_name(function, &owner));
+            }
+
             // call table
             let call_table = memory::alloc_value(Table::new());
 
@@ -1472,91 +3163,811 @@ impl VM {
             (*call_table).parent = table;
             (*call_table).closure = (*function).closure;
 
-            // freeing call table
-            defer! {
-                try_free_table(call_table);
-            }
-
             // root & self
-            if (*function).owner.is_some() {
-                match (*function).owner.clone().unwrap() {
+            if let Some(owner) = &owner {
+                match owner {
                     FnOwner::Unit(unit) => {
-                        (*call_table).set_root((*unit).fields);
-                        (*call_table).define(addr, "self", Value::Unit(unit));
+                        (*call_table).set_root((**unit).fields);
+                        (*call_table).define(addr, "self", Value::Unit(*unit));
                     }
                     FnOwner::Instance(instance) => {
-                        (*call_table).set_root((*instance).fields);
-                        (*call_table).define(addr, "self", Value::Instance(instance));
+                        (*call_table).set_root((**instance).fields);
+                        (*call_table).define(addr, "self", Value::Instance(*instance));
+                    }
+                    FnOwner::Type(_) => {
+                        panic!(
+                            "method is called without receiver. report this error to the developer."
+                        )
                     }
                 }
+            } else if (*function).kind != FnKind::Plain {
+                // generator or task outlives caller table
+                (*call_table).set_root(self.globals)
             } else {
                 (*call_table).set_root(table)
             }
 
-            // passing args
-            pass_arguments(
+            // entering debugger frame
+            if let Some(debugger) = &mut self.debugger {
+                debugger.enter(Self::profile_name(function, &owner), addr, call_table);
+            }
+
+            // passing args and running body, fn is guarded,
+            // because after tail call it could be unreachable
+            self.gc_guard(Value::Fn(function));
+            let flow = match pass_arguments(
                 self,
                 addr,
                 name,
-                (*function).params.len(),
-                args,
-                (*function).params.clone(),
-                table,
+                function,
+                passed_amount,
+                &named,
                 call_table,
-            )?;
+            ) {
+                Ok(()) if (*function).kind == FnKind::Generator => {
+                    self.make_generator(addr, function, call_table, table)
+                }
+                Ok(()) if (*function).kind == FnKind::Async => {
+                    self.make_task(addr, function, call_table, table)
+                }
+                Ok(()) => self.run(&*(*function).body, call_table),
+                Err(e) => Err(e),
+            };
+            self.gc_unguard();
+
+            // freeing call table
+            try_free_table(call_table);
+
+            // leaving profiler and debugger frames
+            if let Some(profiler) = &mut self.profiler {
+                profiler.leave();
+            }
+            if let Some(debugger) = &mut self.debugger {
+                debugger.leave();
+            }
+
+            match flow {
+                Ok(()) => break Ok(()),
+                // if return
+                Err(ControlFlow::Return(val)) => {
+                    if should_push {
+                        self.push(val);
+                    }
+                    break Ok(());
+                }
+                // if tail call, continuing with next fn
+                Err(ControlFlow::TailCall {
+                    addr,
+                    name,
+                    callable,
+                    receiver: next_receiver,
+                    passed_amount: amount,
+                    named: next_named,
+                }) => {
+                    if let Unboxed::Fn(next) = callable.unbox() {
+                        function = next;
+                        receiver = next_receiver;
+                        passed_amount = amount;
+                        named = next_named.into_vec();
+                        tail_site = Some((addr, name));
+                    } else {
+                        panic!("tail call of non-fn value. report this error to the developer.")
+                    }
+                }
+                // otherwise, panic
+                Err(e) => {
+                    panic!("unhandled control flow: {e:?}. report this error to the developer.")
+                }
+            }
+        };
+
+        self.call_depth -= 1;
+        result
+    }
+
+    /// Raises error, if call depth
+    /// exceeds max call depth
+    fn check_call_depth(&self, addr: &Address) {
+        if self.call_depth >= self.settings.max_call_depth {
+            error!(Error::limit(
+                addr.clone(),
+                "stack overflow.".to_string(),
+                format!(
+                    "call depth exceeded {}, check recursion or raise --max-call-depth.",
+                    self.settings.max_call_depth
+                )
+            ));
+        }
+    }
+
+    /// Makes generator of fn, args are already
+    /// passed to `call_table`, generator instance
+    /// is returned from fn
+    ///
+    /// safety guaranteed by pushing generator
+    /// to stack before registering in gc.
+    ///
+    unsafe fn make_generator(
+        &mut self,
+        addr: &Address,
+        function: *mut Function,
+        call_table: *mut Table,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // allocating generator, body
+        // is started by first resume
+        let raw = memory::alloc_any(Generator::new(function, call_table));
+
+        // pushing generator, then registering it
+        // with call table, args are defined in
+        let value = Value::Any(raw);
+        self.push(value);
+        self.gc_register(value, call_table);
+
+        // creating generator instance
+        let instance = self.wrap_internal(addr, "Generator", value, table)?;
+        self.pop(addr);
+
+        // caller table could be freed before generator is
+        // resumed, so call table is chained with resumer
+        (*call_table).parent = std::ptr::null_mut();
+
+        Err(ControlFlow::Return(instance))
+    }
+
+    /// Makes task of async fn, args are already
+    /// passed to `call_table`, task is scheduled
+    /// and it's instance is returned from fn
+    unsafe fn make_task(
+        &mut self,
+        addr: &Address,
+        function: *mut Function,
+        call_table: *mut Table,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // task body
+        let mut body = Generator::new(function, call_table);
+        body.task = true;
+
+        // spawning task
+        let instance = self.spawn_task(
+            Task::new(addr.clone(), TaskKind::Body(body)),
+            call_table,
+            table,
+        )?;
+
+        // caller table could be freed before task is
+        // resumed, so call table is chained with resumer
+        (*call_table).parent = std::ptr::null_mut();
+
+        Err(ControlFlow::Return(instance))
+    }
+
+    /// Takes host stack segment for generator body,
+    /// raises error, if it couldn't be allocated
+    unsafe fn body_segment(&mut self, addr: &Address) -> DefaultStack {
+        match self.host_stack.take() {
+            Ok(segment) => segment,
+            Err(err) => error!(Error::own(
+                addr.clone(),
+                "couldn't start generator.".to_string(),
+                format!("failed to allocate stack segment: {err}.")
+            )),
+        }
+    }
+
+    /// Starts generator coroutine, that runs
+    /// fn body on it's own host stack segment
+    unsafe fn start_body(&mut self, generator: *mut Generator, segment: DefaultStack) {
+        let body = (*(*generator).function).body;
+        (*generator).vm = self;
+        (*generator).stack_limit = HostStack::limit_of(&segment);
+        (*generator).coroutine = Some(GeneratorCoroutine::with_stack(
+            segment,
+            move |yielder: &GeneratorYielder, vm: *mut VM| {
+                (*generator).yielder = yielder;
+                match (*vm).run(&*body, (*generator).table) {
+                    Ok(()) => Ok(Value::Null),
+                    Err(ControlFlow::Return(value)) => Ok(value),
+                    // tail call is made on generator stack
+                    Err(ControlFlow::TailCall {
+                        addr,
+                        name,
+                        callable,
+                        receiver,
+                        passed_amount,
+                        named,
+                    }) => {
+                        let Unboxed::Fn(callable) = callable.unbox() else {
+                            panic!("tail call of non-fn value. report this error to the developer.")
+                        };
+                        (*vm).call_fn(
+                            &addr,
+                            &name,
+                            callable,
+                            receiver,
+                            passed_amount,
+                            &named,
+                            (*generator).table,
+                            true,
+                        )?;
+                        Ok((*vm).pop(&addr))
+                    }
+                    Err(e) => Err(e),
+                }
+            },
+        ));
+    }
+
+    /// Makes instance of type `name`, with
+    /// internal value, which should be rooted
+    pub(crate) unsafe fn wrap_internal(
+        &mut self,
+        addr: &Address,
+        name: &str,
+        internal: Value,
+        table: *mut Table,
+    ) -> Result<Value, ControlFlow> {
+        let args = Chunk::new(vec![Opcode::Push {
+            addr: addr.clone(),
+            value: OpcodeValue::Raw(internal),
+        }]);
+        self.op_instance(addr, name, &args, true, table)?;
+        Ok(self.pop(addr))
+    }
+
+    /// Makes instance of type `t` with given fields,
+    /// type body and init fn are not called, fields
+    /// should be rooted, fn-s are not bound
+    pub(crate) unsafe fn restore_instance(
+        &mut self,
+        addr: &Address,
+        t: *mut Type,
+        fields: Vec<(String, Value)>,
+        table: *mut Table,
+    ) -> Value {
+        // creating instance
+        let instance = memory::alloc_value(Instance::new(t, memory::alloc_value(Table::new())));
+        let instance_value = Value::Instance(instance);
+
+        // guarding instance in gc and registering it
+        gc_guard!(self.gc, instance_value);
+        self.gc_register(instance_value, table);
+
+        // methods of type are rooted to globals
+        (*(*instance).fields).set_root((*t).methods);
+
+        // setting fields
+        for (name, value) in fields {
+            (*(*instance).fields).fields.insert(name, value);
+            self.gc_barrier((*instance).fields, value);
+        }
+
+        // resolving traits of type
+        self.check_traits(addr, instance, table);
+
+        instance_value
+    }
+
+    /// Spawns task, scheduling it by it's kind,
+    /// task is registered with `register` table,
+    /// returns `Task` instance of it
+    ///
+    /// safety guaranteed by scheduling task,
+    /// before registering in gc.
+    ///
+    pub(crate) unsafe fn spawn_task(
+        &mut self,
+        task: Task,
+        register: *mut Table,
+        table: *mut Table,
+    ) -> Result<Value, ControlFlow> {
+        // allocating task, body
+        // is started by first resume
+        let addr = task.addr.clone();
+        let value = Value::Any(memory::alloc_any(task));
+        let task = task_of(value);
+
+        // scheduling task
+        match &mut (*task).kind {
+            TaskKind::Body(_) => self.scheduler.ready.push_back(value),
+            TaskKind::Sleep(_) => self.scheduler.sleeping.push(value),
+            TaskKind::Poll(wake, _) => {
+                if let Wake::Io(token) = wake {
+                    self.scheduler.io.insert(*token, value);
+                }
+                self.scheduler.polling.push(value);
+            }
+        }
+        self.gc_register(value, register);
+
+        // creating task instance
+        self.wrap_internal(&addr, "Task", value, table)
+    }
+
+    /// Resumes generator, until it yields
+    /// next value or it's body returns
+    ///
+    /// suspended vm stack and gc guard values of body
+    /// are restored, and call table is chained with
+    /// `table`, so resumer values are reachable by gc.
+    /// after suspend, they are split off back
+    ///
+    pub(crate) unsafe fn resume_generator(
+        &mut self,
+        addr: &Address,
+        generator: *mut Generator,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // value is already yielded, or body returned
+        if (*generator).done || (*generator).peeked.is_some() {
+            return Ok(());
+        }
+        if (*generator).running {
+            error!(Error::new(
+                addr.clone(),
+                "generator is already running.",
+                "generator can't iterate itself."
+            ));
+        }
+
+        // starting body, segment is taken only
+        // by generators, that are resumed
+        if (*generator).coroutine.is_none() {
+            let segment = self.body_segment(addr);
+            self.start_body(generator, segment);
+        }
+
+        // checking call depth
+        self.check_call_depth(addr);
+        self.call_depth += 1;
+
+        // restoring suspended body
+        let stack_base = self.stack.len();
+        let guard_base = (*self.gc).guarded().len();
+        self.stack.append(&mut (*generator).stack);
+        (*self.gc).restore_guards(std::mem::take(&mut (*generator).guard));
+        (*(*generator).table).parent = table;
+        (*generator).suspended = std::ptr::null_mut();
+        (*generator).running = true;
+
+        // resuming, body runs with it's own host stack limit
+        (*generator).vm = self;
+        self.generators.push(generator);
+        let limit = std::mem::replace(&mut self.host_stack.limit, (*generator).stack_limit);
+        let result = match &mut (*generator).coroutine {
+            Some(coroutine) => coroutine.resume(self as *mut VM),
+            None => panic!("generator has no coroutine. report this error to the developer."),
+        };
+        (*generator).stack_limit = std::mem::replace(&mut self.host_stack.limit, limit);
+        self.generators.pop();
+
+        // suspending body
+        (*generator).running = false;
+        (*(*generator).table).parent = std::ptr::null_mut();
+        self.call_depth -= 1;
+
+        match result {
+            CoroutineResult::Yield(value) => {
+                (*generator).stack = self.stack.split_off(stack_base);
+                (*generator).guard = (*self.gc).split_guards(guard_base);
+                (*generator).peeked = Some(value);
+                Ok(())
+            }
+            CoroutineResult::Return(result) => {
+                if let Some(coroutine) = (*generator).coroutine.take() {
+                    self.host_stack.put(coroutine.into_stack());
+                }
+                (*generator).done = true;
+                (*generator).returned = Some(result?);
+                Ok(())
+            }
+        }
+    }
+
+    /// Finishes generator body coroutine
+    ///
+    /// suspended body is unwound, so locals of it's frames
+    /// are dropped. frames pop gc guards, they pushed, so
+    /// guards of body are restored first. segment of body
+    /// is returned to pool then
+    ///
+    pub(crate) unsafe fn finish_body(
+        &mut self,
+        mut coroutine: GeneratorCoroutine,
+        guard: Vec<Value>,
+    ) {
+        if !coroutine.done() {
+            let limit = self.host_stack.limit;
+            let depth = self.call_depth;
+            let guard_base = (*self.gc).guarded().len();
+            (*self.gc).restore_guards(guard);
+            coroutine.force_unwind();
+            (*self.gc).split_guards(guard_base);
+            self.call_depth = depth;
+            self.host_stack.limit = limit;
+        }
+        self.host_stack.put(coroutine.into_stack());
+    }
+
+    /// Opcode: Yield
+    ///
+    /// suspends running generator with value,
+    /// innermost table is kept by generator,
+    /// so it's roots chain is reachable by gc
+    ///
+    unsafe fn op_yield(
+        &mut self,
+        addr: &Address,
+        value: &Chunk,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // running value
+        self.run(value, table)?;
+        let value = self.pop(addr);
+
+        // suspending generator
+        let Some(generator) = self.generators.last().copied().filter(|g| !(**g).task) else {
+            error!(Error::new(
+                addr.clone(),
+                "couldn't use yield outside generator.",
+                "you can use it only inside fn* generators."
+            ))
+        };
+        (*generator).suspended = table;
+        (*(*generator).yielder).suspend(value);
+
+        Ok(())
+    }
+
+    /// Opcode: Await
+    ///
+    /// awaits task result. inside task body, task
+    /// is suspended, until awaited task is done,
+    /// otherwise event loop runs until it's done
+    ///
+    unsafe fn op_await(
+        &mut self,
+        addr: &Address,
+        value: &Chunk,
+        should_push: bool,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // running value
+        self.run(value, table)?;
+        let value = self.pop(addr);
+        gc_guard!(self.gc, value);
+        let (handle, task) = self.expect_task(addr, value);
+
+        // waiting for task
+        if !(*task).is_done() {
+            match self.generators.last().copied() {
+                Some(generator) if (*generator).task => {
+                    (*generator).suspended = table;
+                    (*(*generator).yielder).suspend(handle);
+                }
+                _ => self.run_until(addr, task, table)?,
+            }
+        }
+
+        // pushing result
+        if should_push {
+            match (*task).result {
+                Some(result) => self.push(result),
+                None => panic!("awaited task is not done. report this error to the developer."),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets task handle and task
+    /// of `Task` instance value
+    unsafe fn expect_task(&self, addr: &Address, value: Value) -> (Value, *mut Task) {
+        if let Unboxed::Instance(instance) = value.unbox()
+            && (*(*instance).t).name.name == "Task"
+            && let handle = (*(*instance).fields).find(addr, "internal")
+            && let Unboxed::Any(any) = handle.unbox()
+            && (*any).is::<Task>()
+        {
+            return (handle, task_of(handle));
+        }
+        error!(Error::own_text(
+            addr.clone(),
+            format!("couldn't await {value}."),
+            "only tasks, returned by async fns and natives, can be awaited."
+        ))
+    }
+
+    /// Runs event loop, until task is done
+    pub(crate) unsafe fn run_until(
+        &mut self,
+        addr: &Address,
+        task: *mut Task,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        while !(*task).is_done() {
+            if !self.tick(addr, table)? {
+                error!(Error::new(
+                    addr.clone(),
+                    "awaited task will never be done.",
+                    "check tasks are not awaiting each other."
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs event loop, until
+    /// all scheduled tasks are done
+    ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
+    pub unsafe fn run_tasks(&mut self, table: *mut Table) -> Result<(), ControlFlow> {
+        while self.tick(&Address::unknown(), table)? {}
+        Ok(())
+    }
 
-            // running body
-            if let Err(e) = self.run(&*(*function).body, call_table) {
-                return match e {
-                    // if return
-                    ControlFlow::Return(val) => {
-                        if should_push {
-                            self.push(val);
-                        }
-                        Ok(())
-                    }
-                    // otherwise, panic
-                    _ => {
-                        panic!("unhandled control flow: {e:?}. report this error to the developer.")
-                    }
-                };
+    /// Event loop tick
+    ///
+    /// resumes ready tasks, then completes expired sleeps
+    /// and woken polls. if nothing progressed, waits for
+    /// io and wakes, until nearest deadline or interval.
+    /// returns false, if no task was run or done
+    ///
+    unsafe fn tick(&mut self, addr: &Address, table: *mut Table) -> Result<bool, ControlFlow> {
+        // resuming ready tasks
+        let ran = self.scheduler.ready.len();
+        let mut progressed = ran > 0;
+        for _ in 0..ran {
+            if let Some(task) = self.scheduler.ready.pop_front() {
+                self.step_task(task, table)?;
             }
-            Ok(())
         }
-        // checking value is native
-        else if let Value::Native(function) = callable {
-            // call table
-            let call_table = memory::alloc_value(Table::new());
 
-            // parent and closure tables, to chain call_table
-            // with current
-            (*call_table).parent = table;
+        // completing expired sleeps, by deadline order
+        let now = Instant::now();
+        let mut expired: Vec<(Instant, Value)> = Vec::new();
+        self.scheduler
+            .sleeping
+            .retain(|value| match (*task_of(*value)).kind {
+                TaskKind::Sleep(deadline) if deadline <= now => {
+                    expired.push((deadline, *value));
+                    false
+                }
+                _ => true,
+            });
+        expired.sort_by_key(|(deadline, _)| *deadline);
+        progressed |= !expired.is_empty();
+        for (_, task) in expired {
+            self.complete_task(task, Value::Null);
+        }
 
-            // freeing
-            defer! {
-                try_free_table(call_table);
+        // polling woken tasks
+        for task in self.scheduler.polling.clone() {
+            let raw = task_of(task);
+            let addr = (*raw).addr.clone();
+            let TaskKind::Poll(wake, poll) = &mut (*raw).kind else {
+                continue;
+            };
+            if !(*raw).woken && !matches!(wake, Wake::Interval(_)) {
+                continue;
             }
+            (*raw).woken = false;
+            if poll(self, &addr, table)? {
+                if let Wake::Io(token) = wake {
+                    self.scheduler.io.remove(token);
+                }
+                let result = self.pop(&addr);
+                self.complete_task(task, result);
+                progressed = true;
+            }
+        }
+        self.scheduler
+            .polling
+            .retain(|value| !(*task_of(*value)).is_done());
+
+        // waiting for tasks, only if nothing is progressed,
+        // so awaiting code could check it's task first
+        if progressed || !self.scheduler.has_pending() {
+            return Ok(progressed);
+        }
+        let timeout = [
+            self.scheduler
+                .nearest_deadline()
+                .map(|deadline| deadline.saturating_duration_since(Instant::now())),
+            self.scheduler.shortest_interval(),
+            self.time_left(),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        match self.reactor.as_mut() {
+            // waiting for io, wakes and timeout
+            Some(reactor) => match reactor.wait(timeout) {
+                Ok(tokens) => self.scheduler.wake(&tokens),
+                Err(err) => error!(Error::own_text(
+                    addr.clone(),
+                    format!("failed to wait for io: {err}"),
+                    "check system resources."
+                )),
+            },
+            // without io, only sleeps and intervals are pending
+            None => std::thread::sleep(timeout.unwrap_or_default()),
+        }
+        self.check_timeout(addr);
 
-            // root to globals
-            (*call_table).set_root(self.globals);
+        Ok(true)
+    }
 
-            // loading arguments to stack
-            load_arguments(self, addr, name, (*function).params_amount, args, table)?;
+    /// Resumes task body, until it awaits
+    /// task, that is not done, or returns
+    unsafe fn step_task(&mut self, task: Value, table: *mut Table) -> Result<(), ControlFlow> {
+        let raw = task_of(task);
+        let TaskKind::Body(body) = &mut (*raw).kind else {
+            panic!("ready task has no body. report this error to the developer.")
+        };
 
-            // calling native fn
-            let native = (*function).function;
-            native(self, addr.clone(), should_push, call_table)?;
+        // resuming body
+        self.scheduler.running.push(task);
+        let result = self.resume_generator(&(*raw).addr.clone(), body, table);
+        self.scheduler.running.pop();
+        result?;
+
+        // awaiting task, or completing
+        if let Some(awaited) = body.peeked.take() {
+            let awaited_task = task_of(awaited);
+            if (*awaited_task).is_done() {
+                self.scheduler.ready.push_back(task);
+            } else {
+                (*awaited_task).waiters.push(task);
+            }
+        } else if body.done {
+            let result = body.returned.take().unwrap_or(Value::Null);
+            self.complete_task(task, result);
+        }
 
-            Ok(())
-        } else {
+        Ok(())
+    }
+
+    /// Completes task with result,
+    /// it's waiters are ready to resume
+    unsafe fn complete_task(&mut self, task: Value, result: Value) {
+        let task = task_of(task);
+        (*task).result = Some(result);
+        self.scheduler
+            .ready
+            .extend(std::mem::take(&mut (*task).waiters));
+    }
+
+    /// Raises error, if named args are
+    /// passed to callable, that takes
+    /// args only by position
+    ///
+    fn expect_positional(addr: &Address, name: &str, named: &[String]) {
+        if !named.is_empty() {
             error!(Error::own_text(
                 addr.clone(),
-                format!("{name} is not a fn."),
-                "you can call only fn-s."
+                format!("{name} doesn't take named arguments."),
+                "pass arguments by position."
             ));
         }
     }
 
+    /// Fn name, used in profiles,
+    /// methods are prefixed with
+    /// type or unit name
+    ///
+    unsafe fn profile_name(function: *mut Function, owner: &Option<FnOwner>) -> String {
+        match owner {
+            Some(FnOwner::Unit(unit)) => {
+                format!("{}.{}", (**unit).name.name, (*function).name.name)
+            }
+            Some(FnOwner::Instance(instance)) => {
+                format!("{}.{}", (*(**instance).t).name.name, (*function).name.name)
+            }
+            Some(FnOwner::Type(t)) => {
+                format!("{}.{}", (**t).name.name, (*function).name.name)
+            }
+            None => (*function).name.name.clone(),
+        }
+    }
+
+    /// Opcode: Destructure
+    ///
+    /// takes values of list by position or
+    /// fields of instance, unit or variant by
+    /// name, then defines or sets variables
+    /// with them, `_` names are skipped
+    ///
+    unsafe fn op_destructure(
+        &mut self,
+        addr: &Address,
+        kind: DestructureKind,
+        names: &[String],
+        value: &Chunk,
+        define: bool,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // value
+        self.run(value, table)?;
+        let value = self.pop(addr);
+        gc_guard!(self.gc, value);
+
+        // collecting values
+        let values: Vec<Value> = match kind {
+            DestructureKind::List => {
+                let Some(list) = Self::list_of(value) else {
+                    error!(Error::own_text(
+                        addr.clone(),
+                        format!("couldn't destructure {value:?} as list."),
+                        "you can destructure only lists by position."
+                    ))
+                };
+                if (*list).len() != names.len() {
+                    error!(Error::own(
+                        addr.clone(),
+                        format!(
+                            "invalid values amount: {} to destructure into {} names.",
+                            (*list).len(),
+                            names.len()
+                        ),
+                        format!("expected {} values.", names.len())
+                    ));
+                }
+                (*list).clone()
+            }
+            DestructureKind::Fields => names
+                .iter()
+                .map(|name| {
+                    let field = match value.unbox() {
+                        Unboxed::Instance(instance) => {
+                            (*(*instance).fields).fields.get(name).copied()
+                        }
+                        Unboxed::Unit(unit) => (*(*unit).fields).fields.get(name).copied(),
+                        Unboxed::Variant(variant) => (*variant)
+                            .declaration()
+                            .fields
+                            .iter()
+                            .position(|field| field == name)
+                            .map(|index| (*variant).values[index]),
+                        _ => error!(Error::own_text(
+                            addr.clone(),
+                            format!("couldn't destructure fields of {value:?}."),
+                            "you can destructure fields of instance, unit or variant."
+                        )),
+                    };
+                    match field {
+                        Some(field) => field,
+                        None => error!(Error::own_text(
+                            addr.clone(),
+                            format!("{name} is not a field of {value:?}."),
+                            "check field existence."
+                        )),
+                    }
+                })
+                .collect(),
+        };
+
+        // defining or setting variables
+        for (name, value) in names.iter().zip(values) {
+            if name == "_" {
+                continue;
+            }
+            if define {
+                (*table).define(addr, name, value);
+                self.gc_barrier(table, value);
+            } else {
+                let written = (*table).set(addr.clone(), name, value);
+                self.gc_barrier(written, value);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Opcode: Call
     ///
     /// calls value by name
@@ -1565,6 +3976,11 @@ impl VM {
     /// safety if previous is tempo,
     /// guaranteed by guarding in gc
     ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
+    #[allow(clippy::too_many_arguments)]
     pub unsafe fn op_call(
         &mut self,
         addr: &Address,
@@ -1572,12 +3988,15 @@ impl VM {
         has_previous: bool,
         should_push: bool,
         args: &Chunk,
+        named: &[String],
+        cache: &InlineCache,
         table: *mut Table,
     ) -> Result<(), ControlFlow> {
         // non-previous
         if !has_previous {
             let value = (*table).lookup(addr, name);
-            self.call(addr, name, value, args, table, should_push)
+            let receiver = Self::self_receiver(addr, value, table);
+            self.call(addr, name, value, receiver, args, named, table, should_push)
         }
         // previous
         else {
@@ -1585,17 +4004,86 @@ impl VM {
             let previous = self.pop(addr);
             gc_guard!(self.gc, previous);
             // calling a function
-            match previous {
+            match previous.unbox() {
                 // call from instance
-                Value::Instance(instance) => {
-                    let value = (*(*instance).fields).find(addr, name);
-                    self.call(addr, name, value, args, table, should_push)
+                Unboxed::Instance(instance) => {
+                    let (value, _) = Self::resolve_member(addr, instance, name, cache);
+                    let receiver = Some(FnOwner::Instance(instance));
+                    self.call(addr, name, value, receiver, args, named, table, should_push)
                 }
                 // call from unit
-                Value::Unit(unit) => {
+                Unboxed::Unit(unit) => {
                     let value = (*(*unit).fields).find(addr, name);
-                    self.call(addr, name, value, args, table, should_push)
+                    self.call(addr, name, value, None, args, named, table, should_push)
+                }
+                // variant from enum
+                Unboxed::Enum(e) => {
+                    Self::expect_positional(addr, name, named);
+                    self.make_variant(addr, e, name, Some(args), table, should_push)
+                }
+                _ => {
+                    error!(Error::own_text(
+                        addr.clone(),
+                        format!("couldn't call {name} from {previous:?}."),
+                        "you can call fn from unit, instance or foreign."
+                    ))
                 }
+            }
+        }
+    }
+
+    /// Opcode: TailCall
+    ///
+    /// resolves fn like `op_call` does, then
+    /// loads args to stack and propagates
+    /// `ControlFlow::TailCall` to the `call_fn`
+    /// loop of the returning fn
+    ///
+    /// natives are just called in place
+    ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn op_tail_call(
+        &mut self,
+        addr: &Address,
+        name: &str,
+        has_previous: bool,
+        args: &Chunk,
+        named: &[String],
+        cache: &InlineCache,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // getting previous and guarding, previous
+        // is receiver, so it's guarded until args are loaded
+        let previous = if has_previous {
+            self.pop(addr)
+        } else {
+            Value::Null
+        };
+        gc_guard!(self.gc, previous);
+
+        // variant from enum
+        if let Unboxed::Enum(e) = previous.unbox() {
+            Self::expect_positional(addr, name, named);
+            return self.make_variant(addr, e, name, Some(args), table, true);
+        }
+
+        // resolving callable and receiver
+        let (callable, receiver) = if !has_previous {
+            let callable = (*table).lookup(addr, name);
+            (callable, Self::self_receiver(addr, callable, table))
+        } else {
+            match previous.unbox() {
+                // call from instance
+                Unboxed::Instance(instance) => (
+                    Self::resolve_member(addr, instance, name, cache).0,
+                    Some(FnOwner::Instance(instance)),
+                ),
+                // call from unit
+                Unboxed::Unit(unit) => ((*(*unit).fields).find(addr, name), None),
                 _ => {
                     error!(Error::own_text(
                         addr.clone(),
@@ -1604,6 +4092,27 @@ impl VM {
                     ))
                 }
             }
+        };
+
+        // checking value is fn
+        if let Unboxed::Fn(_) = callable.unbox() {
+            // loading arguments to stack
+            gc_guard!(self.gc, callable);
+            let prev_size = self.stack.len();
+            self.run(args, table)?;
+            let passed_amount = self.stack.len() - prev_size;
+
+            // propagating tail call
+            Err(ControlFlow::TailCall {
+                addr: addr.clone(),
+                name: name.to_string(),
+                callable,
+                receiver,
+                passed_amount,
+                named: named.into(),
+            })
+        } else {
+            self.call(addr, name, callable, receiver, args, named, table, true)
         }
     }
 
@@ -1618,15 +4127,23 @@ impl VM {
         Ok(())
     }
 
-    /// Checking instance impls all traits,
-    /// if instance doesn't impl some fn-s,
-    /// adds default implementation if exists,
+    /// Checking instance type impls all traits,
+    /// if type doesn't impl some fn-s, adds default
+    /// implementation to type methods if exists,
     /// otherwise raises error
     ///
+    /// traits are checked once per type, when
+    /// it's first instance is created
+    ///
     unsafe fn check_traits(&mut self, addr: &Address, instance: *mut Instance, table: *mut Table) {
         // type of instance, used to check traits
         let instance_type = (*instance).t;
 
+        // traits are already checked
+        if (*instance_type).traits.len() == (*instance_type).impls.len() {
+            return;
+        }
+
         /// Gets trait by name
         unsafe fn get_trait(
             traits: *mut Table,
@@ -1636,42 +4153,48 @@ impl VM {
             // looking up trait
             let trait_value = (*traits).find(addr, trait_name);
 
-            match trait_value {
-                Value::Trait(_trait) => Some(_trait),
+            match trait_value.unbox() {
+                Unboxed::Trait(_trait) => Some(_trait),
                 _ => {
                     panic!("not a trait in traits table. report to developer.")
                 }
             }
         }
 
-        /// Gets impl by name
-        unsafe fn get_impl(
-            table: *mut Table,
-            addr: &Address,
-            impl_name: &str,
-        ) -> Option<*mut Function> {
-            // looking up for impl
-            let fn_value = (*table).lookup(addr, impl_name);
+        // resolving traits of type, resolved
+        // traits are kept alive by type
+        (*instance_type).traits = (*instance_type)
+            .impls
+            .iter()
+            .map(|trait_name| get_trait(self.traits, addr, trait_name).unwrap())
+            .collect();
+        for _trait in (*instance_type).traits.clone() {
+            self.gc_barrier_value(Value::Type(instance_type), Value::Trait(_trait));
+        }
 
-            match fn_value {
-                Value::Fn(_fn) => Some(_fn),
-                _ => None,
+        // methods table of type
+        let methods = (*instance_type).methods;
+
+        // checking all traits from a type, with their
+        // parents, trait goes before it's parents,
+        // so it's defaults are taken first
+        let mut family: Vec<*mut Trait> = Vec::new();
+        for _trait in (*instance_type).traits.clone() {
+            for member in Trait::with_parents(_trait) {
+                if !family.contains(&member) {
+                    family.push(member);
+                }
             }
         }
-
-        // checking all traits from a type
-        for trait_name in &(*instance_type).impls {
-            let _trait = get_trait(self.traits, addr, trait_name).unwrap();
+        for _trait in family {
+            // name of trait, declaring fn-s
+            let trait_name = &(*_trait).name.name;
             // checking all fn-s
             for function in &(*_trait).functions {
                 // if impl exists, checking it
-                if (*(*instance).fields).exists(&function.name) {
-                    // checking impl
-                    let _impl = get_impl((*instance).fields, addr, &function.name);
-
-                    // if impl exists, checking params amount
-                    if _impl.is_some() {
-                        let implementation = _impl.unwrap();
+                if let Some(_impl) = Self::find_member(instance, &function.name) {
+                    // if impl is fn, checking params amount
+                    if let Unboxed::Fn(implementation) = _impl.unbox() {
                         if (*implementation).params.len() != function.params_amount {
                             error!(Error::own(
                                 addr.clone(),
@@ -1703,14 +4226,20 @@ impl VM {
                     }
                 } else {
                     // default implementation
-                    if function.default.is_some() {
+                    if let Some(default_impl) = &function.default {
                         // creating default fn
-                        let default_impl = function.default.as_ref().unwrap();
                         let default_fn = Value::Fn(memory::alloc_value(Function::new(
                             Symbol::by_name(function.name.clone()),
                             memory::alloc_value(default_impl.chunk.clone()),
-                            default_impl.params.clone(),
+                            default_impl
+                                .params
+                                .iter()
+                                .map(|param| Param::new(param.clone(), None, false))
+                                .collect(),
                         )));
+                        if let Unboxed::Fn(default_fn) = default_fn.unbox() {
+                            (*default_fn).owner = Some(FnOwner::Type(instance_type));
+                        }
 
                         // guarding in gc
                         gc_guard!(self.gc, default_fn);
@@ -1718,8 +4247,9 @@ impl VM {
                         // registering in gc
                         self.gc_register(default_fn, table);
 
-                        // defining fn in fields of instance
-                        (*(*instance).fields).define(addr, &function.name, default_fn);
+                        // defining fn in methods of type
+                        (*methods).define(addr, &function.name, default_fn);
+                        self.gc_barrier(methods, default_fn);
                     } else {
                         error!(Error::own(
                             addr.clone(),
@@ -1788,6 +4318,7 @@ impl VM {
                 for param in params.iter().rev() {
                     let operand = vm.pop(addr);
                     (*fields_table).define(addr, param, operand);
+                    vm.gc_barrier(fields_table, operand);
                 }
                 Ok(())
             } else {
@@ -1800,8 +4331,8 @@ impl VM {
         }
         // looking up a type
         let value = (*self.types).lookup(addr, name);
-        match value {
-            Value::Type(t) => {
+        match value.unbox() {
+            Unboxed::Type(t) => {
                 // creating instance
                 let instance =
                     memory::alloc_value(Instance::new(t, memory::alloc_value(Table::new())));
@@ -1825,8 +4356,9 @@ impl VM {
                     (*instance).fields,
                 )?;
 
-                // setting root
-                (*(*instance).fields).set_root(self.globals);
+                // setting root, methods of type are
+                // rooted to globals
+                (*(*instance).fields).set_root((*t).methods);
 
                 // setting temp parent
                 (*(*instance).fields).parent = table;
@@ -1845,14 +4377,28 @@ impl VM {
                 // checking traits implementation
                 self.check_traits(addr, instance, table);
 
-                // binding functions
+                // checking fields don't shadow methods
+                for field in (*(*instance).fields).fields.keys() {
+                    Self::check_shadowing(addr, instance, field);
+                }
+
+                // binding fn-s, stored in fields
                 self.bind_functions((*instance).fields, FnOwner::Instance(instance));
 
                 // calling optional init fn
                 let init_fn = "init";
-                if (*(*instance).fields).exists(init_fn) {
-                    self.push(instance_value);
-                    self.op_call(addr, init_fn, true, false, &Chunk::new(vec![]), table)?
+                if let Some(init) = Self::find_member(instance, init_fn) {
+                    let receiver = Some(FnOwner::Instance(instance));
+                    self.call(
+                        addr,
+                        init_fn,
+                        init,
+                        receiver,
+                        &Chunk::new(vec![]),
+                        &[],
+                        table,
+                        false,
+                    )?
                 }
 
                 // pushing instance
@@ -1943,9 +4489,9 @@ impl VM {
             table: *mut Table,
         ) -> Result<bool, ControlFlow> {
             // finding callable
-            let callable = (*(*instance).fields).find(addr, "is_ok");
-            match callable {
-                Value::Fn(function) => {
+            let callable = VM::member(addr, instance, "is_ok");
+            match callable.unbox() {
+                Unboxed::Fn(function) => {
                     if !(*function).params.is_empty() {
                         error!(Error::own_text(
                             addr.clone(),
@@ -1964,12 +4510,22 @@ impl VM {
             }
 
             // calling
-            vm.call(addr, "is_ok", callable, &Chunk::new(vec![]), table, true)?;
+            let receiver = Some(FnOwner::Instance(instance));
+            vm.call(
+                addr,
+                "is_ok",
+                callable,
+                receiver,
+                &Chunk::new(vec![]),
+                &[],
+                table,
+                true,
+            )?;
 
             // matching result
             let result = vm.pop(addr);
-            match result {
-                Value::Bool(boolean) => Ok(boolean),
+            match result.unbox() {
+                Unboxed::Bool(boolean) => Ok(boolean),
                 _ => {
                     error!(Error::own(
                         addr.clone(),
@@ -1989,10 +4545,10 @@ impl VM {
             instance: *mut Instance,
             table: *mut Table,
         ) -> Result<(), ControlFlow> {
-            let callable = (*(*instance).fields).find(addr, "unwrap");
+            let callable = VM::member(addr, instance, "unwrap");
 
-            match callable {
-                Value::Fn(function) => {
+            match callable.unbox() {
+                Unboxed::Fn(function) => {
                     if !(*function).params.is_empty() {
                         error!(Error::own_text(
                             addr.clone(),
@@ -2010,11 +4566,21 @@ impl VM {
                 }
             }
 
-            vm.call(addr, "unwrap", callable, &Chunk::new(vec![]), table, true)?;
+            let receiver = Some(FnOwner::Instance(instance));
+            vm.call(
+                addr,
+                "unwrap",
+                callable,
+                receiver,
+                &Chunk::new(vec![]),
+                &[],
+                table,
+                true,
+            )?;
             Ok(())
         }
 
-        if let Value::Instance(instance) = value {
+        if let Unboxed::Instance(instance) = value.unbox() {
             // calling is ok
             let is_ok = call_is_ok(self, addr, instance, table)?;
 
@@ -2039,6 +4605,39 @@ impl VM {
         Ok(())
     }
 
+    /// Opcode: Index
+    ///
+    /// indexes value by key, with `index`
+    /// fn of instance, that impls Index
+    ///
+    unsafe fn op_index(
+        &mut self,
+        addr: &Address,
+        should_push: bool,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // value and key
+        let value = self.pop(addr);
+        let key = self.pop(addr);
+
+        // calling index fn
+        match Self::impl_of(value, "Index") {
+            Some(instance) => {
+                let result = self.call_operator(addr, instance, "index", &[key], table)?;
+                if should_push {
+                    self.push(result);
+                }
+            }
+            None => error!(Error::own_text(
+                addr.clone(),
+                format!("could not index {value:?}."),
+                "indexed value should impl Index."
+            )),
+        }
+
+        Ok(())
+    }
+
     /// Opcode: Impls
     ///
     /// Checks value is impls a
@@ -2057,24 +4656,27 @@ impl VM {
 
         // if value returned instance, checking trait
         // is implemented
-        if let Value::Instance(instance) = value {
+        if let Unboxed::Instance(instance) = value.unbox() {
             // checking trait is implemented
             let trait_value = (*self.traits).lookup(addr, trait_name);
-            match trait_value {
-                Value::Trait(_trait) => {
-                    let impls = &(*(*instance).t).impls;
+            match trait_value.unbox() {
+                Unboxed::Trait(_trait) => {
+                    let instance_type = &*(*instance).t;
 
+                    // trait is implemented directly
+                    // or through child trait
                     let name = &(*_trait).name.name;
                     let full_name_option = &(*_trait).name.full_name;
 
                     match full_name_option {
                         Some(full_name) => {
                             self.push(Value::Bool(
-                                impls.contains(name) || impls.contains(full_name),
+                                instance_type.impls_named(name)
+                                    || instance_type.impls_named(full_name),
                             ));
                         }
                         _ => {
-                            self.push(Value::Bool(impls.contains(name)));
+                            self.push(Value::Bool(instance_type.impls_named(name)));
                         }
                     }
                 }
@@ -2104,10 +4706,133 @@ impl VM {
         (*table).fields.remove(name);
     }
 
+    /// Opcode: With
+    ///
+    /// Defines instance of type, that impls
+    /// `Drop` in local table, runs body and then
+    /// calls `drop`, even if body is left by
+    /// `return`, `break` or `continue`
+    ///
+    unsafe fn op_with(
+        &mut self,
+        addr: &Address,
+        name: &str,
+        value: &Chunk,
+        body: &Chunk,
+        table: *mut Table,
+    ) -> Result<(), ControlFlow> {
+        // running value
+        self.run(value, table)?;
+        let value = self.pop(addr);
+
+        // checking value impls drop
+        let instance = match value.unbox() {
+            Unboxed::Instance(instance) if GC::is_finalizable(instance) => instance,
+            _ => error!(Error::own_text(
+                addr.clone(),
+                format!("could not use `with` statement with {value:?}."),
+                "with requires instance of type that impls Drop."
+            )),
+        };
+
+        // defining variable
+        (*table).define(addr, name, value);
+        self.gc_barrier(table, value);
+        gc_guard!(self.gc, value);
+
+        // running body
+        let result = self.run(body, table);
+
+        // guarding returned value
+        // during drop call
+        let returned = match &result {
+            Err(ControlFlow::Return(value)) => *value,
+            _ => Value::Null,
+        };
+        gc_guard!(self.gc, returned);
+
+        // dropping, if it's not dropped yet
+        if (*self.gc).set_finalized(value) {
+            self.call_drop(addr, instance, table)?;
+        }
+        (*table).fields.remove(name);
+
+        result
+    }
+
+    /// Runs `f` on next host stack segment
+    unsafe fn grow_stack<R>(&mut self, addr: &Address, f: impl FnOnce(&mut VM) -> R) -> R {
+        let segment = match self.host_stack.take() {
+            Ok(segment) => segment,
+            Err(err) => error!(Error::limit(
+                addr.clone(),
+                "stack overflow.".to_string(),
+                format!("failed to allocate stack segment: {err}.")
+            )),
+        };
+
+        // running on segment
+        let previous = self.host_stack.enter(&segment);
+        let vm = self as *mut VM;
+        let result = stack::on_segment(&segment, || f(&mut *vm));
+        self.host_stack.leave(segment, previous);
+
+        match result {
+            Ok(result) => result,
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+
     /// Running chunk
-    #[allow(unused_variables)]
+    ///
+    /// # Safety
+    ///
+    /// `table` should be an alive table of vm
+    ///
     pub unsafe fn run(&mut self, chunk: &Chunk, table: *mut Table) -> Result<(), ControlFlow> {
+        // continuing on next segment, if host stack is nearly exhausted
+        if self.host_stack.exhausted() {
+            let addr = match chunk.opcodes().first() {
+                Some(op) => op.address().clone(),
+                None => return Ok(()),
+            };
+            return self.grow_stack(&addr, |vm| vm.run(chunk, table));
+        }
+        // running ops, address of
+        // outer op is restored after
+        let op_addr = self.op_addr;
+        let result = self.run_ops(chunk, table);
+        self.op_addr = op_addr;
+        result
+    }
+
+    /// Running chunk ops
+    #[allow(unused_variables)]
+    unsafe fn run_ops(&mut self, chunk: &Chunk, table: *mut Table) -> Result<(), ControlFlow> {
         for op in chunk.opcodes() {
+            self.op_addr = op.address();
+            // limits
+            self.instructions += 1;
+            if let Some(max) = self.settings.limits.max_instructions
+                && self.instructions > max
+            {
+                Self::limit_exceeded(op.address(), "instructions", max, "max-instructions");
+            }
+            if self.instructions.is_multiple_of(TIMEOUT_CHECK_INTERVAL) {
+                self.check_timeout(op.address());
+            }
+            // coverage
+            if let Some(coverage) = &mut self.coverage {
+                coverage.hit(op.address());
+            }
+            // debugger
+            if self.debugger.is_some() {
+                self.debug_hook(op.address(), table);
+            }
+            // finalizers
+            if (*self.gc).has_finalizers() {
+                self.run_finalizers(op.address(), table)?;
+            }
             match op {
                 Opcode::Push { addr, value } => {
                     self.op_push(value.clone(), table)?;
@@ -2119,13 +4844,13 @@ impl VM {
                     self.op_binary(addr, op, table)?;
                 }
                 Opcode::Neg { addr } => {
-                    self.op_negate(addr)?;
+                    self.op_negate(addr, table)?;
                 }
                 Opcode::Bang { addr } => {
                     self.op_bang(addr)?;
                 }
                 Opcode::Cond { addr, op } => {
-                    self.op_conditional(addr, op)?;
+                    self.op_conditional(addr, op, table)?;
                 }
                 Opcode::Logic { addr, a, b, op } => self.op_logical(addr, a, b, op, table)?,
                 Opcode::If {
@@ -2136,6 +4861,14 @@ impl VM {
                 } => {
                     self.op_if(addr, cond, body, elif, table)?;
                 }
+                Opcode::Match {
+                    addr,
+                    matchable,
+                    arms,
+                    default,
+                } => {
+                    self.op_match(addr, matchable, arms, default, table)?;
+                }
                 Opcode::Loop { addr, body } => {
                     self.op_loop(addr, body, table)?;
                 }
@@ -2146,6 +4879,7 @@ impl VM {
                     body,
                     params,
                     make_closure,
+                    kind,
                 } => {
                     self.op_define_fn(
                         addr,
@@ -2153,6 +4887,7 @@ impl VM {
                         body,
                         params,
                         *make_closure,
+                        *kind,
                         table,
                     )?;
                 }
@@ -2161,22 +4896,26 @@ impl VM {
                     body,
                     params,
                     make_closure,
+                    kind,
                 } => {
-                    self.op_anonymous_fn(body, params, *make_closure, table)?;
+                    self.op_anonymous_fn(body, params, *make_closure, *kind, table)?;
                 }
                 Opcode::DefineType {
                     addr,
                     name,
                     full_name,
                     body,
+                    methods,
                     constructor,
                     impls,
                 } => self.op_define_type(
                     addr,
                     &Symbol::new_option(name.clone(), full_name.clone()),
                     body,
+                    methods,
                     constructor,
                     impls,
+                    table,
                 )?,
                 Opcode::DefineUnit {
                     addr,
@@ -2193,11 +4932,25 @@ impl VM {
                     addr,
                     name,
                     full_name,
+                    parents,
                     functions,
                 } => self.op_define_trait(
                     addr,
                     &Symbol::new_option(name.clone(), full_name.clone()),
+                    parents,
                     functions,
+                    table,
+                )?,
+                Opcode::DefineEnum {
+                    addr,
+                    name,
+                    full_name,
+                    variants,
+                } => self.op_define_enum(
+                    addr,
+                    &Symbol::new_option(name.clone(), full_name.clone()),
+                    variants,
+                    table,
                 )?,
                 Opcode::Define {
                     addr,
@@ -2220,8 +4973,9 @@ impl VM {
                     name,
                     has_previous,
                     should_push,
+                    cache,
                 } => {
-                    self.op_load(addr, name, *has_previous, *should_push, table)?;
+                    self.op_load(addr, name, *has_previous, *should_push, cache, table)?;
                 }
                 Opcode::Call {
                     addr,
@@ -2229,7 +4983,26 @@ impl VM {
                     has_previous,
                     should_push,
                     args,
-                } => self.op_call(addr, name, *has_previous, *should_push, args, table)?,
+                    named,
+                    cache,
+                } => self.op_call(
+                    addr,
+                    name,
+                    *has_previous,
+                    *should_push,
+                    args,
+                    named,
+                    cache,
+                    table,
+                )?,
+                Opcode::TailCall {
+                    addr,
+                    name,
+                    args,
+                    named,
+                    has_previous,
+                    cache,
+                } => self.op_tail_call(addr, name, *has_previous, args, named, cache, table)?,
                 Opcode::Duplicate { addr } => {
                     self.op_duplicate(addr)?;
                 }
@@ -2250,6 +5023,16 @@ impl VM {
                 Opcode::Ret { addr, value } => {
                     self.op_return(addr, value, table)?;
                 }
+                Opcode::Yield { addr, value } => {
+                    self.op_yield(addr, value, table)?;
+                }
+                Opcode::Await {
+                    addr,
+                    value,
+                    should_push,
+                } => {
+                    self.op_await(addr, value, *should_push, table)?;
+                }
                 Opcode::Native { addr, fn_name } => {
                     self.op_native(addr, fn_name)?;
                 }
@@ -2260,6 +5043,9 @@ impl VM {
                 } => {
                     self.op_error_propagation(addr, value, table, *should_push)?;
                 }
+                Opcode::Index { addr, should_push } => {
+                    self.op_index(addr, *should_push, table)?;
+                }
                 Opcode::Impls {
                     addr,
                     value,
@@ -2268,6 +5054,19 @@ impl VM {
                     self.op_impls(addr, value, trait_name, table)?;
                 }
                 Opcode::DeleteLocal { addr, name } => self.op_delete_local(addr, name, table),
+                Opcode::Destructure {
+                    addr,
+                    kind,
+                    names,
+                    value,
+                    define,
+                } => self.op_destructure(addr, *kind, names, value, *define, table)?,
+                Opcode::With {
+                    addr,
+                    name,
+                    value,
+                    body,
+                } => self.op_with(addr, name, value, body, table)?,
             }
         }
         Ok(())
@@ -2278,7 +5077,31 @@ impl VM {
 unsafe impl Send for VM {}
 unsafe impl Sync for VM {}
 
+/// Big integer of int or big integer value
+pub(crate) unsafe fn bigint_of(value: Value) -> Option<BigInt> {
+    match value.unbox() {
+        Unboxed::Int(i) => Some(BigInt::from(i)),
+        Unboxed::BigInt(i) => Some((*i).clone()),
+        _ => None,
+    }
+}
+
+/// Float of numeric value
+pub(crate) unsafe fn float_of(value: Value) -> Option<f64> {
+    match value.unbox() {
+        Unboxed::Float(f) => Some(f),
+        Unboxed::Int(i) => Some(i as f64),
+        Unboxed::BigInt(i) => (*i).to_f64(),
+        _ => None,
+    }
+}
+
 /// Frees table, if table captures equals 0.
+///
+/// # Safety
+///
+/// `table` is not used after it's freed
+///
 pub unsafe fn try_free_table(table: *mut Table) {
     if !table.is_null() && (*table).captures == 0 {
         memory::free_value(table)